@@ -0,0 +1,484 @@
+//! Canonical labeling and hashing of undirected graphs.
+//!
+//! Isomorphic graphs get the same canonical form and the same hash, no matter the order their
+//! vertices were added in. Both only depend on the graph, so they are the same across runs and can
+//! be stored.
+
+use std::hash::{Hash, Hasher};
+
+use super::util::Indexed;
+use super::{Graph, UnionFind};
+
+/// A graph relabeled into canonical form, as returned by [`canonical_form`].
+#[derive(Clone, Debug)]
+pub struct CanonicalForm<G> {
+	/// The graph with its vertices added in canonical order, carrying their original data.
+	pub graph: G,
+	/// The ID of the original vertex at each position of the canonical order. The `i`-th vertex
+	/// added to `graph` is the vertex `permutation[i]` of the original graph.
+	pub permutation: Vec<usize>,
+	/// The edges of the canonical graph as sorted pairs of positions in the canonical order, in
+	/// increasing order. Two graphs with the same number of vertices are isomorphic exactly when
+	/// their canonical edges are equal, so these can be used as keys in a hash set.
+	pub edges: Vec<(usize, usize)>,
+}
+
+/// Refines a coloring of the vertices until it is equitable: until any two vertices of the same
+/// color have the same number of neighbors of each color. The new colors are numbered from zero,
+/// in an order that only depends on the old colors and the structure of the graph.
+fn refine(adj: &[Vec<usize>], mut colors: Vec<usize>) -> Vec<usize> {
+	let mut count = usize::MAX;
+	loop {
+		// Color the vertices by their old color and the colors of their neighbors
+		let signatures: Vec<(usize, Vec<usize>)> = adj.iter().enumerate()
+			.map(|(u, neighbors)| {
+				let mut neighbor_colors: Vec<usize> = neighbors.iter().map(|&v| colors[v]).collect();
+				neighbor_colors.sort_unstable();
+				(colors[u], neighbor_colors)
+			})
+			.collect();
+		let mut distinct: Vec<&(usize, Vec<usize>)> = signatures.iter().collect();
+		distinct.sort_unstable();
+		distinct.dedup();
+
+		colors = signatures.iter().map(|s| distinct.binary_search(&s).unwrap()).collect();
+		if distinct.len() == count {
+			return colors;
+		}
+		count = distinct.len();
+	}
+}
+
+/// Gives a vertex a color of its own, just before the other vertices of its color.
+fn individualize(colors: &[usize], v: usize) -> Vec<usize> {
+	colors.iter().enumerate().map(|(u, &c)| 2 * c + (u != v) as usize).collect()
+}
+
+/// Lists the edges of a graph under a labeling of its vertices, in increasing order.
+fn relabeled_edges(adj: &[Vec<usize>], labels: &[usize]) -> Vec<(usize, usize)> {
+	let mut edges: Vec<(usize, usize)> = adj.iter().enumerate()
+		.flat_map(|(u, neighbors)| neighbors.iter().map(move |&v| (labels[u], labels[v])))
+		.filter(|&(a, b)| a <= b)
+		.collect();
+	edges.sort_unstable();
+	edges
+}
+
+/// A leaf of the search tree: a labeling of the vertices and the edges under it.
+#[derive(Clone, Debug)]
+struct Leaf {
+	labels: Vec<usize>,
+	edges: Vec<(usize, usize)>,
+}
+
+/// The state of an individualization–refinement search for the canonical labeling of a graph.
+#[derive(Debug)]
+struct Canonizer<'a> {
+	adj: &'a [Vec<usize>],
+	first: Option<(Vec<usize>, Leaf)>, // The first leaf found, and the path to it
+	best: Option<Leaf>, // The leaf with the smallest edges found so far
+	automorphisms: Vec<Vec<usize>>,
+}
+
+impl Canonizer<'_> {
+	/// The automorphism that maps the vertices of one leaf to those with the same labels in another.
+	fn automorphism(from: &Leaf, to: &Leaf) -> Vec<usize> {
+		let mut vertex = vec![0; to.labels.len()];
+		for (u, &label) in to.labels.iter().enumerate() {
+			vertex[label] = u;
+		}
+		from.labels.iter().map(|&label| vertex[label]).collect()
+	}
+
+	/// Searches the subtree below a node, given the coloring at the node and the vertices
+	/// individualized on the path to it.
+	///
+	/// Returns the depth to return to when the search found a leaf equivalent to the first leaf:
+	/// an automorphism then maps the subtree the two leaves part ways in onto one that has been
+	/// searched already.
+	fn search(&mut self, colors: Vec<usize>, path: &mut Vec<usize>) -> Option<usize> {
+		let colors = refine(self.adj, colors);
+		let n = colors.len();
+
+		// Branch on the first color with more than one vertex
+		let mut sizes = vec![0; n];
+		for &c in &colors {
+			sizes[c] += 1;
+		}
+		let Some(target) = (0..n).find(|&c| sizes[c] > 1) else {
+			return self.leaf(colors, path);
+		};
+		let cell: Vec<usize> = (0..n).filter(|&u| colors[u] == target).collect();
+
+		let mut tried: Vec<usize> = Vec::new();
+		for v in cell {
+			// Skip vertices that an automorphism fixing the path maps onto a tried vertex
+			if !tried.is_empty() {
+				let mut orbits = UnionFind::new(n);
+				for gamma in &self.automorphisms {
+					if path.iter().all(|&u| gamma[u] == u) {
+						for (u, &image) in gamma.iter().enumerate() {
+							orbits.union(u, image);
+						}
+					}
+				}
+				if tried.iter().any(|&t| orbits.connected(t, v)) {
+					continue;
+				}
+			}
+
+			path.push(v);
+			let jump = self.search(individualize(&colors, v), path);
+			path.pop();
+			tried.push(v);
+
+			if let Some(depth) = jump.filter(|&depth| depth < path.len()) {
+				return Some(depth);
+			}
+		}
+
+		None
+	}
+
+	/// Records a leaf of the search tree.
+	fn leaf(&mut self, labels: Vec<usize>, path: &[usize]) -> Option<usize> {
+		let leaf = Leaf { edges: relabeled_edges(self.adj, &labels), labels };
+
+		let Some((first_path, first)) = &self.first else {
+			self.first = Some((path.to_vec(), leaf.clone()));
+			self.best = Some(leaf);
+			return None;
+		};
+
+		if leaf.edges == first.edges {
+			self.automorphisms.push(Self::automorphism(first, &leaf));
+			let common = first_path.iter().zip(path).take_while(|(a, b)| a == b).count();
+			return Some(common);
+		}
+
+		let best = self.best.as_ref().unwrap();
+		if leaf.edges == best.edges {
+			self.automorphisms.push(Self::automorphism(best, &leaf));
+		} else if leaf.edges < best.edges {
+			self.best = Some(leaf);
+		}
+		None
+	}
+}
+
+/// Computes the canonical form of a graph: a relabeling of its vertices that is the same for all
+/// graphs isomorphic to it.
+///
+/// The labeling is found with an individualization–refinement search in the style of nauty: the
+/// vertices are colored by repeatedly refining their colors by the colors of their neighbors, and
+/// vertices that share a color are given colors of their own one at a time, until every vertex has
+/// its own color. Of the labelings found this way, the one with the smallest list of edges is
+/// canonical. Automorphisms found along the way prune the search, so it is fast even on graphs
+/// with many symmetries, though it takes exponential time in the worst case.
+///
+/// Only the structure of the graph is taken into account: not the vertex data and not the edge
+/// weights. The canonical graph carries the data of the vertices, but its edges have the default
+/// weight.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to compute the canonical form of.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::canonical::canonical_form;
+///
+/// // A path of three vertices, built in two different orders
+/// let mut graph_1: UndirectedGraph<char> = UndirectedGraph::new();
+/// let a = graph_1.add_vertex('a');
+/// let b = graph_1.add_vertex('b');
+/// let c = graph_1.add_vertex('c');
+/// graph_1.add_edge(a, b);
+/// graph_1.add_edge(b, c);
+///
+/// let mut graph_2: UndirectedGraph<char> = UndirectedGraph::new();
+/// let x = graph_2.add_vertex('x');
+/// let y = graph_2.add_vertex('y');
+/// let z = graph_2.add_vertex('z');
+/// graph_2.add_edge(x, z);
+/// graph_2.add_edge(z, y);
+///
+/// let form_1 = canonical_form(&graph_1);
+/// let form_2 = canonical_form(&graph_2);
+///
+/// assert_eq!(form_1.edges, form_2.edges);
+///
+/// // The middle vertices end up in the same place
+/// let middle = form_1.permutation.iter().position(|&u| u == b).unwrap();
+/// assert_eq!(form_2.permutation[middle], z);
+/// ```
+pub fn canonical_form<T, G>(graph: &G) -> CanonicalForm<G>
+where
+	T: Clone,
+	G: Graph<T> + Default,
+{
+	let index = Indexed::new(graph);
+	let n = index.len();
+
+	let mut canonizer = Canonizer { adj: &index.adj, first: None, best: None, automorphisms: Vec::new() };
+	canonizer.search(vec![0; n], &mut Vec::new());
+	let labels = canonizer.best.map_or_else(Vec::new, |leaf| leaf.labels);
+
+	let mut permutation = vec![0; n];
+	for (u, &label) in labels.iter().enumerate() {
+		permutation[label] = index.ids[u];
+	}
+	let edges = relabeled_edges(&index.adj, &labels);
+
+	let mut canonical = G::default();
+	let new_ids: Vec<usize> = permutation.iter()
+		.map(|&id| canonical.add_vertex(graph.get_vertex_data(id).expect("vertices have data")))
+		.collect();
+	for &(a, b) in &edges {
+		canonical.add_edge(new_ids[a], new_ids[b]);
+	}
+
+	CanonicalForm { graph: canonical, permutation, edges }
+}
+
+/// A hasher whose results don't depend on the run or the platform: FNV-1a over the little-endian
+/// bytes of integers.
+#[derive(Debug)]
+struct StableHasher(u64);
+
+impl StableHasher {
+	fn new() -> Self {
+		Self(0xcbf2_9ce4_8422_2325)
+	}
+}
+
+impl Hasher for StableHasher {
+	fn finish(&self) -> u64 {
+		self.0
+	}
+
+	fn write(&mut self, bytes: &[u8]) {
+		for &byte in bytes {
+			self.0 ^= byte as u64;
+			self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+		}
+	}
+
+	fn write_u16(&mut self, i: u16) {
+		self.write(&i.to_le_bytes());
+	}
+
+	fn write_u32(&mut self, i: u32) {
+		self.write(&i.to_le_bytes());
+	}
+
+	fn write_u64(&mut self, i: u64) {
+		self.write(&i.to_le_bytes());
+	}
+
+	fn write_u128(&mut self, i: u128) {
+		self.write(&i.to_le_bytes());
+	}
+
+	fn write_usize(&mut self, i: usize) {
+		self.write_u64(i as u64);
+	}
+}
+
+/// Hashes a value with a [`StableHasher`].
+fn stable_hash<V: Hash + ?Sized>(value: &V) -> u64 {
+	let mut hasher = StableHasher::new();
+	value.hash(&mut hasher);
+	hasher.finish()
+}
+
+/// Computes the Weisfeiler–Lehman hash of a graph.
+///
+/// Every vertex starts with a hash of its label, and in each iteration its hash is replaced with a
+/// hash of its own hash and the sorted hashes of its neighbors. The hash of the graph is a hash of
+/// the sorted hashes of all vertices in all iterations. Runs in `O(k (V + E) log V)` time for `k`
+/// iterations.
+///
+/// Isomorphic graphs with the same labels have the same hash, but some graphs that are not
+/// isomorphic do too: the hash never tells apart two regular graphs with the same number of
+/// vertices and the same degree, for example. Use [`canonical_form`] when that matters.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to hash.
+/// - `iterations`: The number of times to update the hashes of the vertices. Each iteration takes
+///   vertices one step further away into account.
+/// - `node_label`: A function that takes the data of a vertex and returns its label. Use `|_| ()`
+///   to hash the structure of the graph alone.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedSparseGraph};
+/// use istos::canonical::weisfeiler_lehman_hash;
+///
+/// let molecule = |atoms: [&'static str; 3]| {
+///     let mut graph: UndirectedSparseGraph<&str> = UndirectedSparseGraph::new();
+///     let v: Vec<usize> = atoms.iter().map(|&atom| graph.add_vertex(atom)).collect();
+///     graph.add_edge(v[0], v[1]);
+///     graph.add_edge(v[1], v[2]);
+///     graph
+/// };
+///
+/// let ethanol = weisfeiler_lehman_hash(&molecule(["C", "C", "O"]), 3, |&atom| atom);
+/// let reversed = weisfeiler_lehman_hash(&molecule(["O", "C", "C"]), 3, |&atom| atom);
+/// let ether = weisfeiler_lehman_hash(&molecule(["C", "O", "C"]), 3, |&atom| atom);
+///
+/// assert_eq!(ethanol, reversed);
+/// assert_ne!(ethanol, ether);
+/// ```
+pub fn weisfeiler_lehman_hash<T, G, L, F>(graph: &G, iterations: usize, mut node_label: F) -> u64
+where
+	T: Clone,
+	G: Graph<T>,
+	L: Hash,
+	F: FnMut(&T) -> L,
+{
+	let index = Indexed::new(graph);
+	let mut hashes: Vec<u64> = index.ids.iter()
+		.map(|&id| stable_hash(&node_label(&graph.get_vertex_data(id).expect("vertices have data"))))
+		.collect();
+	let mut all_hashes = hashes.clone();
+
+	for _ in 0..iterations {
+		hashes = index.adj.iter().enumerate()
+			.map(|(u, neighbors)| {
+				let mut neighbor_hashes: Vec<u64> = neighbors.iter().map(|&v| hashes[v]).collect();
+				neighbor_hashes.sort_unstable();
+				stable_hash(&(hashes[u], neighbor_hashes))
+			})
+			.collect();
+		all_hashes.extend(&hashes);
+	}
+
+	all_hashes.sort_unstable();
+	stable_hash(&all_hashes)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use super::*;
+	use crate::isomorphism::is_isomorphic;
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+	use crate::util::{random_graph, Rng};
+
+	/// Copies a graph with its vertices added in a shuffled order.
+	fn shuffled<G: Graph<usize>>(graph: &G, seed: u64) -> UndirectedGraph<usize> {
+		let mut vertices = graph.get_vertices();
+		Rng::new(seed).shuffle(&mut vertices);
+
+		let mut copy = UndirectedGraph::new();
+		let new_ids: HashMap<usize, usize> = vertices.iter()
+			.map(|&u| (u, copy.add_vertex(graph.get_vertex_data(u).unwrap())))
+			.collect();
+		for &u in &vertices {
+			for v in graph.get_neighbors(u) {
+				if !copy.is_adjacent(new_ids[&u], new_ids[&v]) {
+					copy.add_edge(new_ids[&u], new_ids[&v]);
+				}
+			}
+		}
+		copy
+	}
+
+	/// Builds some graphs with many automorphisms: an empty graph, a complete graph, a cycle, the
+	/// Petersen graph and a 4-dimensional cube.
+	fn symmetric_graphs() -> Vec<UndirectedSparseGraph<usize>> {
+		let build = |n: usize, adjacent: &dyn Fn(usize, usize) -> bool| {
+			let mut graph = UndirectedSparseGraph::new();
+			let v: Vec<usize> = (0..n).map(|i| graph.add_vertex(i)).collect();
+			for a in 0..n {
+				for b in a + 1..n {
+					if adjacent(a, b) {
+						graph.add_edge(v[a], v[b]);
+					}
+				}
+			}
+			graph
+		};
+
+		vec![
+			build(10, &|_, _| false),
+			build(8, &|_, _| true),
+			build(12, &|a, b| b - a == 1 || b - a == 11),
+			build(10, &|a, b| {
+				(b < 5 && (b - a == 1 || b - a == 4))
+					|| (a < 5 && b == a + 5)
+					|| (a >= 5 && ((b - a) % 5 == 2 || (b - a) % 5 == 3))
+			}),
+			build(16, &|a, b| (a ^ b).is_power_of_two()),
+		]
+	}
+
+	#[test]
+	fn test_canonical_form() {
+		let mut graphs = symmetric_graphs();
+		graphs.extend((1..20).map(|seed| random_graph(25, 25, seed)));
+
+		for (i, graph) in graphs.iter().enumerate() {
+			let form = canonical_form(graph);
+			assert!(is_isomorphic(graph, &form.graph));
+			assert_eq!(form.graph.get_vertices().len(), form.permutation.len());
+
+			// The canonical graph carries the data of the vertices in their new places
+			for (position, &u) in form.permutation.iter().enumerate() {
+				assert_eq!(form.graph.get_vertex_data(form.graph.get_vertices()[position]), graph.get_vertex_data(u));
+			}
+
+			for seed in 1..4 {
+				let copy = canonical_form(&shuffled(graph, seed + i as u64));
+				assert_eq!(copy.edges, form.edges);
+			}
+		}
+	}
+
+	#[test]
+	fn test_canonical_form_distinguishes() {
+		for seed in 1..100 {
+			let graph_1 = random_graph(6, 50, seed);
+			let graph_2 = random_graph(6, 50, seed + 1000);
+			assert_eq!(
+				canonical_form(&graph_1).edges == canonical_form(&graph_2).edges,
+				is_isomorphic(&graph_1, &graph_2),
+			);
+		}
+
+		let graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let form = canonical_form(&graph);
+		assert!(form.permutation.is_empty() && form.edges.is_empty());
+	}
+
+	#[test]
+	fn test_weisfeiler_lehman_hash() {
+		for seed in 1..10 {
+			let graph = random_graph(20, 20, seed);
+			let hash = weisfeiler_lehman_hash(&graph, 3, |_| ());
+			assert_eq!(weisfeiler_lehman_hash(&shuffled(&graph, seed), 3, |_| ()), hash);
+			assert_ne!(weisfeiler_lehman_hash(&graph, 3, |&i| i), hash);
+		}
+
+		// Two triangles and a hexagon are both 2-regular on six vertices
+		let mut triangles: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let mut hexagon: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let t: Vec<usize> = (0..6).map(|_| triangles.add_vertex(())).collect();
+		let h: Vec<usize> = (0..6).map(|_| hexagon.add_vertex(())).collect();
+		for i in 0..6 {
+			triangles.add_edge(t[i], t[i / 3 * 3 + (i + 1) % 3]);
+			hexagon.add_edge(h[i], h[(i + 1) % 6]);
+		}
+		assert_eq!(weisfeiler_lehman_hash(&triangles, 5, |_| ()), weisfeiler_lehman_hash(&hexagon, 5, |_| ()));
+		assert_ne!(canonical_form(&triangles).edges, canonical_form(&hexagon).edges);
+
+		// The hash doesn't change between runs
+		assert_eq!(weisfeiler_lehman_hash(&hexagon, 2, |_| ()), 13977910535527919487);
+	}
+}
@@ -0,0 +1,777 @@
+//! Centrality measures: betweenness, closeness, harmonic, eigenvector and Katz centrality.
+//!
+//! The distance based measures come in an unweighted version, which counts the edges on the
+//! shortest paths, and a weighted version, which adds up the edge weights of a [`WeightedGraph`].
+//! Edge weights must be positive. Eigenvector and Katz centrality come in a weighted version as
+//! well, where an edge weight is instead the strength of the tie between its ends, so heavier edges
+//! pass on more centrality.
+
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use super::util::{dijkstra, Indexed, MinScored};
+use super::{Graph, Weight, WeightedGraph};
+
+/// The shortest paths from a source vertex, as needed by Brandes' algorithm.
+struct ShortestPaths {
+	/// The vertices reachable from the source, in order of their distance.
+	order: Vec<usize>,
+	/// The vertices right before each vertex on its shortest paths.
+	predecessors: Vec<Vec<usize>>,
+	/// The number of shortest paths to each vertex.
+	count: Vec<f64>,
+}
+
+/// Finds the shortest paths from a vertex by breadth-first search.
+fn bfs_paths(index: &Indexed, source: usize) -> ShortestPaths {
+	let n = index.len();
+	let mut dist: Vec<Option<usize>> = vec![None; n];
+	let mut paths = ShortestPaths { order: Vec::new(), predecessors: vec![Vec::new(); n], count: vec![0.0; n] };
+	let mut queue = VecDeque::from([source]);
+	dist[source] = Some(0);
+	paths.count[source] = 1.0;
+
+	while let Some(u) = queue.pop_front() {
+		paths.order.push(u);
+		let d = dist[u].unwrap() + 1;
+		for &v in &index.adj[u] {
+			if dist[v].is_none() {
+				dist[v] = Some(d);
+				queue.push_back(v);
+			}
+			if dist[v] == Some(d) {
+				paths.count[v] += paths.count[u];
+				paths.predecessors[v].push(u);
+			}
+		}
+	}
+
+	paths
+}
+
+/// Finds the shortest paths from a vertex with Dijkstra's algorithm.
+fn dijkstra_paths<W: Weight>(index: &Indexed, weights: &[Vec<W>], source: usize) -> ShortestPaths {
+	let n = index.len();
+	let mut dist: Vec<Option<W>> = vec![None; n];
+	let mut settled = vec![false; n];
+	let mut paths = ShortestPaths { order: Vec::new(), predecessors: vec![Vec::new(); n], count: vec![0.0; n] };
+	let mut heap = BinaryHeap::from([MinScored(W::zero(), source)]);
+	dist[source] = Some(W::zero());
+	paths.count[source] = 1.0;
+
+	while let Some(MinScored(d, u)) = heap.pop() {
+		if std::mem::replace(&mut settled[u], true) {
+			continue;
+		}
+		paths.order.push(u);
+
+		for (&v, &w) in index.adj[u].iter().zip(&weights[u]) {
+			let candidate = d + w;
+			if settled[v] {
+				continue;
+			}
+			match dist[v] {
+				Some(dv) if dv < candidate => {}
+				Some(dv) if dv == candidate => {
+					paths.count[v] += paths.count[u];
+					paths.predecessors[v].push(u);
+				}
+				_ => {
+					dist[v] = Some(candidate);
+					paths.count[v] = paths.count[u];
+					paths.predecessors[v] = vec![u];
+					heap.push(MinScored(candidate, v));
+				}
+			}
+		}
+	}
+
+	paths
+}
+
+/// The betweenness of the vertices and of the edges of a graph, by vertex index.
+struct Betweenness {
+	vertices: Vec<f64>,
+	/// The betweenness of the edges, keyed by the indices of their endpoints in increasing order.
+	edges: HashMap<(usize, usize), f64>,
+}
+
+/// Computes the betweenness of every vertex and edge with Brandes' algorithm, adding up how much
+/// each vertex and edge depends on the shortest paths from every source.
+fn brandes<F: FnMut(usize) -> ShortestPaths>(index: &Indexed, mut shortest_paths: F) -> Betweenness {
+	let n = index.len();
+	let mut betweenness = Betweenness { vertices: vec![0.0; n], edges: HashMap::new() };
+	for (u, neighbors) in index.adj.iter().enumerate() {
+		for &v in neighbors.iter().filter(|&&v| u < v) {
+			betweenness.edges.insert((u, v), 0.0);
+		}
+	}
+
+	let mut dependency = vec![0.0; n];
+	for source in 0..n {
+		let paths = shortest_paths(source);
+		for &w in paths.order.iter().rev() {
+			for &v in &paths.predecessors[w] {
+				let share = paths.count[v] / paths.count[w] * (1.0 + dependency[w]);
+				*betweenness.edges.get_mut(&(v.min(w), v.max(w))).unwrap() += share;
+				dependency[v] += share;
+			}
+			if w != source {
+				betweenness.vertices[w] += dependency[w];
+			}
+		}
+		paths.order.iter().for_each(|&w| dependency[w] = 0.0);
+	}
+
+	// Every path was counted from both of its ends
+	betweenness.vertices.iter_mut().for_each(|b| *b /= 2.0);
+	betweenness.edges.values_mut().for_each(|b| *b /= 2.0);
+	betweenness
+}
+
+/// Collects the vertex betweenness by vertex ID, scaled to be at most one if `normalized` is set.
+fn vertex_betweenness(index: &Indexed, betweenness: Betweenness, normalized: bool) -> HashMap<usize, f64> {
+	let n = index.len() as f64;
+	let scale = if normalized && n > 2.0 { 2.0 / ((n - 1.0) * (n - 2.0)) } else { 1.0 };
+	index.ids.iter().zip(betweenness.vertices).map(|(&id, b)| (id, b * scale)).collect()
+}
+
+/// Collects the edge betweenness by the IDs of the endpoints, scaled to be at most one if
+/// `normalized` is set.
+fn edge_betweenness(index: &Indexed, betweenness: Betweenness, normalized: bool) -> HashMap<(usize, usize), f64> {
+	let n = index.len() as f64;
+	let scale = if normalized && n > 1.0 { 2.0 / (n * (n - 1.0)) } else { 1.0 };
+	betweenness.edges.into_iter().map(|((u, v), b)| ((index.ids[u], index.ids[v]), b * scale)).collect()
+}
+
+/// Computes the betweenness centrality of every vertex of a graph with Brandes' algorithm: the
+/// number of shortest paths between other vertices that go through the vertex.
+///
+/// When two vertices are joined by several shortest paths, each path counts as a fraction of one.
+/// Takes `O(V E)` time.
+///
+/// Returns a map from the IDs of the vertices to their centrality. If `normalized` is set, the
+/// centrality is divided by the number of pairs of other vertices, so that it is at most one.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the centrality in.
+/// - `normalized`: Whether to divide by the number of pairs of other vertices.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::centrality::betweenness_centrality;
+///
+/// // Every path between two leaves of a star goes through its center
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let center = graph.add_vertex(());
+/// let leaves: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+/// for &leaf in &leaves {
+///     graph.add_edge(center, leaf);
+/// }
+///
+/// let centrality = betweenness_centrality(&graph, false);
+///
+/// assert_eq!(centrality[&center], 6.0);
+/// assert_eq!(centrality[&leaves[0]], 0.0);
+/// assert_eq!(betweenness_centrality(&graph, true)[&center], 1.0);
+/// ```
+pub fn betweenness_centrality<T: Clone, G: Graph<T>>(graph: &G, normalized: bool) -> HashMap<usize, f64> {
+	let index = Indexed::new(graph);
+	let betweenness = brandes(&index, |source| bfs_paths(&index, source));
+	vertex_betweenness(&index, betweenness, normalized)
+}
+
+/// Computes the betweenness centrality of every vertex of a weighted graph with Brandes' algorithm,
+/// along the shortest paths by total edge weight.
+///
+/// Takes `O(V E log V)` time. Otherwise the same as [`betweenness_centrality`].
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find the centrality in.
+/// - `normalized`: Whether to divide by the number of pairs of other vertices.
+pub fn weighted_betweenness_centrality<T, W, G>(graph: &G, normalized: bool) -> HashMap<usize, f64>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let betweenness = brandes(&index, |source| dijkstra_paths(&index, &weights, source));
+	vertex_betweenness(&index, betweenness, normalized)
+}
+
+/// Computes the betweenness centrality of every edge of a graph with Brandes' algorithm: the number
+/// of shortest paths between vertices that go along the edge.
+///
+/// When two vertices are joined by several shortest paths, each path counts as a fraction of one.
+/// Takes `O(V E)` time.
+///
+/// Returns a map from the edges to their centrality. Each edge is keyed by the IDs of its endpoints,
+/// with the earlier added vertex first, and self loops are left out. If `normalized` is set, the
+/// centrality is divided by the number of pairs of vertices, so that it is at most one.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the centrality in.
+/// - `normalized`: Whether to divide by the number of pairs of vertices.
+pub fn edge_betweenness_centrality<T: Clone, G: Graph<T>>(graph: &G, normalized: bool) -> HashMap<(usize, usize), f64> {
+	let index = Indexed::new(graph);
+	let betweenness = brandes(&index, |source| bfs_paths(&index, source));
+	edge_betweenness(&index, betweenness, normalized)
+}
+
+/// Computes the betweenness centrality of every edge of a weighted graph with Brandes' algorithm,
+/// along the shortest paths by total edge weight.
+///
+/// Takes `O(V E log V)` time. Otherwise the same as [`edge_betweenness_centrality`].
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find the centrality in.
+/// - `normalized`: Whether to divide by the number of pairs of vertices.
+pub fn weighted_edge_betweenness_centrality<T, W, G>(graph: &G, normalized: bool) -> HashMap<(usize, usize), f64>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let betweenness = brandes(&index, |source| dijkstra_paths(&index, &weights, source));
+	edge_betweenness(&index, betweenness, normalized)
+}
+
+/// Finds the distances from a vertex to the vertices it can reach by breadth-first search.
+fn bfs_distances(index: &Indexed, source: usize) -> Vec<Option<f64>> {
+	let mut dist: Vec<Option<f64>> = vec![None; index.len()];
+	let mut queue = VecDeque::from([source]);
+	dist[source] = Some(0.0);
+	while let Some(u) = queue.pop_front() {
+		let d = dist[u].unwrap() + 1.0;
+		for &v in &index.adj[u] {
+			if dist[v].is_none() {
+				dist[v] = Some(d);
+				queue.push_back(v);
+			}
+		}
+	}
+	dist
+}
+
+/// Computes the closeness of a vertex from its distances to the other vertices, scaled by the
+/// fraction of the vertices it can reach.
+fn closeness(dist: Vec<Option<f64>>) -> f64 {
+	let n = dist.len();
+	let (reached, total) = dist.into_iter().flatten().fold((0, 0.0), |(reached, total), d| (reached + 1, total + d));
+	let reached = (reached - 1) as f64;
+	if total == 0.0 {
+		return 0.0;
+	}
+	reached / total * reached / (n - 1) as f64
+}
+
+/// Computes the harmonic centrality of a vertex from its distances to the other vertices.
+fn harmonic(dist: Vec<Option<f64>>) -> f64 {
+	dist.into_iter().flatten().filter(|&d| d > 0.0).map(|d| 1.0 / d).sum()
+}
+
+/// Computes the closeness centrality of every vertex of a graph: the inverse of the average
+/// distance from the vertex to the other vertices.
+///
+/// In a graph that isn't connected, the average is over the vertices the vertex can reach, and the
+/// centrality is scaled by the fraction of the other vertices it can reach, as proposed by
+/// Wasserman and Faust. A vertex that can't reach any other vertex has centrality zero. Takes
+/// `O(V E)` time.
+///
+/// Returns a map from the IDs of the vertices to their centrality.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the centrality in.
+pub fn closeness_centrality<T: Clone, G: Graph<T>>(graph: &G) -> HashMap<usize, f64> {
+	let index = Indexed::new(graph);
+	(0..index.len()).map(|u| (index.ids[u], closeness(bfs_distances(&index, u)))).collect()
+}
+
+/// Computes the closeness centrality of every vertex of a weighted graph, with the distances along
+/// the shortest paths by total edge weight.
+///
+/// Takes `O(V E log V)` time. Otherwise the same as [`closeness_centrality`].
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find the centrality in.
+pub fn weighted_closeness_centrality<T, W, G>(graph: &G) -> HashMap<usize, f64>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	(0..index.len())
+		.map(|u| {
+			let dist = dijkstra(&index.adj, &weights, u).0;
+			(index.ids[u], closeness(dist.into_iter().map(|d| d.map(W::to_f64)).collect()))
+		})
+		.collect()
+}
+
+/// Computes the harmonic centrality of every vertex of a graph: the sum of the inverse distances
+/// from the vertex to the other vertices.
+///
+/// Vertices that can't be reached add nothing, so unlike closeness centrality, harmonic centrality
+/// needs no special treatment of graphs that aren't connected. Takes `O(V E)` time.
+///
+/// Returns a map from the IDs of the vertices to their centrality.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the centrality in.
+pub fn harmonic_centrality<T: Clone, G: Graph<T>>(graph: &G) -> HashMap<usize, f64> {
+	let index = Indexed::new(graph);
+	(0..index.len()).map(|u| (index.ids[u], harmonic(bfs_distances(&index, u)))).collect()
+}
+
+/// Computes the harmonic centrality of every vertex of a weighted graph, with the distances along
+/// the shortest paths by total edge weight.
+///
+/// Takes `O(V E log V)` time. Otherwise the same as [`harmonic_centrality`].
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find the centrality in.
+pub fn weighted_harmonic_centrality<T, W, G>(graph: &G) -> HashMap<usize, f64>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	(0..index.len())
+		.map(|u| {
+			let dist = dijkstra(&index.adj, &weights, u).0;
+			(index.ids[u], harmonic(dist.into_iter().map(|d| d.map(W::to_f64)).collect()))
+		})
+		.collect()
+}
+
+/// Scales a vector to unit Euclidean length, unless it is zero.
+fn normalize(x: &mut [f64]) {
+	let norm = x.iter().map(|v| v * v).sum::<f64>().sqrt();
+	if norm > 0.0 {
+		x.iter_mut().for_each(|v| *v /= norm);
+	}
+}
+
+/// Gives every edge of a graph weight one, in the layout of [`Indexed::weights`].
+fn unit_weights(index: &Indexed) -> Vec<Vec<f64>> {
+	index.adj.iter().map(|neighbors| vec![1.0; neighbors.len()]).collect()
+}
+
+/// Converts the edge weights of a graph to floating point numbers, in the layout of
+/// [`Indexed::weights`].
+fn float_weights<T, W, G>(index: &Indexed, graph: &G) -> Vec<Vec<f64>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	index.weights(graph).into_iter().map(|row| row.into_iter().map(W::to_f64).collect()).collect()
+}
+
+/// Finds the eigenvector centralities by power iteration on the weighted adjacency matrix plus the
+/// identity.
+fn eigenvector(index: &Indexed, weights: &[Vec<f64>], tolerance: f64, max_iterations: usize) -> Option<HashMap<usize, f64>> {
+	let n = index.len();
+	let mut x = vec![1.0 / (n as f64).sqrt(); n];
+
+	for _ in 0..max_iterations {
+		let mut next = x.clone();
+		for (u, neighbors) in index.adj.iter().enumerate() {
+			for (&v, &w) in neighbors.iter().zip(&weights[u]) {
+				next[v] += w * x[u];
+			}
+		}
+		normalize(&mut next);
+
+		let change: f64 = next.iter().zip(&x).map(|(a, b)| (a - b).abs()).sum();
+		x = next;
+		if change < tolerance {
+			return Some(index.ids.iter().copied().zip(x).collect());
+		}
+	}
+
+	None
+}
+
+/// Finds the Katz centralities by iterating `x = alpha A x + beta` for the weighted adjacency matrix
+/// `A`.
+fn katz(index: &Indexed, weights: &[Vec<f64>], alpha: f64, beta: f64, tolerance: f64, max_iterations: usize) -> Option<HashMap<usize, f64>> {
+	let n = index.len();
+	let mut x = vec![0.0; n];
+
+	for _ in 0..max_iterations {
+		let mut next = vec![beta; n];
+		for (u, neighbors) in index.adj.iter().enumerate() {
+			for (&v, &w) in neighbors.iter().zip(&weights[u]) {
+				next[v] += alpha * w * x[u];
+			}
+		}
+
+		let change: f64 = next.iter().zip(&x).map(|(a, b)| (a - b).abs()).sum();
+		x = next;
+		if change < tolerance {
+			normalize(&mut x);
+			return Some(index.ids.iter().copied().zip(x).collect());
+		}
+	}
+
+	None
+}
+
+/// Computes the eigenvector centrality of every vertex of a graph: the entries of the eigenvector
+/// of the adjacency matrix that belongs to its largest eigenvalue, so that the centrality of each
+/// vertex is proportional to the sum of the centralities of its neighbors.
+///
+/// The eigenvector is found by power iteration on the adjacency matrix plus the identity, which has
+/// the same eigenvectors but also converges on bipartite graphs. The iteration stops once the
+/// centralities change by less than `tolerance` in total. In a graph that isn't connected, the
+/// result depends on the sizes of the components and may take many iterations to settle.
+///
+/// Returns a map from the IDs of the vertices to their centrality, scaled so that the vector of all
+/// centralities has length one, or `None` if the iteration doesn't converge within
+/// `max_iterations` iterations.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the centrality in.
+/// - `tolerance`: The total change in the centralities below which the iteration stops.
+/// - `max_iterations`: The most iterations to run.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::centrality::eigenvector_centrality;
+///
+/// // The center of a star with three leaves has centrality 1/√2, and the leaves 1/√6
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let center = graph.add_vertex(());
+/// for _ in 0..3 {
+///     let leaf = graph.add_vertex(());
+///     graph.add_edge(center, leaf);
+/// }
+///
+/// let centrality = eigenvector_centrality(&graph, 1e-9, 1000).unwrap();
+///
+/// assert!((centrality[&center] - 0.5f64.sqrt()).abs() < 1e-6);
+/// ```
+pub fn eigenvector_centrality<T: Clone, G: Graph<T>>(graph: &G, tolerance: f64, max_iterations: usize) -> Option<HashMap<usize, f64>> {
+	let index = Indexed::new(graph);
+	let weights = unit_weights(&index);
+	eigenvector(&index, &weights, tolerance, max_iterations)
+}
+
+/// Computes the eigenvector centrality of every vertex of a weighted graph, with the adjacency
+/// matrix holding the edge weights, so that the centrality of each vertex is proportional to the
+/// sum of the centralities of its neighbors times the weights of the edges to them.
+///
+/// Edge weights must not be negative. Otherwise the same as [`eigenvector_centrality`].
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find the centrality in.
+/// - `tolerance`: The total change in the centralities below which the iteration stops.
+/// - `max_iterations`: The most iterations to run.
+pub fn weighted_eigenvector_centrality<T, W, G>(graph: &G, tolerance: f64, max_iterations: usize) -> Option<HashMap<usize, f64>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = float_weights(&index, graph);
+	eigenvector(&index, &weights, tolerance, max_iterations)
+}
+
+/// Computes the Katz centrality of every vertex of a graph: the weighted number of walks that end at
+/// the vertex, where a walk of length `k` counts `alpha^k` times and each walk contributes `beta`.
+///
+/// The centralities satisfy `x = alpha A x + beta` for the adjacency matrix `A`, which has a
+/// solution as long as `alpha` is less than one over the largest eigenvalue of `A`. They are found
+/// by iterating the equation, which stops once the centralities change by less than `tolerance` in
+/// total.
+///
+/// Returns a map from the IDs of the vertices to their centrality, scaled so that the vector of all
+/// centralities has length one, or `None` if the iteration doesn't converge within
+/// `max_iterations` iterations, as happens when `alpha` is too large.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the centrality in.
+/// - `alpha`: The attenuation factor for each step of a walk, usually around 0.1.
+/// - `beta`: The weight of each walk, usually one.
+/// - `tolerance`: The total change in the centralities below which the iteration stops.
+/// - `max_iterations`: The most iterations to run.
+pub fn katz_centrality<T: Clone, G: Graph<T>>(
+	graph: &G,
+	alpha: f64,
+	beta: f64,
+	tolerance: f64,
+	max_iterations: usize,
+) -> Option<HashMap<usize, f64>> {
+	let index = Indexed::new(graph);
+	let weights = unit_weights(&index);
+	katz(&index, &weights, alpha, beta, tolerance, max_iterations)
+}
+
+/// Computes the Katz centrality of every vertex of a weighted graph, where a walk counts the product
+/// of the weights of its edges times `alpha^k`.
+///
+/// The centralities satisfy `x = alpha A x + beta` for the adjacency matrix `A` holding the edge
+/// weights. Edge weights must not be negative. Otherwise the same as [`katz_centrality`].
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find the centrality in.
+/// - `alpha`: The attenuation factor for each step of a walk.
+/// - `beta`: The weight of each walk, usually one.
+/// - `tolerance`: The total change in the centralities below which the iteration stops.
+/// - `max_iterations`: The most iterations to run.
+pub fn weighted_katz_centrality<T, W, G>(
+	graph: &G,
+	alpha: f64,
+	beta: f64,
+	tolerance: f64,
+	max_iterations: usize,
+) -> Option<HashMap<usize, f64>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = float_weights(&index, graph);
+	katz(&index, &weights, alpha, beta, tolerance, max_iterations)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::random_weighted_graph;
+	use crate::UndirectedSparseGraph;
+
+	fn assert_close(a: f64, b: f64) {
+		assert!((a - b).abs() < 1e-9, "{a} is not close to {b}");
+	}
+
+	fn build_graph(n: usize, edges: &[(usize, usize, u32)]) -> (UndirectedSparseGraph<(), u32>, Vec<usize>) {
+		let mut graph = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..n).map(|_| graph.add_vertex(())).collect();
+		for &(a, b, w) in edges {
+			graph.set_edge_weight(v[a], v[b], w);
+		}
+		(graph, v)
+	}
+
+	/// Computes the betweenness of the vertices and edges from the definition, by counting the
+	/// shortest paths between all pairs with Floyd–Warshall distances.
+	fn brute_force(graph: &UndirectedSparseGraph<usize, u32>, weighted: bool) -> (HashMap<usize, f64>, HashMap<(usize, usize), f64>) {
+		let v = graph.get_vertices();
+		let n = v.len();
+		let length = |a: usize, b: usize| graph.get_edge_weight(v[a], v[b]).map(|w| if weighted { w as u64 } else { 1 });
+		let mut dist: Vec<Vec<u64>> = (0..n)
+			.map(|a| (0..n).map(|b| if a == b { 0 } else { length(a, b).unwrap_or(u64::MAX / 4) }).collect())
+			.collect();
+		for k in 0..n {
+			for a in 0..n {
+				for b in 0..n {
+					dist[a][b] = dist[a][b].min(dist[a][k] + dist[k][b]);
+				}
+			}
+		}
+
+		// Count the shortest paths from each vertex, going out in order of distance
+		let mut count = vec![vec![0.0; n]; n];
+		for s in 0..n {
+			let mut order: Vec<usize> = (0..n).collect();
+			order.sort_by_key(|&x| dist[s][x]);
+			count[s][s] = 1.0;
+			for &x in &order[1..] {
+				count[s][x] = (0..n).filter(|&y| length(y, x).is_some_and(|w| dist[s][y] + w == dist[s][x])).map(|y| count[s][y]).sum();
+			}
+		}
+
+		let mut vertices = HashMap::new();
+		for x in 0..n {
+			let mut total = 0.0;
+			for s in 0..n {
+				for t in s + 1..n {
+					if s != x && t != x && count[s][t] > 0.0 && dist[s][x] + dist[x][t] == dist[s][t] {
+						total += count[s][x] * count[x][t] / count[s][t];
+					}
+				}
+			}
+			vertices.insert(v[x], total);
+		}
+
+		let mut edges = HashMap::new();
+		for a in 0..n {
+			for b in a + 1..n {
+				let Some(w) = length(a, b) else { continue };
+				let mut total = 0.0;
+				for s in 0..n {
+					for t in s + 1..n {
+						for (x, y) in [(a, b), (b, a)] {
+							if count[s][t] > 0.0 && dist[s][x] + w + dist[y][t] == dist[s][t] {
+								total += count[s][x] * count[y][t] / count[s][t];
+							}
+						}
+					}
+				}
+				edges.insert((v[a], v[b]), total);
+			}
+		}
+
+		(vertices, edges)
+	}
+
+	#[test]
+	fn test_betweenness_centrality() {
+		// A path, and a square where the paths between opposite corners split
+		let (graph, v) = build_graph(4, &[(0, 1, 1), (1, 2, 1), (2, 3, 1)]);
+		let centrality = betweenness_centrality(&graph, false);
+		assert_eq!([0, 1, 2, 3].map(|i| centrality[&v[i]]), [0.0, 2.0, 2.0, 0.0]);
+		assert_close(betweenness_centrality(&graph, true)[&v[1]], 2.0 / 3.0);
+
+		let edges = edge_betweenness_centrality(&graph, false);
+		assert_eq!(edges.len(), 3);
+		assert_eq!([edges[&(v[0], v[1])], edges[&(v[1], v[2])], edges[&(v[2], v[3])]], [3.0, 4.0, 3.0]);
+
+		let (graph, v) = build_graph(4, &[(0, 1, 1), (1, 2, 1), (2, 3, 1), (3, 0, 1)]);
+		let centrality = betweenness_centrality(&graph, false);
+		(0..4).for_each(|i| assert_eq!(centrality[&v[i]], 0.5));
+		edge_betweenness_centrality(&graph, false).values().for_each(|&b| assert_eq!(b, 2.0));
+
+		// With weights, the long edge is avoided
+		let (graph, v) = build_graph(3, &[(0, 1, 1), (1, 2, 1), (0, 2, 5)]);
+		assert_eq!(betweenness_centrality(&graph, false)[&v[1]], 0.0);
+		assert_eq!(weighted_betweenness_centrality(&graph, false)[&v[1]], 1.0);
+		assert_eq!(weighted_edge_betweenness_centrality(&graph, false)[&(v[0], v[2])], 0.0);
+	}
+
+	#[test]
+	fn test_betweenness_brute_force() {
+		for seed in 1..30 {
+			let graph = random_weighted_graph(9, 30, 3, seed);
+			for weighted in [false, true] {
+				let (vertices, edges) = brute_force(&graph, weighted);
+				let (found_vertices, found_edges) = if weighted {
+					(weighted_betweenness_centrality(&graph, false), weighted_edge_betweenness_centrality(&graph, false))
+				} else {
+					(betweenness_centrality(&graph, false), edge_betweenness_centrality(&graph, false))
+				};
+
+				assert_eq!(found_vertices.len(), vertices.len());
+				vertices.iter().for_each(|(id, &b)| assert_close(found_vertices[id], b));
+				assert_eq!(found_edges.len(), edges.len());
+				edges.iter().for_each(|(e, &b)| assert_close(found_edges[e], b));
+			}
+		}
+	}
+
+	#[test]
+	fn test_closeness_and_harmonic_centrality() {
+		// A path of three vertices and an isolated vertex
+		let (graph, v) = build_graph(4, &[(0, 1, 1), (1, 2, 3)]);
+		let closeness = closeness_centrality(&graph);
+		assert_close(closeness[&v[0]], 2.0 / 3.0 * 2.0 / 3.0);
+		assert_close(closeness[&v[1]], 2.0 / 3.0);
+		assert_eq!(closeness[&v[3]], 0.0);
+
+		let harmonic = harmonic_centrality(&graph);
+		assert_close(harmonic[&v[0]], 1.5);
+		assert_close(harmonic[&v[1]], 2.0);
+		assert_eq!(harmonic[&v[3]], 0.0);
+
+		let closeness = weighted_closeness_centrality(&graph);
+		assert_close(closeness[&v[0]], 2.0 / 5.0 * 2.0 / 3.0);
+		assert_close(closeness[&v[1]], 2.0 / 4.0 * 2.0 / 3.0);
+
+		let harmonic = weighted_harmonic_centrality(&graph);
+		assert_close(harmonic[&v[0]], 1.0 + 1.0 / 4.0);
+		assert_close(harmonic[&v[2]], 1.0 / 3.0 + 1.0 / 4.0);
+	}
+
+	#[test]
+	fn test_eigenvector_centrality() {
+		// A path of three vertices is bipartite, with the eigenvector (1, √2, 1) / 2
+		let (graph, v) = build_graph(3, &[(0, 1, 1), (1, 2, 1)]);
+		let centrality = eigenvector_centrality(&graph, 1e-12, 1000).unwrap();
+		assert!((centrality[&v[0]] - 0.5).abs() < 1e-6);
+		assert!((centrality[&v[1]] - 0.5f64.sqrt()).abs() < 1e-6);
+
+		assert_eq!(eigenvector_centrality(&graph, 1e-12, 2), None);
+		assert_eq!(eigenvector_centrality(&UndirectedSparseGraph::<()>::new(), 1e-6, 10), Some(HashMap::new()));
+	}
+
+	#[test]
+	fn test_weighted_eigenvector_centrality() {
+		// With weights 1 and 2 on the path, the largest eigenvalue is √5 with the eigenvector
+		// (1, √5, 2) / √10
+		let (graph, v) = build_graph(3, &[(0, 1, 1), (1, 2, 2)]);
+		let centrality = weighted_eigenvector_centrality(&graph, 1e-12, 1000).unwrap();
+		assert!((centrality[&v[0]] - 0.1f64.sqrt()).abs() < 1e-6);
+		assert!((centrality[&v[1]] - 0.5f64.sqrt()).abs() < 1e-6);
+		assert!((centrality[&v[2]] - 0.4f64.sqrt()).abs() < 1e-6);
+
+		// Scaling all weights alike gives the unweighted centralities
+		let (graph, v) = build_graph(4, &[(0, 1, 3), (1, 2, 3), (2, 0, 3), (2, 3, 3)]);
+		let weighted = weighted_eigenvector_centrality(&graph, 1e-12, 1000).unwrap();
+		let unweighted = eigenvector_centrality(&graph, 1e-12, 1000).unwrap();
+		v.iter().for_each(|u| assert!((weighted[u] - unweighted[u]).abs() < 1e-6));
+	}
+
+	#[test]
+	fn test_katz_centrality() {
+		// On a path of three vertices with alpha 0.1, x0 = 1.1 / 0.98 and x1 = 0.2 x0 + 1
+		let (graph, v) = build_graph(3, &[(0, 1, 1), (1, 2, 1)]);
+		let centrality = katz_centrality(&graph, 0.1, 1.0, 1e-12, 1000).unwrap();
+		let (x0, x1): (f64, f64) = (1.1 / 0.98, 0.2 * 1.1 / 0.98 + 1.0);
+		let norm = (2.0 * x0 * x0 + x1 * x1).sqrt();
+		assert_close(centrality[&v[0]], x0 / norm);
+		assert_close(centrality[&v[1]], x1 / norm);
+		assert_close(centrality[&v[2]], x0 / norm);
+
+		// The largest eigenvalue of a triangle is 2, so walks don't die out with alpha 0.6
+		let (graph, _) = build_graph(3, &[(0, 1, 1), (1, 2, 1), (2, 0, 1)]);
+		assert_eq!(katz_centrality(&graph, 0.6, 1.0, 1e-9, 1000), None);
+		katz_centrality(&graph, 0.3, 1.0, 1e-12, 1000).unwrap().values().for_each(|&x| assert_close(x, 1.0 / 3.0f64.sqrt()));
+	}
+
+	#[test]
+	fn test_weighted_katz_centrality() {
+		// Doubling every weight is the same as doubling alpha
+		let (graph, v) = build_graph(4, &[(0, 1, 2), (1, 2, 2), (2, 0, 2), (2, 3, 2)]);
+		let weighted = weighted_katz_centrality(&graph, 0.05, 1.0, 1e-12, 1000).unwrap();
+		let unweighted = katz_centrality(&graph, 0.1, 1.0, 1e-12, 1000).unwrap();
+		v.iter().for_each(|u| assert!((weighted[u] - unweighted[u]).abs() < 1e-9));
+
+		// On a path with weights 1 and 2 and alpha 0.1, x0 = 0.1 x1 + 1, x2 = 0.2 x1 + 1 and
+		// x1 = 0.1 x0 + 0.2 x2 + 1, so x1 = 1.3 / 0.95
+		let (graph, v) = build_graph(3, &[(0, 1, 1), (1, 2, 2)]);
+		let centrality = weighted_katz_centrality(&graph, 0.1, 1.0, 1e-12, 1000).unwrap();
+		let x1: f64 = 1.3 / 0.95;
+		let (x0, x2) = (0.1 * x1 + 1.0, 0.2 * x1 + 1.0);
+		let norm = (x0 * x0 + x1 * x1 + x2 * x2).sqrt();
+		assert_close(centrality[&v[0]], x0 / norm);
+		assert_close(centrality[&v[1]], x1 / norm);
+		assert_close(centrality[&v[2]], x2 / norm);
+	}
+}
@@ -0,0 +1,305 @@
+//! Cliques of undirected graphs.
+//!
+//! The searches in this module work on the rows of an adjacency matrix stored as bit sets, so the
+//! neighborhoods of two vertices are intersected a machine word at a time. The bit sets are filled
+//! in from the neighbors of every vertex, which an [`UndirectedGraph`](crate::UndirectedGraph) reads
+//! off the rows of its own adjacency matrix, so building them takes `O(V^2)` time on it. Self loops
+//! are ignored.
+
+use super::util::{adjacency_matrix, degeneracy_order, BitSet, Indexed};
+use super::Graph;
+
+/// A step of the Bron–Kerbosch search: the clique built so far, the vertices that can still extend
+/// it, and the vertices that could extend it but have been explored already.
+#[derive(Debug)]
+struct Frame {
+	clique: Vec<usize>,
+	candidates: BitSet,
+	excluded: BitSet,
+	branches: Vec<usize>, // The candidates to branch on, which are not neighbors of the pivot
+	next: usize, // The position of the next branch
+}
+
+/// A lazy iterator over the maximal cliques of a graph, returned by [`maximal_cliques`].
+///
+/// The iterator works on a snapshot of the graph taken when it was created.
+#[derive(Debug)]
+pub struct MaximalCliques {
+	ids: Vec<usize>,
+	adj: Vec<BitSet>,
+	order: Vec<usize>, // The vertices in degeneracy order
+	position: usize, // The position in `order` of the next vertex to start a search from
+	stack: Vec<Frame>,
+}
+
+impl MaximalCliques {
+	/// Starts a search for the cliques extending `clique` with the given candidates and excluded
+	/// vertices. Returns the clique itself if it is maximal.
+	fn branch(&mut self, clique: Vec<usize>, candidates: BitSet, excluded: BitSet) -> Option<Vec<usize>> {
+		if candidates.is_empty() {
+			return excluded.is_empty().then(|| self.to_ids(clique));
+		}
+
+		// Tomita's pivot: the vertex with the most neighbors among the candidates. Every maximal
+		// clique contains the pivot or one of its non-neighbors, so only those need to be tried.
+		let pivot = candidates.iter().chain(excluded.iter())
+			.max_by_key(|&u| candidates.intersection_len(&self.adj[u]))
+			.unwrap();
+		let branches = candidates.difference(&self.adj[pivot]).iter().collect();
+
+		self.stack.push(Frame { clique, candidates, excluded, branches, next: 0 });
+		None
+	}
+
+	/// Maps a clique to vertex IDs, in the order the vertices were added to the graph.
+	fn to_ids(&self, mut clique: Vec<usize>) -> Vec<usize> {
+		clique.sort_unstable();
+		clique.into_iter().map(|u| self.ids[u]).collect()
+	}
+}
+
+impl Iterator for MaximalCliques {
+	type Item = Vec<usize>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let Some(frame) = self.stack.last_mut() else {
+				// Start a new search from the next vertex in degeneracy order, as the earliest
+				// vertex of the cliques it finds. Only its later neighbors can join them, and there
+				// are at most as many as the degeneracy of the graph.
+				let &v = self.order.get(self.position)?;
+				self.position += 1;
+
+				let later = BitSet::from_indices(self.ids.len(), self.order[self.position..].iter().copied());
+				let candidates = self.adj[v].intersection(&later);
+				let excluded = self.adj[v].difference(&later);
+				if let Some(clique) = self.branch(vec![v], candidates, excluded) {
+					return Some(clique);
+				}
+				continue;
+			};
+
+			let Some(&v) = frame.branches.get(frame.next) else {
+				self.stack.pop();
+				continue;
+			};
+			frame.next += 1;
+
+			let candidates = frame.candidates.intersection(&self.adj[v]);
+			let excluded = frame.excluded.intersection(&self.adj[v]);
+			let mut clique = frame.clique.clone();
+			clique.push(v);
+
+			// Later branches must not find this clique again
+			frame.candidates.remove(v);
+			frame.excluded.insert(v);
+
+			if let Some(clique) = self.branch(clique, candidates, excluded) {
+				return Some(clique);
+			}
+		}
+	}
+}
+
+/// Lists the maximal cliques of a graph, lazily.
+///
+/// A clique is a set of vertices that are all adjacent to each other, and it is maximal if no other
+/// vertex can be added to it. The cliques are found with the Bron–Kerbosch algorithm, using Tomita's
+/// pivoting rule within a degeneracy ordering of the vertices (as proposed by Eppstein, Löffler and
+/// Strash). Every maximal clique is returned exactly once, with the IDs of its vertices in the order
+/// they were added to the graph.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to find the cliques of.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::cliques::maximal_cliques;
+///
+/// // Two triangles sharing an edge, and a pendant vertex
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+/// for (a, b) in [(0, 1), (1, 2), (2, 0), (1, 3), (2, 3), (3, 4)] {
+///     graph.add_edge(v[a], v[b]);
+/// }
+///
+/// let mut cliques: Vec<Vec<usize>> = maximal_cliques(&graph).collect();
+/// cliques.sort();
+///
+/// assert_eq!(cliques, vec![vec![v[0], v[1], v[2]], vec![v[1], v[2], v[3]], vec![v[3], v[4]]]);
+/// ```
+pub fn maximal_cliques<T: Clone, G: Graph<T>>(graph: &G) -> MaximalCliques {
+	let index = Indexed::new(graph);
+	let adj = adjacency_matrix(&index);
+	let order = degeneracy_order(&index.adj);
+
+	MaximalCliques {
+		ids: index.ids,
+		adj,
+		order,
+		position: 0,
+		stack: Vec::new(),
+	}
+}
+
+/// Searches for cliques larger than the best one found so far, pruning with a greedy coloring of
+/// the candidates: a clique can't contain two vertices of the same color.
+fn expand_clique(adj: &[BitSet], clique: &mut Vec<usize>, mut candidates: BitSet, best: &mut Vec<usize>) {
+	// Color the candidates one color class at a time, so that the colors are non-decreasing
+	let mut colored: Vec<(usize, usize)> = Vec::new();
+	let mut uncolored = candidates.clone();
+	let mut color = 0;
+	while !uncolored.is_empty() {
+		color += 1;
+		let mut class = uncolored.clone();
+		while let Some(v) = class.first() {
+			class = class.difference(&adj[v]);
+			class.remove(v);
+			uncolored.remove(v);
+			colored.push((v, color));
+		}
+	}
+
+	// Try the vertices with the highest colors first; the color bounds the size of any clique
+	// among the vertices up to it
+	for &(v, color) in colored.iter().rev() {
+		if clique.len() + color <= best.len() {
+			return;
+		}
+
+		clique.push(v);
+		let next = candidates.intersection(&adj[v]);
+		if next.is_empty() {
+			if clique.len() > best.len() {
+				best.clone_from(clique);
+			}
+		} else {
+			expand_clique(adj, clique, next, best);
+		}
+		clique.pop();
+		candidates.remove(v);
+	}
+}
+
+/// Finds a maximum clique of a graph: a clique with as many vertices as possible.
+///
+/// Uses a branch and bound search in which greedy colorings bound the size of the cliques that are
+/// left to find. This takes exponential time in the worst case, but is fast on sparse graphs and on
+/// graphs of a few hundred vertices.
+///
+/// Returns the IDs of the vertices of the clique in the order they were added to the graph, or an
+/// empty vector if the graph has no vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to find a maximum clique of.
+pub fn maximum_clique<T: Clone, G: Graph<T>>(graph: &G) -> Vec<usize> {
+	let index = Indexed::new(graph);
+	let adj = adjacency_matrix(&index);
+	let n = index.len();
+
+	let mut best = Vec::new();
+	expand_clique(&adj, &mut Vec::new(), BitSet::from_indices(n, 0..n), &mut best);
+
+	best.sort_unstable();
+	best.into_iter().map(|u| index.ids[u]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+	use crate::util::random_graph;
+
+	/// Checks that a set of vertices is a clique of a graph.
+	fn is_clique<T: Clone, G: Graph<T>>(graph: &G, clique: &[usize]) -> bool {
+		clique.iter().enumerate().all(|(i, &u)| clique[i + 1..].iter().all(|&v| graph.is_adjacent(u, v)))
+	}
+
+	/// Finds the maximal cliques by checking every subset of the vertices.
+	fn brute_force(graph: &UndirectedSparseGraph<usize>) -> Vec<Vec<usize>> {
+		let v = graph.get_vertices();
+		let mut cliques = Vec::new();
+		for mask in 1..1u32 << v.len() {
+			let clique: Vec<usize> = (0..v.len()).filter(|&i| mask & (1 << i) != 0).map(|i| v[i]).collect();
+			let maximal = v.iter().all(|&u| clique.contains(&u) || !clique.iter().all(|&c| graph.is_adjacent(u, c)));
+			if is_clique(graph, &clique) && maximal {
+				cliques.push(clique);
+			}
+		}
+		cliques.sort();
+		cliques
+	}
+
+	#[test]
+	fn test_maximal_cliques() {
+		for seed in 1..30 {
+			let graph = random_graph(10, 50, seed);
+			let mut cliques: Vec<Vec<usize>> = maximal_cliques(&graph).collect();
+			cliques.sort();
+
+			assert_eq!(cliques, brute_force(&graph));
+		}
+	}
+
+	#[test]
+	fn test_maximal_cliques_special_cases() {
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		assert_eq!(maximal_cliques(&graph).next(), None);
+
+		// Isolated vertices are cliques of their own, and self loops don't matter
+		let v1 = graph.add_vertex(());
+		let v2 = graph.add_vertex(());
+		graph.add_edge(v2, v2);
+		assert_eq!(maximal_cliques(&graph).collect::<Vec<_>>(), vec![vec![v1], vec![v2]]);
+	}
+
+	#[test]
+	fn test_maximal_cliques_lazy() {
+		// The Moon–Moser graph with 3 parts of 10 vertices has 10^3 maximal cliques
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..30).map(|_| graph.add_vertex(())).collect();
+		for a in 0..30 {
+			for b in a + 1..30 {
+				if a % 3 != b % 3 {
+					graph.add_edge(v[a], v[b]);
+				}
+			}
+		}
+
+		let mut cliques = maximal_cliques(&graph);
+		let first: Vec<Vec<usize>> = cliques.by_ref().take(5).collect();
+		assert!(first.iter().all(|c| c.len() == 3 && is_clique(&graph, c)));
+		assert_eq!(cliques.count(), 995);
+	}
+
+	#[test]
+	fn test_maximum_clique() {
+		for seed in 1..30 {
+			let graph = random_graph(10, 60, seed);
+			let clique = maximum_clique(&graph);
+
+			assert!(is_clique(&graph, &clique));
+			assert_eq!(clique.len(), brute_force(&graph).iter().map(|c| c.len()).max().unwrap());
+		}
+
+		// A hidden clique of 12 in a sparse graph of 200 vertices
+		let mut graph = random_graph(200, 5, 7);
+		let v = graph.get_vertices();
+		let hidden: Vec<usize> = (0..12).map(|i| v[i * 16]).collect();
+		for (i, &a) in hidden.iter().enumerate() {
+			for &b in &hidden[i + 1..] {
+				if !graph.is_adjacent(a, b) {
+					graph.add_edge(a, b);
+				}
+			}
+		}
+		assert_eq!(maximum_clique(&graph), hidden);
+
+		let graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		assert!(maximum_clique(&graph).is_empty());
+	}
+}
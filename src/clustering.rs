@@ -0,0 +1,297 @@
+//! Triangle counting, clustering coefficients and transitivity.
+//!
+//! The method of counting triangles is picked by the density of the graph, whatever type holds it.
+//! When the average degree is at least `n / 64`, the adjacency matrix is built as bit sets and the
+//! rows of the two ends of every edge are intersected. Otherwise every edge is directed from the
+//! endpoint of lower degree to the one of higher degree, and each triangle is found once from its
+//! lowest vertex by intersecting these forward neighborhoods, which takes `O(E √E)` time. Either way
+//! the neighbors of every vertex are read first, which an [`UndirectedGraph`](crate::UndirectedGraph)
+//! does off the rows of its own adjacency matrix in `O(V^2)` time. Self loops are ignored.
+
+use std::collections::HashMap;
+
+use super::util::{adjacency_matrix, Indexed};
+use super::Graph;
+
+/// Lists the distinct neighbors of every vertex, without self loops.
+fn simple_adjacency(index: &Indexed) -> Vec<Vec<usize>> {
+	index.adj.iter().enumerate()
+		.map(|(u, neighbors)| {
+			let mut neighbors: Vec<usize> = neighbors.iter().copied().filter(|&v| v != u).collect();
+			neighbors.sort_unstable();
+			neighbors.dedup();
+			neighbors
+		})
+		.collect()
+}
+
+/// Counts the triangles at every vertex by intersecting rows of the adjacency matrix.
+fn matrix_triangles(index: &Indexed, adj: &[Vec<usize>]) -> Vec<usize> {
+	let rows = adjacency_matrix(index);
+	adj.iter().enumerate()
+		.map(|(u, neighbors)| neighbors.iter().map(|&v| rows[u].intersection_len(&rows[v])).sum::<usize>() / 2)
+		.collect()
+}
+
+/// Counts the triangles at every vertex by intersecting the neighborhoods of each vertex and its
+/// neighbors of higher degree.
+fn forward_triangles(adj: &[Vec<usize>]) -> Vec<usize> {
+	let n = adj.len();
+	let rank = |u: usize| (adj[u].len(), u);
+	let forward: Vec<Vec<usize>> = (0..n)
+		.map(|u| adj[u].iter().copied().filter(|&v| rank(v) > rank(u)).collect())
+		.collect();
+
+	let mut triangles = vec![0; n];
+	let mut marked = vec![false; n];
+	for u in 0..n {
+		forward[u].iter().for_each(|&v| marked[v] = true);
+		for &v in &forward[u] {
+			for &w in forward[v].iter().filter(|&&w| marked[w]) {
+				triangles[u] += 1;
+				triangles[v] += 1;
+				triangles[w] += 1;
+			}
+		}
+		forward[u].iter().for_each(|&v| marked[v] = false);
+	}
+
+	triangles
+}
+
+/// Counts the triangles at every vertex, picking the method by the density of the graph.
+fn triangles(index: &Indexed, adj: &[Vec<usize>]) -> Vec<usize> {
+	let n = index.len();
+	let edges: usize = adj.iter().map(Vec::len).sum::<usize>() / 2;
+
+	// A row intersection takes `n / 64` word operations, which beats merging the neighborhoods once
+	// the average degree is above that
+	if edges * 128 >= n * n {
+		matrix_triangles(index, adj)
+	} else {
+		forward_triangles(adj)
+	}
+}
+
+/// Computes the clustering coefficient of a vertex from its triangles and degree.
+fn clustering(triangles: usize, degree: usize) -> f64 {
+	if degree < 2 {
+		return 0.0;
+	}
+	2.0 * triangles as f64 / (degree * (degree - 1)) as f64
+}
+
+/// Counts the triangles in a graph: the sets of three vertices that are all adjacent to each other.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to count the triangles of.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::clustering::triangle_count;
+///
+/// // A complete graph on four vertices has four triangles
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+/// for a in 0..4 {
+///     for b in a + 1..4 {
+///         graph.add_edge(v[a], v[b]);
+///     }
+/// }
+///
+/// assert_eq!(triangle_count(&graph), 4);
+/// ```
+pub fn triangle_count<T: Clone, G: Graph<T>>(graph: &G) -> usize {
+	let index = Indexed::new(graph);
+	let adj = simple_adjacency(&index);
+	triangles(&index, &adj).iter().sum::<usize>() / 3
+}
+
+/// Counts the triangles that every vertex of a graph is part of.
+///
+/// Returns a map from the IDs of the vertices to their number of triangles.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to count the triangles of.
+pub fn triangles_per_vertex<T: Clone, G: Graph<T>>(graph: &G) -> HashMap<usize, usize> {
+	let index = Indexed::new(graph);
+	let adj = simple_adjacency(&index);
+	index.ids.iter().copied().zip(triangles(&index, &adj)).collect()
+}
+
+/// Computes the local clustering coefficient of a vertex: the fraction of the pairs of its
+/// neighbors that are adjacent to each other.
+///
+/// The coefficient is zero for vertices with fewer than two neighbors. Takes time proportional to
+/// the sum of the degrees of the neighbors, after building an index of the graph.
+///
+/// Returns `None` if the vertex does not exist.
+///
+/// # Arguments
+///
+/// - `graph`: The graph the vertex is in.
+/// - `vertex_id`: The ID of the vertex to compute the coefficient of.
+pub fn local_clustering<T: Clone, G: Graph<T>>(graph: &G, vertex_id: usize) -> Option<f64> {
+	let index = Indexed::new(graph);
+	let u = *index.index.get(&vertex_id)?;
+	let adj = simple_adjacency(&index);
+
+	let mut neighbor = vec![false; index.len()];
+	adj[u].iter().for_each(|&v| neighbor[v] = true);
+	let links: usize = adj[u].iter().map(|&v| adj[v].iter().filter(|&&w| neighbor[w]).count()).sum();
+
+	Some(clustering(links / 2, adj[u].len()))
+}
+
+/// Computes the average clustering coefficient of a graph: the mean of the local clustering
+/// coefficients of all vertices, including those with fewer than two neighbors.
+///
+/// Returns zero for a graph without vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to compute the coefficient of.
+pub fn average_clustering<T: Clone, G: Graph<T>>(graph: &G) -> f64 {
+	let index = Indexed::new(graph);
+	if index.len() == 0 {
+		return 0.0;
+	}
+
+	let adj = simple_adjacency(&index);
+	let total: f64 = triangles(&index, &adj).into_iter().zip(&adj).map(|(t, neighbors)| clustering(t, neighbors.len())).sum();
+	total / index.len() as f64
+}
+
+/// Computes the transitivity of a graph: the fraction of the paths of two edges whose ends are
+/// adjacent, closing a triangle. Also known as the global clustering coefficient.
+///
+/// Unlike [`average_clustering`], every path counts the same, so vertices of high degree weigh
+/// more. Returns zero for a graph without paths of two edges.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to compute the transitivity of.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::clustering::transitivity;
+///
+/// // A triangle with a pendant vertex has five paths of two edges, three of them in the triangle
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+/// for (a, b) in [(0, 1), (1, 2), (2, 0), (2, 3)] {
+///     graph.add_edge(v[a], v[b]);
+/// }
+///
+/// assert_eq!(transitivity(&graph), 0.6);
+/// ```
+pub fn transitivity<T: Clone, G: Graph<T>>(graph: &G) -> f64 {
+	let index = Indexed::new(graph);
+	let adj = simple_adjacency(&index);
+	let paths: usize = adj.iter().map(|neighbors| neighbors.len() * neighbors.len().saturating_sub(1) / 2).sum();
+	if paths == 0 {
+		return 0.0;
+	}
+
+	let closed: usize = triangles(&index, &adj).iter().sum();
+	closed as f64 / paths as f64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+	use crate::util::random_graph;
+
+	/// Counts the triangles at every vertex by checking every set of three vertices against a
+	/// prebuilt adjacency matrix.
+	fn brute_force(graph: &UndirectedSparseGraph<usize>) -> Vec<usize> {
+		let v = graph.get_vertices();
+		let n = v.len();
+		let adjacent: Vec<Vec<bool>> = v.iter().map(|&a| v.iter().map(|&b| a != b && graph.is_adjacent(a, b)).collect()).collect();
+		let mut triangles = vec![0; n];
+		for a in 0..n {
+			for b in a + 1..n {
+				for c in b + 1..n {
+					if adjacent[a][b] && adjacent[b][c] && adjacent[a][c] {
+						triangles[a] += 1;
+						triangles[b] += 1;
+						triangles[c] += 1;
+					}
+				}
+			}
+		}
+		triangles
+	}
+
+	#[test]
+	fn test_both_methods() {
+		for (percent, seed) in [(5, 1), (20, 2), (50, 3), (90, 4)] {
+			let graph = random_graph(70, percent, seed);
+			let index = Indexed::new(&graph);
+			let adj = simple_adjacency(&index);
+			let expected = brute_force(&graph);
+
+			assert_eq!(matrix_triangles(&index, &adj), expected);
+			assert_eq!(forward_triangles(&adj), expected);
+			assert_eq!(triangle_count(&graph), expected.iter().sum::<usize>() / 3);
+			let per_vertex = triangles_per_vertex(&graph);
+			assert!(index.ids.iter().zip(&expected).all(|(id, &t)| per_vertex[id] == t));
+		}
+	}
+
+	#[test]
+	fn test_clustering() {
+		// A triangle with a pendant vertex, and an isolated vertex
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+		for (a, b) in [(0, 1), (1, 2), (2, 0), (2, 3)] {
+			graph.add_edge(v[a], v[b]);
+		}
+		graph.add_edge(v[0], v[0]);
+
+		assert_eq!(triangle_count(&graph), 1);
+		assert_eq!(local_clustering(&graph, v[0]), Some(1.0));
+		assert_eq!(local_clustering(&graph, v[2]), Some(1.0 / 3.0));
+		assert_eq!(local_clustering(&graph, v[3]), Some(0.0));
+		assert_eq!(local_clustering(&graph, v[4]), Some(0.0));
+		assert_eq!(local_clustering(&graph, 100), None);
+		assert!((average_clustering(&graph) - (2.0 + 1.0 / 3.0) / 5.0).abs() < 1e-12);
+		assert_eq!(transitivity(&graph), 0.6);
+	}
+
+	#[test]
+	fn test_clustering_random() {
+		let graph = random_graph(60, 15, 5);
+		let v = graph.get_vertices();
+		let triangles = brute_force(&graph);
+
+		let mut total = 0.0;
+		let (mut closed, mut paths) = (0, 0);
+		for (i, &u) in v.iter().enumerate() {
+			let degree = graph.get_neighbors(u).len();
+			let expected = if degree < 2 { 0.0 } else { 2.0 * triangles[i] as f64 / (degree * (degree - 1)) as f64 };
+			assert!((local_clustering(&graph, u).unwrap() - expected).abs() < 1e-12);
+			total += expected;
+			closed += triangles[i];
+			paths += degree * degree.saturating_sub(1) / 2;
+		}
+		assert!((average_clustering(&graph) - total / 60.0).abs() < 1e-12);
+		assert!((transitivity(&graph) - closed as f64 / paths as f64).abs() < 1e-12);
+	}
+
+	#[test]
+	fn test_empty() {
+		let graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		assert_eq!(triangle_count(&graph), 0);
+		assert!(triangles_per_vertex(&graph).is_empty());
+		assert_eq!(average_clustering(&graph), 0.0);
+		assert_eq!(transitivity(&graph), 0.0);
+	}
+}
@@ -0,0 +1,392 @@
+//! Vertex coloring.
+//!
+//! A coloring assigns every vertex a color, numbered from zero, so that no edge joins two vertices
+//! of the same color. Colorings are returned as a map from vertex ID to color. Self loops can't be
+//! satisfied by any coloring, so they are ignored.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use super::util::{degeneracy_order, Indexed};
+use super::Graph;
+
+/// The order in which [`greedy_coloring`] colors the vertices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColoringOrder {
+	/// The order the vertices were added to the graph.
+	Natural,
+	/// Decreasing degree, as in the Welsh–Powell algorithm.
+	LargestFirst,
+	/// The reverse of the order in which repeatedly removing a vertex of minimum degree removes
+	/// them. Uses at most one color more than the degeneracy of the graph.
+	SmallestLast,
+	/// DSatur: always the vertex with the most differently colored neighbors, with ties broken by
+	/// degree. Colors bipartite graphs, cycles and wheels optimally.
+	Saturation,
+}
+
+/// Builds the adjacency of a graph without self loops.
+fn simple_adjacency(index: &Indexed) -> Vec<Vec<usize>> {
+	index.adj.iter().enumerate()
+		.map(|(u, neighbors)| neighbors.iter().copied().filter(|&v| v != u).collect())
+		.collect()
+}
+
+/// Gives a vertex the lowest color none of its neighbors has.
+fn lowest_free_color(adj: &[Vec<usize>], colors: &[Option<usize>], u: usize) -> usize {
+	let mut taken = vec![false; adj[u].len() + 1];
+	for &v in &adj[u] {
+		if let Some(c) = colors[v].filter(|&c| c < taken.len()) {
+			taken[c] = true;
+		}
+	}
+	taken.iter().position(|&t| !t).unwrap()
+}
+
+/// Colors the vertices in DSatur order, each with its lowest free color.
+fn dsatur(adj: &[Vec<usize>]) -> Vec<Option<usize>> {
+	let n = adj.len();
+	let mut colors: Vec<Option<usize>> = vec![None; n];
+	let mut neighbor_colors: Vec<Vec<bool>> = vec![Vec::new(); n]; // The colors among the neighbors of each vertex
+	let mut saturation = vec![0; n];
+
+	for _ in 0..n {
+		let u = (0..n)
+			.filter(|&u| colors[u].is_none())
+			.max_by_key(|&u| (saturation[u], adj[u].len(), Reverse(u)))
+			.unwrap();
+
+		let color = lowest_free_color(adj, &colors, u);
+		colors[u] = Some(color);
+		for &v in &adj[u] {
+			if neighbor_colors[v].len() <= color {
+				neighbor_colors[v].resize(color + 1, false);
+			}
+			if !neighbor_colors[v][color] {
+				neighbor_colors[v][color] = true;
+				saturation[v] += 1;
+			}
+		}
+	}
+
+	colors
+}
+
+/// Maps the colors of an indexed graph back to vertex IDs.
+fn to_map(index: &Indexed, colors: &[Option<usize>]) -> HashMap<usize, usize> {
+	colors.iter().enumerate()
+		.filter_map(|(u, &c)| Some((index.ids[u], c?)))
+		.collect()
+}
+
+/// Colors a graph greedily.
+///
+/// The vertices are visited in the given order, and each one gets the lowest color that none of its
+/// neighbors has yet. This runs in `O(V + E)` time, plus the time to compute the order, but the
+/// number of colors depends heavily on the order and can be far from the minimum.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to color.
+/// - `order`: The order in which to color the vertices.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::coloring::{greedy_coloring, is_valid_coloring, ColoringOrder};
+///
+/// // A cycle of five vertices needs three colors
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+/// for i in 0..5 {
+///     graph.add_edge(v[i], v[(i + 1) % 5]);
+/// }
+///
+/// let coloring = greedy_coloring(&graph, ColoringOrder::Saturation);
+///
+/// assert!(is_valid_coloring(&graph, &coloring));
+/// assert_eq!(coloring.values().max(), Some(&2));
+/// ```
+pub fn greedy_coloring<T: Clone, G: Graph<T>>(graph: &G, order: ColoringOrder) -> HashMap<usize, usize> {
+	let index = Indexed::new(graph);
+	let adj = simple_adjacency(&index);
+	let n = index.len();
+
+	let order: Vec<usize> = match order {
+		ColoringOrder::Natural => (0..n).collect(),
+		ColoringOrder::LargestFirst => {
+			// The sort is stable, so equal degrees keep the order of the vertices
+			let mut order: Vec<usize> = (0..n).collect();
+			order.sort_by_key(|&u| Reverse(adj[u].len()));
+			order
+		}
+		ColoringOrder::SmallestLast => {
+			let mut order = degeneracy_order(&adj);
+			order.reverse();
+			order
+		}
+		ColoringOrder::Saturation => return to_map(&index, &dsatur(&adj)),
+	};
+
+	let mut colors: Vec<Option<usize>> = vec![None; n];
+	for u in order {
+		colors[u] = Some(lowest_free_color(&adj, &colors, u));
+	}
+
+	to_map(&index, &colors)
+}
+
+/// Tries to extend a partial coloring to all vertices with at most `k` colors, `used` of which are
+/// in use already. Colors the most saturated vertex next, and only ever opens one new color at a
+/// time, since the new colors are interchangeable.
+fn extend_coloring(adj: &[Vec<usize>], colors: &mut [Option<usize>], k: usize, used: usize) -> bool {
+	let n = adj.len();
+
+	// Pick the uncolored vertex with the most distinct neighbor colors
+	let mut best: Option<(usize, usize)> = None;
+	let mut seen = vec![usize::MAX; k];
+	for u in (0..n).filter(|&u| colors[u].is_none()) {
+		let mut saturation = 0;
+		for &v in &adj[u] {
+			if let Some(c) = colors[v] {
+				if seen[c] != u {
+					seen[c] = u;
+					saturation += 1;
+				}
+			}
+		}
+
+		if best.is_none_or(|(_, s)| saturation > s) {
+			best = Some((u, saturation));
+		}
+	}
+
+	let Some((u, _)) = best else { return true };
+	for color in 0..(used + 1).min(k) {
+		if adj[u].iter().all(|&v| colors[v] != Some(color)) {
+			colors[u] = Some(color);
+			if extend_coloring(adj, colors, k, used.max(color + 1)) {
+				return true;
+			}
+			colors[u] = None;
+		}
+	}
+
+	false
+}
+
+/// Colors a graph with the fewest possible colors.
+///
+/// Starts from a DSatur coloring, and then searches for colorings with fewer colors by
+/// backtracking until none exists. This takes exponential time in the worst case, so it is only
+/// suitable for small graphs (up to a few dozen vertices, depending on their structure).
+///
+/// # Arguments
+///
+/// - `graph`: The graph to color.
+pub fn exact_coloring<T: Clone, G: Graph<T>>(graph: &G) -> HashMap<usize, usize> {
+	let index = Indexed::new(graph);
+	let adj = simple_adjacency(&index);
+
+	let mut best = dsatur(&adj);
+	let mut count = best.iter().flatten().max().map_or(0, |&c| c + 1);
+
+	// Try one color less until that fails
+	while count > 1 {
+		let mut colors: Vec<Option<usize>> = vec![None; index.len()];
+		if !extend_coloring(&adj, &mut colors, count - 1, 0) {
+			break;
+		}
+		count -= 1;
+		best = colors;
+	}
+
+	to_map(&index, &best)
+}
+
+/// Computes the chromatic number of a graph: the fewest colors a coloring of the graph can use.
+///
+/// See [`exact_coloring`] for how it is found; this is only suitable for small graphs. A graph
+/// without vertices has a chromatic number of zero.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to compute the chromatic number of.
+pub fn chromatic_number<T: Clone, G: Graph<T>>(graph: &G) -> usize {
+	exact_coloring(graph).values().max().map_or(0, |&c| c + 1)
+}
+
+/// Checks whether a coloring of a graph is valid.
+///
+/// Returns `true` if every vertex of the graph has a color and no edge (other than a self loop)
+/// joins two vertices of the same color.
+///
+/// # Arguments
+///
+/// - `graph`: The graph that was colored.
+/// - `coloring`: The color of every vertex ID.
+pub fn is_valid_coloring<T: Clone, G: Graph<T>>(graph: &G, coloring: &HashMap<usize, usize>) -> bool {
+	graph.get_vertices().into_iter().all(|u| {
+		coloring.get(&u).is_some_and(|c| {
+			graph.get_neighbors(u).into_iter().all(|v| v == u || coloring.get(&v) != Some(c))
+		})
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::Rng;
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+
+	const ORDERS: [ColoringOrder; 4] = [
+		ColoringOrder::Natural,
+		ColoringOrder::LargestFirst,
+		ColoringOrder::SmallestLast,
+		ColoringOrder::Saturation,
+	];
+
+	/// Counts the colors used by a coloring.
+	fn color_count(coloring: &HashMap<usize, usize>) -> usize {
+		coloring.values().max().map_or(0, |&c| c + 1)
+	}
+
+	/// Builds the Petersen graph, which has chromatic number 3.
+	fn petersen() -> UndirectedGraph<()> {
+		let mut graph = UndirectedGraph::new();
+		let v: Vec<usize> = (0..10).map(|_| graph.add_vertex(())).collect();
+		for i in 0..5 {
+			graph.add_edge(v[i], v[(i + 1) % 5]);
+			graph.add_edge(v[i], v[i + 5]);
+			graph.add_edge(v[i + 5], v[(i + 2) % 5 + 5]);
+		}
+		graph
+	}
+
+	#[test]
+	fn test_greedy_coloring() {
+		let graph = petersen();
+		for order in ORDERS {
+			let coloring = greedy_coloring(&graph, order);
+			assert!(is_valid_coloring(&graph, &coloring));
+			assert!(color_count(&coloring) <= 4);
+		}
+	}
+
+	#[test]
+	fn test_greedy_coloring_crown() {
+		// A crown graph with the vertices in an order that makes the natural order use a new color
+		// for every pair, while it is bipartite
+		let n = 6;
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let mut a = Vec::new();
+		let mut b = Vec::new();
+		for _ in 0..n {
+			a.push(graph.add_vertex(()));
+			b.push(graph.add_vertex(()));
+		}
+		for (i, &x) in a.iter().enumerate() {
+			for (j, &y) in b.iter().enumerate() {
+				if i != j {
+					graph.add_edge(x, y);
+				}
+			}
+		}
+
+		assert_eq!(color_count(&greedy_coloring(&graph, ColoringOrder::Natural)), n);
+		assert_eq!(color_count(&greedy_coloring(&graph, ColoringOrder::Saturation)), 2);
+		assert_eq!(chromatic_number(&graph), 2);
+	}
+
+	#[test]
+	fn test_chromatic_number() {
+		assert_eq!(chromatic_number(&petersen()), 3);
+
+		// Complete graphs need a color for every vertex
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+		for i in 0..5 {
+			for j in i + 1..5 {
+				graph.add_edge(v[i], v[j]);
+			}
+		}
+		assert_eq!(chromatic_number(&graph), 5);
+
+		// Removing an edge lets its ends share a color
+		graph.remove_edge(v[0], v[1]);
+		let coloring = exact_coloring(&graph);
+		assert!(is_valid_coloring(&graph, &coloring));
+		assert_eq!(coloring[&v[0]], coloring[&v[1]]);
+		assert_eq!(color_count(&coloring), 4);
+
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		assert_eq!(chromatic_number(&graph), 0);
+		let v1 = graph.add_vertex(());
+		assert_eq!(chromatic_number(&graph), 1);
+		graph.add_edge(v1, v1);
+		assert_eq!(chromatic_number(&graph), 1);
+	}
+
+	#[test]
+	fn test_chromatic_number_triangle_free() {
+		// The Grötzsch graph has no triangles, but still needs four colors
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let u: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+		let w: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+		let z = graph.add_vertex(());
+		for i in 0..5 {
+			let (previous, next) = ((i + 4) % 5, (i + 1) % 5);
+			graph.add_edge(u[i], u[next]);
+			graph.add_edge(w[i], u[previous]);
+			graph.add_edge(w[i], u[next]);
+			graph.add_edge(w[i], z);
+		}
+
+		let coloring = exact_coloring(&graph);
+		assert!(is_valid_coloring(&graph, &coloring));
+		assert_eq!(color_count(&coloring), 4);
+	}
+
+	#[test]
+	fn test_chromatic_number_random() {
+		// Compare with trying every assignment of colors on small random graphs
+		let mut rng = Rng::new(1);
+
+		for _ in 0..50 {
+			let n = 1 + rng.below(7);
+			let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+			let v: Vec<usize> = (0..n).map(|_| graph.add_vertex(())).collect();
+			for a in 0..n {
+				for b in a + 1..n {
+					if rng.below(2) == 0 {
+						graph.add_edge(v[a], v[b]);
+					}
+				}
+			}
+
+			let colorable = |k: usize| {
+				(0..k.pow(n as u32)).any(|code| {
+					let coloring: HashMap<usize, usize> = (0..n).map(|i| (v[i], code / k.pow(i as u32) % k)).collect();
+					is_valid_coloring(&graph, &coloring)
+				})
+			};
+			let expected = (1..=n).find(|&k| colorable(k)).unwrap();
+			assert_eq!(chromatic_number(&graph), expected);
+		}
+	}
+
+	#[test]
+	fn test_is_valid_coloring() {
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v1 = graph.add_vertex(());
+		let v2 = graph.add_vertex(());
+		let v3 = graph.add_vertex(());
+		graph.add_edge(v1, v2);
+		graph.add_edge(v3, v3);
+
+		assert!(is_valid_coloring(&graph, &HashMap::from([(v1, 0), (v2, 1), (v3, 0)])));
+		assert!(!is_valid_coloring(&graph, &HashMap::from([(v1, 0), (v2, 0), (v3, 0)])));
+		assert!(!is_valid_coloring(&graph, &HashMap::from([(v1, 0), (v2, 1)])));
+	}
+}
@@ -0,0 +1,691 @@
+//! Community detection: the Louvain and Leiden methods, label propagation and modularity.
+//!
+//! A partition of a graph is returned as a vector of communities, each with the IDs of its vertices
+//! in the order they were added to the graph. The communities are ordered by their first vertex.
+//! The algorithms are randomized, and take a seed so that their results are reproducible.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::util::{Indexed, Rng};
+use super::Graph;
+
+/// The randomness of the merges in the refinement phase of the Leiden method. Lower values make the
+/// refinement greedier.
+const LEIDEN_RANDOMNESS: f64 = 0.01;
+
+/// A graph with weighted edges between communities of the original vertices, as the Louvain and
+/// Leiden methods aggregate them.
+struct Network {
+	/// The neighbors of each node, with the weights of the edges to them. Self loops are left out.
+	adj: Vec<Vec<(usize, f64)>>,
+	/// The weight of the self loop of each node, counting every edge within it from both ends.
+	loops: Vec<f64>,
+	/// The total weight of the edges at each node, including its self loop.
+	strength: Vec<f64>,
+	/// The total strength of all nodes, which is twice the total edge weight.
+	total: f64,
+}
+
+impl Network {
+	/// Builds the network of a graph, where every edge has weight one. A self loop has both ends at
+	/// its vertex, so it adds two to the strength of the vertex, like to its degree.
+	fn new(index: &Indexed) -> Self {
+		let mut adj = vec![Vec::new(); index.len()];
+		let mut loops = vec![0.0; index.len()];
+		for (u, neighbors) in index.adj.iter().enumerate() {
+			for &v in neighbors {
+				if u == v {
+					loops[u] += 2.0;
+				} else {
+					adj[u].push((v, 1.0));
+				}
+			}
+		}
+		Self::with_edges(adj, loops)
+	}
+
+	fn with_edges(adj: Vec<Vec<(usize, f64)>>, loops: Vec<f64>) -> Self {
+		let strength: Vec<f64> = adj.iter().zip(&loops)
+			.map(|(neighbors, &l)| l + neighbors.iter().map(|&(_, w)| w).sum::<f64>())
+			.collect();
+		let total = strength.iter().sum();
+		Self { adj, loops, strength, total }
+	}
+
+	fn len(&self) -> usize {
+		self.adj.len()
+	}
+
+	/// Merges the nodes of each community into a single node.
+	fn aggregate(&self, community: &[usize], count: usize) -> Self {
+		let mut edges: Vec<HashMap<usize, f64>> = vec![HashMap::new(); count];
+		let mut loops = vec![0.0; count];
+		for u in 0..self.len() {
+			let c = community[u];
+			loops[c] += self.loops[u];
+			for &(v, w) in &self.adj[u] {
+				if community[v] == c {
+					loops[c] += w;
+				} else {
+					*edges[c].entry(community[v]).or_insert(0.0) += w;
+				}
+			}
+		}
+
+		let adj = edges.into_iter()
+			.map(|neighbors| {
+				let mut neighbors: Vec<(usize, f64)> = neighbors.into_iter().collect();
+				neighbors.sort_unstable_by_key(|&(v, _)| v);
+				neighbors
+			})
+			.collect();
+		Self::with_edges(adj, loops)
+	}
+
+	/// Adds up the weights of the edges from a node to each community it is adjacent to, in
+	/// `weights`, and lists those communities in `touched`.
+	fn community_weights(&self, u: usize, community: &[usize], weights: &mut [f64], touched: &mut Vec<usize>) {
+		for &c in touched.iter() {
+			weights[c] = 0.0;
+		}
+		touched.clear();
+		for &(v, w) in &self.adj[u] {
+			let c = community[v];
+			if weights[c] == 0.0 {
+				touched.push(c);
+			}
+			weights[c] += w;
+		}
+	}
+}
+
+/// Numbers the communities `0..count` in order of their first node, and returns the count.
+fn renumber(community: &mut [usize]) -> usize {
+	let mut labels: HashMap<usize, usize> = HashMap::new();
+	for c in community.iter_mut() {
+		let next = labels.len();
+		*c = *labels.entry(*c).or_insert(next);
+	}
+	labels.len()
+}
+
+/// Moves single nodes to the neighboring community that increases the modularity the most, in
+/// passes over all nodes in a random order, until no move increases it. Returns whether any node
+/// moved.
+fn move_nodes(network: &Network, community: &mut [usize], resolution: f64, rng: &mut Rng) -> bool {
+	let n = network.len();
+	let mut totals = vec![0.0; n];
+	for u in 0..n {
+		totals[community[u]] += network.strength[u];
+	}
+
+	let mut order: Vec<usize> = (0..n).collect();
+	let mut weights = vec![0.0; n];
+	let mut touched = Vec::new();
+	let mut moved = false;
+	loop {
+		rng.shuffle(&mut order);
+		let mut changed = false;
+		for &u in &order {
+			let (current, k) = (community[u], network.strength[u]);
+			network.community_weights(u, community, &mut weights, &mut touched);
+			totals[current] -= k;
+
+			// The gain of joining a community, compared to being alone
+			let gain = |c: usize| weights[c] - resolution * totals[c] * k / network.total;
+			let mut best = current;
+			let mut best_gain = gain(current);
+			for &c in &touched {
+				if gain(c) > best_gain {
+					best = c;
+					best_gain = gain(c);
+				}
+			}
+
+			totals[best] += k;
+			if best != current {
+				community[u] = best;
+				changed = true;
+			}
+		}
+
+		if !changed {
+			return moved;
+		}
+		moved = true;
+	}
+}
+
+/// Moves single nodes to the neighboring community that increases the modularity the most, going
+/// through a queue that starts with all nodes in a random order and gets the neighbors of every
+/// node that moves added back.
+fn move_nodes_fast(network: &Network, community: &mut [usize], resolution: f64, rng: &mut Rng) {
+	let n = network.len();
+	let mut totals = vec![0.0; n];
+	let mut sizes = vec![0usize; n];
+	for u in 0..n {
+		totals[community[u]] += network.strength[u];
+		sizes[community[u]] += 1;
+	}
+	let mut empty: Vec<usize> = (0..n).filter(|&c| sizes[c] == 0).collect();
+
+	let mut queue: VecDeque<usize> = {
+		let mut order: Vec<usize> = (0..n).collect();
+		rng.shuffle(&mut order);
+		order.into()
+	};
+	let mut queued = vec![true; n];
+	let mut weights = vec![0.0; n];
+	let mut touched = Vec::new();
+
+	while let Some(u) = queue.pop_front() {
+		queued[u] = false;
+		let (current, k) = (community[u], network.strength[u]);
+		network.community_weights(u, community, &mut weights, &mut touched);
+		totals[current] -= k;
+		sizes[current] -= 1;
+		if sizes[current] == 0 {
+			empty.push(current);
+		}
+
+		// Consider the neighboring communities and an empty one
+		let gain = |c: usize| weights[c] - resolution * totals[c] * k / network.total;
+		let mut best = current;
+		let mut best_gain = gain(current);
+		for &c in touched.iter().chain(empty.last()) {
+			if gain(c) > best_gain {
+				best = c;
+				best_gain = gain(c);
+			}
+		}
+
+		// A community that was empty is always the last in the list
+		totals[best] += k;
+		sizes[best] += 1;
+		if sizes[best] == 1 {
+			empty.pop();
+		}
+
+		if best != current {
+			community[u] = best;
+			for &(v, _) in &network.adj[u] {
+				if !queued[v] && community[v] != best {
+					queued[v] = true;
+					queue.push_back(v);
+				}
+			}
+		}
+	}
+}
+
+/// Refines a partition for the Leiden method: splits every community into subcommunities by
+/// merging single nodes, starting from singletons, into subcommunities that are well connected to
+/// the rest of their community, picking randomly among the merges that don't decrease the
+/// modularity. Every subcommunity is connected.
+fn refine(network: &Network, community: &[usize], resolution: f64, rng: &mut Rng) -> Vec<usize> {
+	let n = network.len();
+	let mut refined: Vec<usize> = (0..n).collect();
+	let mut sizes = vec![1usize; n];
+	let mut totals = network.strength.clone();
+
+	// The strength of each community, and the weight of the edges from each subcommunity to the
+	// rest of its community
+	let mut community_totals = vec![0.0; n];
+	let mut external = vec![0.0; n];
+	for u in 0..n {
+		community_totals[community[u]] += network.strength[u];
+		external[u] = network.adj[u].iter().filter(|&&(v, _)| community[v] == community[u]).map(|&(_, w)| w).sum();
+	}
+	let well_connected = |external: f64, total: f64, community_total: f64| {
+		external >= resolution * total * (community_total - total) / network.total
+	};
+
+	let mut order: Vec<usize> = (0..n).collect();
+	rng.shuffle(&mut order);
+	let mut weights = vec![0.0; n];
+	let mut touched = Vec::new();
+	let mut candidates: Vec<(usize, f64)> = Vec::new();
+	for &u in &order {
+		let (k, community_total) = (network.strength[u], community_totals[community[u]]);
+		if sizes[refined[u]] > 1 || !well_connected(external[u], k, community_total) {
+			continue;
+		}
+
+		network.community_weights(u, &refined, &mut weights, &mut touched);
+		candidates.clear();
+		candidates.push((u, 0.0));
+		for &c in &touched {
+			// Each subcommunity is named after a node in it, so it is in the same community as that node
+			if c == u || community[c] != community[u] || !well_connected(external[c], totals[c], community_total) {
+				continue;
+			}
+			let gain = weights[c] - resolution * totals[c] * k / network.total;
+			if gain >= 0.0 {
+				candidates.push((c, gain));
+			}
+		}
+
+		// Pick a subcommunity with a probability that grows exponentially with the gain
+		let best_gain = candidates.iter().map(|&(_, gain)| gain).fold(0.0, f64::max);
+		let odds: Vec<f64> = candidates.iter().map(|&(_, gain)| ((gain - best_gain) / LEIDEN_RANDOMNESS).exp()).collect();
+		let mut pick = rng.next_f64() * odds.iter().sum::<f64>();
+		let mut chosen = candidates[candidates.len() - 1].0;
+		for (&(c, _), &odd) in candidates.iter().zip(&odds) {
+			if pick < odd {
+				chosen = c;
+				break;
+			}
+			pick -= odd;
+		}
+
+		if chosen != u {
+			refined[u] = chosen;
+			sizes[u] = 0;
+			sizes[chosen] += 1;
+			totals[chosen] += k;
+			external[chosen] += external[u] - 2.0 * weights[chosen];
+		}
+	}
+
+	refined
+}
+
+/// Collects the vertices of each community, ordered by their first vertex.
+fn to_communities(index: &Indexed, membership: &[usize]) -> Vec<Vec<usize>> {
+	let mut membership = membership.to_vec();
+	let mut communities = vec![Vec::new(); renumber(&mut membership)];
+	for (u, &c) in membership.iter().enumerate() {
+		communities[c].push(index.ids[u]);
+	}
+	communities
+}
+
+/// Finds communities in a graph with the Louvain method: moves single vertices to the neighboring
+/// community that increases the modularity the most until no move does, then merges each community
+/// into a single vertex and repeats on the merged graph.
+///
+/// The resolution sets the size of the communities: higher resolutions give more and smaller
+/// communities, and a resolution of one maximizes the usual modularity. The communities may turn
+/// out to be internally disconnected, which [`leiden`] avoids. Each pass over the vertices takes
+/// `O(V + E)` time, and the method usually runs in `O(E log V)` time.
+///
+/// Returns the communities, each with the IDs of its vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find communities in.
+/// - `resolution`: The resolution of the modularity, usually one.
+/// - `seed`: The seed for the random order in which the vertices are moved.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::community::louvain;
+///
+/// // Two triangles joined by an edge
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..6).map(|_| graph.add_vertex(())).collect();
+/// for (a, b) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+///     graph.add_edge(v[a], v[b]);
+/// }
+///
+/// assert_eq!(louvain(&graph, 1.0, 7), vec![vec![v[0], v[1], v[2]], vec![v[3], v[4], v[5]]]);
+/// ```
+pub fn louvain<T: Clone, G: Graph<T>>(graph: &G, resolution: f64, seed: u64) -> Vec<Vec<usize>> {
+	let index = Indexed::new(graph);
+	let mut rng = Rng::new(seed);
+	let mut network = Network::new(&index);
+	let mut membership: Vec<usize> = (0..index.len()).collect();
+
+	loop {
+		let mut community: Vec<usize> = (0..network.len()).collect();
+		if !move_nodes(&network, &mut community, resolution, &mut rng) {
+			break;
+		}
+
+		let count = renumber(&mut community);
+		membership.iter_mut().for_each(|c| *c = community[*c]);
+		network = network.aggregate(&community, count);
+	}
+
+	to_communities(&index, &membership)
+}
+
+/// Finds communities in a graph with the Leiden method, which improves on the Louvain method by
+/// refining the communities before merging them, so that every community is connected.
+///
+/// Like [`louvain`], moves vertices between communities to increase the modularity, but revisits
+/// only the neighbors of vertices that moved. Then it splits every community into well connected
+/// subcommunities, and merges each subcommunity into a single vertex for the next round, starting
+/// from the unrefined communities. Usually finds communities at least as good as the Louvain
+/// method, in similar time.
+///
+/// Returns the communities, each with the IDs of its vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find communities in.
+/// - `resolution`: The resolution of the modularity, usually one. Higher resolutions give more and
+///   smaller communities.
+/// - `seed`: The seed for the random order of the vertices and the random merges.
+pub fn leiden<T: Clone, G: Graph<T>>(graph: &G, resolution: f64, seed: u64) -> Vec<Vec<usize>> {
+	let index = Indexed::new(graph);
+	let mut rng = Rng::new(seed);
+	let mut network = Network::new(&index);
+	let mut membership: Vec<usize> = (0..index.len()).collect();
+	let mut community: Vec<usize> = (0..network.len()).collect();
+
+	loop {
+		move_nodes_fast(&network, &mut community, resolution, &mut rng);
+		let count = renumber(&mut community);
+		if count == network.len() {
+			break;
+		}
+
+		let mut refined = refine(&network, &community, resolution, &mut rng);
+		let refined_count = renumber(&mut refined);
+		if refined_count == network.len() {
+			break;
+		}
+
+		// Start the merged graph from the unrefined communities
+		let mut merged_community = vec![0; refined_count];
+		for u in 0..network.len() {
+			merged_community[refined[u]] = community[u];
+		}
+		membership.iter_mut().for_each(|c| *c = refined[*c]);
+		network = network.aggregate(&refined, refined_count);
+		community = merged_community;
+	}
+
+	membership.iter_mut().for_each(|c| *c = community[*c]);
+	to_communities(&index, &membership)
+}
+
+/// Finds communities in a graph by label propagation: every vertex starts with its own label, and
+/// in rounds over the vertices in a random order, each vertex takes the label that is most common
+/// among its neighbors, until every vertex has such a label.
+///
+/// Ties are broken randomly, except that a vertex keeps its label if it is among the most common.
+/// Each round takes `O(V + E)` time, and few rounds are usually needed. The method needs no
+/// parameters, but the communities it finds vary more between seeds than those of [`louvain`].
+///
+/// Returns the communities, each with the IDs of its vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find communities in.
+/// - `seed`: The seed for the random order of the vertices and the tie breaks.
+pub fn label_propagation<T: Clone, G: Graph<T>>(graph: &G, seed: u64) -> Vec<Vec<usize>> {
+	let index = Indexed::new(graph);
+	let n = index.len();
+	let mut rng = Rng::new(seed);
+	let mut label: Vec<usize> = (0..n).collect();
+	let mut order: Vec<usize> = (0..n).collect();
+	let mut counts = vec![0usize; n];
+	let mut touched = Vec::new();
+	let mut best = Vec::new();
+
+	loop {
+		rng.shuffle(&mut order);
+		let mut changed = false;
+		for &u in &order {
+			for &v in index.adj[u].iter().filter(|&&v| v != u) {
+				if counts[label[v]] == 0 {
+					touched.push(label[v]);
+				}
+				counts[label[v]] += 1;
+			}
+
+			let most = touched.iter().map(|&l| counts[l]).max().unwrap_or(0);
+			best.clear();
+			best.extend(touched.iter().copied().filter(|&l| counts[l] == most));
+			if !best.is_empty() && counts[label[u]] < most {
+				label[u] = best[rng.below(best.len())];
+				changed = true;
+			}
+
+			touched.drain(..).for_each(|l| counts[l] = 0);
+		}
+
+		if !changed {
+			break;
+		}
+	}
+
+	to_communities(&index, &label)
+}
+
+/// Computes the modularity of a partition of a graph: the fraction of the edges that fall within
+/// communities, minus the fraction expected if the edges were placed at random between vertices of
+/// the same degrees.
+///
+/// The modularity is at most one, and is zero for the partition into a single community.
+///
+/// Returns `None` if the communities don't contain every vertex of the graph exactly once, or if
+/// the graph has no edges.
+///
+/// # Arguments
+///
+/// - `graph`: The graph the partition is of.
+/// - `partition`: The communities, each with the IDs of its vertices.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::community::modularity;
+///
+/// // Two triangles joined by an edge
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..6).map(|_| graph.add_vertex(())).collect();
+/// for (a, b) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+///     graph.add_edge(v[a], v[b]);
+/// }
+///
+/// let triangles = modularity(&graph, &[vec![v[0], v[1], v[2]], vec![v[3], v[4], v[5]]]).unwrap();
+///
+/// assert!((triangles - 5.0 / 14.0).abs() < 1e-12);
+/// assert_eq!(modularity(&graph, &[v.clone()]), Some(0.0));
+/// ```
+pub fn modularity<T: Clone, G: Graph<T>>(graph: &G, partition: &[Vec<usize>]) -> Option<f64> {
+	let index = Indexed::new(graph);
+	let mut community: Vec<Option<usize>> = vec![None; index.len()];
+	for (c, members) in partition.iter().enumerate() {
+		for id in members {
+			let u = *index.index.get(id)?;
+			if community[u].replace(c).is_some() {
+				return None;
+			}
+		}
+	}
+	let community: Vec<usize> = community.into_iter().collect::<Option<_>>()?;
+
+	let network = Network::new(&index);
+	if network.total == 0.0 {
+		return None;
+	}
+	let merged = network.aggregate(&community, partition.len());
+	Some(merged.loops.iter().zip(&merged.strength)
+		.map(|(&inside, &total)| inside / network.total - (total / network.total).powi(2))
+		.sum())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::UndirectedSparseGraph;
+
+	/// Builds a ring of cliques, each joined to the next by a single edge.
+	fn ring_of_cliques(cliques: usize, size: usize) -> (UndirectedSparseGraph<()>, Vec<Vec<usize>>) {
+		let mut graph = UndirectedSparseGraph::new();
+		let groups: Vec<Vec<usize>> = (0..cliques).map(|_| (0..size).map(|_| graph.add_vertex(())).collect()).collect();
+		for (i, group) in groups.iter().enumerate() {
+			for a in 0..size {
+				for b in a + 1..size {
+					graph.add_edge(group[a], group[b]);
+				}
+			}
+			graph.add_edge(group[0], groups[(i + 1) % cliques][size - 1]);
+		}
+		(graph, groups)
+	}
+
+	/// Generates a graph with dense groups of vertices and few edges between them.
+	fn planted_partition(groups: usize, size: usize, seed: u64) -> (UndirectedSparseGraph<()>, Vec<Vec<usize>>) {
+		let mut rng = Rng::new(seed);
+		let mut graph = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..groups * size).map(|_| graph.add_vertex(())).collect();
+		for a in 0..v.len() {
+			for b in a + 1..v.len() {
+				let percent = if a / size == b / size { 40 } else { 1 };
+				if rng.below(100) < percent {
+					graph.add_edge(v[a], v[b]);
+				}
+			}
+		}
+		(graph, v.chunks(size).map(|c| c.to_vec()).collect())
+	}
+
+	/// Checks that every community induces a connected subgraph.
+	fn assert_connected(graph: &UndirectedSparseGraph<()>, communities: &[Vec<usize>]) {
+		for members in communities {
+			let mut reached = vec![members[0]];
+			let mut stack = vec![members[0]];
+			while let Some(u) = stack.pop() {
+				for v in graph.get_neighbors(u) {
+					if members.contains(&v) && !reached.contains(&v) {
+						reached.push(v);
+						stack.push(v);
+					}
+				}
+			}
+			assert_eq!(reached.len(), members.len());
+		}
+	}
+
+	#[test]
+	fn test_modularity() {
+		let (graph, groups) = ring_of_cliques(4, 4);
+		let v = graph.get_vertices();
+
+		// Every clique has 12 of the 2 * 28 edge ends inside, and 14 in total
+		let expected = 4.0 * (12.0 / 56.0 - (14.0f64 / 56.0).powi(2));
+		assert!((modularity(&graph, &groups).unwrap() - expected).abs() < 1e-12);
+		let singletons: Vec<Vec<usize>> = v.iter().map(|&u| vec![u]).collect();
+		assert!(modularity(&graph, &singletons).unwrap() < 0.0);
+
+		// Missing, repeated and unknown vertices
+		assert_eq!(modularity(&graph, &groups[1..]), None);
+		assert_eq!(modularity(&graph, &[v.clone(), vec![v[0]]]), None);
+		assert_eq!(modularity(&graph, &[v.clone(), vec![1000]]), None);
+
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let a = graph.add_vertex(());
+		assert_eq!(modularity(&graph, &[vec![a]]), None);
+	}
+
+	#[test]
+	fn test_modularity_self_loop() {
+		// An edge and a self loop, which adds two edge ends to its vertex: the strengths are 3 and 1
+		// out of 4, and the loop has both ends inside its community
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let a = graph.add_vertex(());
+		let b = graph.add_vertex(());
+		graph.add_edge(a, b);
+		graph.add_edge(a, a);
+
+		let expected = 2.0 / 4.0 - (3.0f64 / 4.0).powi(2) - (1.0f64 / 4.0).powi(2);
+		assert!((expected + 0.125).abs() < 1e-12);
+		assert!((modularity(&graph, &[vec![a], vec![b]]).unwrap() - expected).abs() < 1e-12);
+		assert!(modularity(&graph, &[vec![a, b]]).unwrap().abs() < 1e-12);
+	}
+
+	#[test]
+	fn test_ring_of_cliques() {
+		let (graph, groups) = ring_of_cliques(8, 5);
+		for seed in 0..5 {
+			assert_eq!(louvain(&graph, 1.0, seed), groups);
+			assert_eq!(leiden(&graph, 1.0, seed), groups);
+
+			// Label propagation may merge neighboring cliques, but never splits one
+			let communities = label_propagation(&graph, seed);
+			assert!(groups.iter().all(|group| communities.iter().any(|c| group.iter().all(|u| c.contains(u)))));
+		}
+	}
+
+	#[test]
+	fn test_planted_partition() {
+		for seed in 1..6 {
+			let (graph, groups) = planted_partition(4, 25, seed);
+			let louvain_communities = louvain(&graph, 1.0, seed);
+			let leiden_communities = leiden(&graph, 1.0, seed);
+			assert_eq!(louvain_communities, groups);
+			assert_eq!(leiden_communities, groups);
+			assert_connected(&graph, &leiden_communities);
+		}
+	}
+
+	#[test]
+	fn test_resolution() {
+		let (graph, groups) = ring_of_cliques(6, 4);
+		let v = graph.get_vertices();
+
+		// A very low resolution puts everything together, and a very high one splits everything
+		assert_eq!(louvain(&graph, 0.001, 1), vec![v.clone()]);
+		assert_eq!(leiden(&graph, 0.001, 1), vec![v.clone()]);
+		assert_eq!(louvain(&graph, 100.0, 1).len(), v.len());
+		assert_eq!(leiden(&graph, 100.0, 1).len(), v.len());
+
+		// Pairs of cliques merge at a lower resolution
+		let louvain_pairs = louvain(&graph, 0.3, 1);
+		assert!(louvain_pairs.len() < groups.len());
+		assert_connected(&graph, &leiden(&graph, 0.3, 1));
+	}
+
+	#[test]
+	fn test_random_graphs() {
+		// The results are reproducible, valid partitions, and the Leiden communities are connected
+		let mut rng = Rng::new(1);
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..300).map(|_| graph.add_vertex(())).collect();
+		for _ in 0..900 {
+			graph.add_edge(v[rng.below(300)], v[rng.below(300)]);
+		}
+
+		for seed in 0..3 {
+			let louvain_communities = louvain(&graph, 1.0, seed);
+			let leiden_communities = leiden(&graph, 1.0, seed);
+			let label_communities = label_propagation(&graph, seed);
+			assert_eq!(louvain(&graph, 1.0, seed), louvain_communities);
+			assert_eq!(leiden(&graph, 1.0, seed), leiden_communities);
+			assert_eq!(label_propagation(&graph, seed), label_communities);
+
+			assert_connected(&graph, &leiden_communities);
+			assert_connected(&graph, &label_communities);
+			let louvain_modularity = modularity(&graph, &louvain_communities).unwrap();
+			let leiden_modularity = modularity(&graph, &leiden_communities).unwrap();
+			assert!(louvain_modularity > 0.35);
+			assert!(leiden_modularity > louvain_modularity - 0.02);
+			assert!(modularity(&graph, &label_communities).unwrap() > 0.0);
+		}
+	}
+
+	#[test]
+	fn test_empty_and_isolated() {
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		assert!(louvain(&graph, 1.0, 0).is_empty());
+		assert!(leiden(&graph, 1.0, 0).is_empty());
+		assert!(label_propagation(&graph, 0).is_empty());
+
+		let a = graph.add_vertex(());
+		let b = graph.add_vertex(());
+		let c = graph.add_vertex(());
+		graph.add_edge(a, b);
+		let expected = vec![vec![a, b], vec![c]];
+		assert_eq!(louvain(&graph, 1.0, 0), expected);
+		assert_eq!(leiden(&graph, 1.0, 0), expected);
+		assert_eq!(label_propagation(&graph, 0), expected);
+	}
+}
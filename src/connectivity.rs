@@ -0,0 +1,408 @@
+//! Connected components, bridges and articulation points.
+
+use std::collections::HashMap;
+
+use super::util::Indexed;
+use super::{Graph, UnionFind};
+
+/// Finds the connected components of a graph.
+///
+/// Returns a vector with the IDs of the vertices in each component. Components are ordered by their
+/// first vertex, and the vertices within each component are in the order they were added to the
+/// graph.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the components of.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::connectivity::connected_components;
+///
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v1 = graph.add_vertex(());
+/// let v2 = graph.add_vertex(());
+/// let v3 = graph.add_vertex(());
+///
+/// graph.add_edge(v1, v3);
+///
+/// assert_eq!(connected_components(&graph), vec![vec![v1, v3], vec![v2]]);
+/// ```
+pub fn connected_components<T: Clone, G: Graph<T>>(graph: &G) -> Vec<Vec<usize>> {
+	let index = Indexed::new(graph);
+
+	// Merge the endpoints of every edge
+	let mut sets = UnionFind::new(index.len());
+	for (u, neighbors) in index.adj.iter().enumerate() {
+		for &v in neighbors {
+			sets.union(u, v);
+		}
+	}
+
+	// Group the vertices by their representative, in order of first appearance
+	let mut components: Vec<Vec<usize>> = Vec::with_capacity(sets.set_count());
+	let mut positions: HashMap<usize, usize> = HashMap::new();
+	for (u, &id) in index.ids.iter().enumerate() {
+		let root = sets.find(u);
+		let position = *positions.entry(root).or_insert_with(|| {
+			components.push(Vec::new());
+			components.len() - 1
+		});
+		components[position].push(id);
+	}
+
+	components
+}
+
+/// Labels every vertex with the connected component it belongs to.
+///
+/// Returns a map from vertex ID to component label. The labels are the positions of the components
+/// in the result of [`connected_components`].
+///
+/// # Arguments
+///
+/// - `graph`: The graph to label the components of.
+pub fn component_labels<T: Clone, G: Graph<T>>(graph: &G) -> HashMap<usize, usize> {
+	connected_components(graph).into_iter()
+		.enumerate()
+		.flat_map(|(label, component)| component.into_iter().map(move |id| (id, label)))
+		.collect()
+}
+
+/// Determines whether a graph is connected.
+///
+/// Returns `true` if there is a path between every pair of vertices. A graph without vertices is
+/// considered connected.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to test.
+pub fn is_connected<T: Clone, G: Graph<T>>(graph: &G) -> bool {
+	connected_components(graph).len() <= 1
+}
+
+/// The cut structure of a graph, in terms of vertex indices.
+struct Biconnectivity {
+	bridges: Vec<(usize, usize)>,
+	articulation_points: Vec<usize>,
+	components: Vec<Vec<(usize, usize)>>,
+}
+
+/// Finds the bridges, articulation points and biconnected components of an indexed graph with
+/// Tarjan's algorithm. The depth-first search uses an explicit stack, so deep graphs can't overflow
+/// the call stack.
+fn biconnectivity(index: &Indexed) -> Biconnectivity {
+	let n = index.len();
+
+	let mut discovery: Vec<Option<usize>> = vec![None; n]; // The DFS preorder number of each vertex
+	let mut low: Vec<usize> = vec![0; n]; // The lowest preorder number reachable through a back edge
+	let mut is_articulation = vec![false; n];
+	let mut time = 0;
+
+	let mut bridges = Vec::new();
+	let mut components = Vec::new();
+	let mut edges: Vec<(usize, usize)> = Vec::new(); // The edges of the blocks still being explored
+
+	for root in 0..n {
+		if discovery[root].is_some() {
+			continue;
+		}
+
+		discovery[root] = Some(time);
+		low[root] = time;
+		time += 1;
+
+		let mut root_children = 0;
+		let mut stack: Vec<(usize, usize)> = vec![(root, 0)]; // The vertex and its next neighbor to visit
+
+		while let Some(&(u, next)) = stack.last() {
+			let parent = stack.len().checked_sub(2).map(|i| stack[i].0);
+
+			if let Some(&v) = index.adj[u].get(next) {
+				stack.last_mut().unwrap().1 += 1;
+
+				// Ignore self loops and the edge back to the parent
+				if v == u || Some(v) == parent {
+					continue;
+				}
+
+				match discovery[v] {
+					None => {
+						// Tree edge
+						discovery[v] = Some(time);
+						low[v] = time;
+						time += 1;
+						edges.push((u, v));
+						stack.push((v, 0));
+					}
+					Some(d) if d < discovery[u].unwrap() => {
+						// Back edge to an ancestor
+						low[u] = low[u].min(d);
+						edges.push((u, v));
+					}
+					Some(_) => {
+						// The other side of a back edge, which was already recorded
+					}
+				}
+			} else {
+				stack.pop();
+				let Some(p) = parent else { continue; };
+
+				low[p] = low[p].min(low[u]);
+
+				// No back edge from the subtree of u reaches above p
+				if low[u] >= discovery[p].unwrap() {
+					if p == root {
+						root_children += 1;
+					} else {
+						is_articulation[p] = true;
+					}
+
+					// The edges added since (p, u) form a block
+					let start = edges.iter().rposition(|&e| e == (p, u)).unwrap();
+					components.push(edges.split_off(start));
+
+					// Not even p itself is reachable, so (p, u) is the only edge of its block
+					if low[u] > discovery[p].unwrap() {
+						bridges.push((p, u));
+					}
+				}
+			}
+		}
+
+		if root_children >= 2 {
+			is_articulation[root] = true;
+		}
+	}
+
+	Biconnectivity {
+		bridges,
+		articulation_points: (0..n).filter(|&u| is_articulation[u]).collect(),
+		components,
+	}
+}
+
+/// Finds the bridges of a graph using Tarjan's algorithm.
+///
+/// A bridge is an edge whose removal disconnects its endpoints. Returns the bridges as pairs of
+/// vertex IDs, in the order the depth-first search finishes them.
+///
+/// The search is iterative, so it handles deep graphs (such as long paths) without overflowing the
+/// stack.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the bridges of.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedSparseGraph};
+/// use istos::connectivity::bridges;
+///
+/// let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+/// let v1 = graph.add_vertex(());
+/// let v2 = graph.add_vertex(());
+/// let v3 = graph.add_vertex(());
+/// let v4 = graph.add_vertex(());
+///
+/// // A triangle with a pendant vertex
+/// graph.add_edge(v1, v2);
+/// graph.add_edge(v2, v3);
+/// graph.add_edge(v3, v1);
+/// graph.add_edge(v3, v4);
+///
+/// assert_eq!(bridges(&graph), vec![(v3, v4)]);
+/// ```
+pub fn bridges<T: Clone, G: Graph<T>>(graph: &G) -> Vec<(usize, usize)> {
+	let index = Indexed::new(graph);
+	biconnectivity(&index).bridges.into_iter()
+		.map(|(u, v)| (index.ids[u], index.ids[v]))
+		.collect()
+}
+
+/// Finds the articulation points of a graph using Tarjan's algorithm.
+///
+/// An articulation point (or cut vertex) is a vertex whose removal increases the number of
+/// connected components. Returns the IDs of the articulation points, in the order they were added
+/// to the graph.
+///
+/// The search is iterative, so it handles deep graphs without overflowing the stack.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the articulation points of.
+pub fn articulation_points<T: Clone, G: Graph<T>>(graph: &G) -> Vec<usize> {
+	let index = Indexed::new(graph);
+	biconnectivity(&index).articulation_points.into_iter()
+		.map(|u| index.ids[u])
+		.collect()
+}
+
+/// Finds the biconnected components of a graph using Tarjan's algorithm.
+///
+/// The biconnected components (or blocks) partition the edges of the graph into maximal sets in
+/// which every two edges lie on a common simple cycle. Returns the edges of each block as pairs of
+/// vertex IDs. Isolated vertices and self loops don't belong to any block.
+///
+/// The search is iterative, so it handles deep graphs without overflowing the stack.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the biconnected components of.
+pub fn biconnected_components<T: Clone, G: Graph<T>>(graph: &G) -> Vec<Vec<(usize, usize)>> {
+	let index = Indexed::new(graph);
+	biconnectivity(&index).components.into_iter()
+		.map(|block| block.into_iter().map(|(u, v)| (index.ids[u], index.ids[v])).collect())
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+
+	fn build_graph<G: Graph<()>>(mut graph: G) -> (G, Vec<usize>) {
+		let v: Vec<usize> = (0..6).map(|_| graph.add_vertex(())).collect();
+
+		graph.add_edge(v[0], v[3]);
+		graph.add_edge(v[3], v[5]);
+		graph.add_edge(v[1], v[2]);
+		// v[4] is isolated
+
+		(graph, v)
+	}
+
+	#[test]
+	fn test_connected_components() {
+		let (graph, v) = build_graph(UndirectedGraph::<()>::new());
+		assert_eq!(connected_components(&graph), vec![vec![v[0], v[3], v[5]], vec![v[1], v[2]], vec![v[4]]]);
+
+		let (graph, v) = build_graph(UndirectedSparseGraph::<()>::new());
+		assert_eq!(connected_components(&graph), vec![vec![v[0], v[3], v[5]], vec![v[1], v[2]], vec![v[4]]]);
+	}
+
+	#[test]
+	fn test_component_labels() {
+		let (graph, v) = build_graph(UndirectedSparseGraph::<()>::new());
+		let labels = component_labels(&graph);
+
+		assert_eq!(labels.len(), 6);
+		assert_eq!(labels[&v[0]], 0);
+		assert_eq!(labels[&v[5]], 0);
+		assert_eq!(labels[&v[2]], 1);
+		assert_eq!(labels[&v[4]], 2);
+	}
+
+	#[test]
+	fn test_is_connected() {
+		let (mut graph, v) = build_graph(UndirectedGraph::<()>::new());
+		assert!(!is_connected(&graph));
+
+		graph.add_edge(v[5], v[2]);
+		graph.add_edge(v[4], v[1]);
+		assert!(is_connected(&graph));
+
+		graph.remove_vertex(v[2]);
+		assert!(!is_connected(&graph));
+
+		assert!(is_connected(&UndirectedSparseGraph::<()>::new()));
+	}
+
+	/// Builds two triangles joined by a path, with a pendant vertex:
+	///
+	/// ```text
+	/// 0 - 1     4 - 5
+	///  \ /       \ /
+	///   2 - 3 -- 6 - 7
+	/// ```
+	fn build_cut_graph<G: Graph<()>>(mut graph: G) -> (G, Vec<usize>) {
+		let v: Vec<usize> = (0..8).map(|_| graph.add_vertex(())).collect();
+
+		for (a, b) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 6), (4, 5), (5, 6), (6, 4), (6, 7)] {
+			graph.add_edge(v[a], v[b]);
+		}
+
+		(graph, v)
+	}
+
+	/// Normalizes a set of edges so they can be compared.
+	fn normalize(edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+		let mut edges: Vec<(usize, usize)> = edges.iter().map(|&(a, b)| (a.min(b), a.max(b))).collect();
+		edges.sort();
+		edges
+	}
+
+	#[test]
+	fn test_bridges() {
+		let (graph, v) = build_cut_graph(UndirectedGraph::<()>::new());
+		assert_eq!(normalize(&bridges(&graph)), vec![(v[2], v[3]), (v[3], v[6]), (v[6], v[7])]);
+
+		let (graph, v) = build_cut_graph(UndirectedSparseGraph::<()>::new());
+		assert_eq!(normalize(&bridges(&graph)), vec![(v[2], v[3]), (v[3], v[6]), (v[6], v[7])]);
+	}
+
+	#[test]
+	fn test_articulation_points() {
+		let (graph, v) = build_cut_graph(UndirectedGraph::<()>::new());
+		assert_eq!(articulation_points(&graph), vec![v[2], v[3], v[6]]);
+
+		let (mut graph, v) = build_cut_graph(UndirectedSparseGraph::<()>::new());
+		assert_eq!(articulation_points(&graph), vec![v[2], v[3], v[6]]);
+
+		// Closing a cycle through the path removes the cut vertices on it
+		graph.add_edge(v[1], v[5]);
+		assert_eq!(articulation_points(&graph), vec![v[6]]);
+		assert_eq!(normalize(&bridges(&graph)), vec![(v[6], v[7])]);
+	}
+
+	#[test]
+	fn test_articulation_point_root() {
+		// The search starts at the center of a star, which is the only cut vertex
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let center = graph.add_vertex(());
+		for _ in 0..3 {
+			let leaf = graph.add_vertex(());
+			graph.add_edge(center, leaf);
+		}
+
+		assert_eq!(articulation_points(&graph), vec![center]);
+	}
+
+	#[test]
+	fn test_biconnected_components() {
+		let (graph, v) = build_cut_graph(UndirectedSparseGraph::<()>::new());
+		let mut blocks: Vec<Vec<(usize, usize)>> = biconnected_components(&graph).iter().map(|b| normalize(b)).collect();
+		blocks.sort();
+
+		assert_eq!(blocks, vec![
+			vec![(v[0], v[1]), (v[0], v[2]), (v[1], v[2])],
+			vec![(v[2], v[3])],
+			vec![(v[3], v[6])],
+			vec![(v[4], v[5]), (v[4], v[6]), (v[5], v[6])],
+			vec![(v[6], v[7])],
+		]);
+	}
+
+	#[test]
+	fn test_deep_graph() {
+		// A long path would overflow the stack with a recursive search
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..5_000).map(|_| graph.add_vertex(())).collect();
+		for pair in v.windows(2) {
+			graph.add_edge(pair[0], pair[1]);
+		}
+
+		assert_eq!(bridges(&graph).len(), 4_999);
+		assert_eq!(articulation_points(&graph).len(), 4_998);
+		assert_eq!(biconnected_components(&graph).len(), 4_999);
+
+		// Closing the path into a cycle leaves a single block
+		graph.add_edge(v[4_999], v[0]);
+		assert!(bridges(&graph).is_empty());
+		assert!(articulation_points(&graph).is_empty());
+		assert_eq!(biconnected_components(&graph).len(), 1);
+	}
+}
@@ -0,0 +1,174 @@
+//! k-core decomposition and degeneracy ordering.
+//!
+//! The k-core of a graph is the largest subgraph in which every vertex has at least `k` neighbors.
+//! It is found by repeatedly removing vertices with fewer than `k` neighbors, and the core number
+//! of a vertex is the largest `k` for which it is in the k-core. Self loops are ignored.
+
+use std::collections::HashMap;
+
+use super::util::{core_decomposition, Indexed};
+use super::{Graph, UndirectedSparseGraph};
+
+/// Computes the core number of every vertex of a graph with the Batagelj–Zaversnik algorithm: the
+/// largest `k` for which the vertex is in the k-core.
+///
+/// Takes `O(V + E)` time. Returns a map from the IDs of the vertices to their core numbers.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to decompose.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::cores::core_numbers;
+///
+/// // A triangle with a pendant vertex
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+/// for (a, b) in [(0, 1), (1, 2), (2, 0), (2, 3)] {
+///     graph.add_edge(v[a], v[b]);
+/// }
+///
+/// let cores = core_numbers(&graph);
+///
+/// assert_eq!([cores[&v[0]], cores[&v[1]], cores[&v[2]], cores[&v[3]]], [2, 2, 2, 1]);
+/// ```
+pub fn core_numbers<T: Clone, G: Graph<T>>(graph: &G) -> HashMap<usize, usize> {
+	let index = Indexed::new(graph);
+	let (_, core) = core_decomposition(&index.adj);
+	index.ids.iter().copied().zip(core).collect()
+}
+
+/// Finds the k-core of a graph: the subgraph induced by the vertices with core number at least `k`,
+/// in which every vertex has at least `k` neighbors.
+///
+/// Takes `O(V + E)` time, plus the time to build the new graph. Returns the k-core as a new graph,
+/// with the original IDs and data of its vertices, which is empty if `k` is larger than the
+/// degeneracy of the graph.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the k-core of.
+/// - `k`: The least number of neighbors of the vertices in the core.
+pub fn k_core<T: Clone, G: Graph<T>>(graph: &G, k: usize) -> UndirectedSparseGraph<T> {
+	let index = Indexed::new(graph);
+	let (_, core) = core_decomposition(&index.adj);
+
+	let vertices = (0..index.len())
+		.filter(|&u| core[u] >= k)
+		.filter_map(|u| Some((index.ids[u], graph.get_vertex_data(index.ids[u])?)))
+		.collect();
+	let edges = index.adj.iter().enumerate()
+		.filter(|&(u, _)| core[u] >= k)
+		.flat_map(|(u, neighbors)| neighbors.iter().filter(move |&&v| u <= v).map(move |&v| (u, v)))
+		.filter(|&(_, v)| core[v] >= k)
+		.map(|(u, v)| (index.ids[u], index.ids[v], ()))
+		.collect();
+
+	UndirectedSparseGraph::from_parts(vertices, edges)
+}
+
+/// Orders the vertices of a graph by repeatedly removing one of minimum degree among the remaining
+/// vertices, using the Batagelj–Zaversnik algorithm.
+///
+/// Every vertex has at most `d` neighbors later in the order, where `d` is the degeneracy of the
+/// graph: the largest core number of its vertices. Visiting the vertices in the reverse order is
+/// useful for greedy coloring and clique enumeration. Takes `O(V + E)` time.
+///
+/// Returns the IDs of the vertices in the order they were removed.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to order the vertices of.
+pub fn degeneracy_ordering<T: Clone, G: Graph<T>>(graph: &G) -> Vec<usize> {
+	let index = Indexed::new(graph);
+	let (order, _) = core_decomposition(&index.adj);
+	order.into_iter().map(|u| index.ids[u]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::UndirectedGraph;
+	use crate::util::random_graph;
+
+	/// Finds the vertices of the k-core by repeatedly removing vertices with fewer than `k`
+	/// neighbors left.
+	fn peel(graph: &UndirectedSparseGraph<usize>, k: usize) -> Vec<usize> {
+		let mut remaining = graph.get_vertices();
+		loop {
+			let degree = |u: usize, remaining: &[usize]| graph.get_neighbors(u).iter().filter(|v| remaining.contains(v)).count();
+			let before = remaining.len();
+			let kept: Vec<usize> = remaining.iter().copied().filter(|&u| degree(u, &remaining) >= k).collect();
+			remaining = kept;
+			if remaining.len() == before {
+				return remaining;
+			}
+		}
+	}
+
+	#[test]
+	fn test_core_numbers() {
+		for seed in 1..10 {
+			let graph = random_graph(40, 5 + seed * 3, seed);
+			let cores = core_numbers(&graph);
+			let degeneracy = cores.values().copied().max().unwrap();
+
+			for k in 0..=degeneracy + 1 {
+				let mut expected: Vec<usize> = cores.iter().filter(|&(_, &c)| c >= k).map(|(&u, _)| u).collect();
+				expected.sort_unstable();
+				assert_eq!(peel(&graph, k), expected);
+			}
+		}
+	}
+
+	#[test]
+	fn test_k_core() {
+		// Two triangles sharing a vertex, a pendant vertex and a self loop
+		let mut graph: UndirectedGraph<char> = UndirectedGraph::new();
+		let v: Vec<usize> = "abcdefg".chars().map(|c| graph.add_vertex(c)).collect();
+		for (a, b) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2), (4, 5), (6, 6)] {
+			graph.add_edge(v[a], v[b]);
+		}
+		graph.remove_vertex(v[1]);
+		graph.add_edge(v[0], v[3]);
+
+		let core = k_core(&graph, 2);
+		assert_eq!(core.get_vertices(), vec![v[0], v[2], v[3], v[4]]);
+		assert_eq!(core.get_vertex_data(v[3]), Some('d'));
+		assert!(core.is_adjacent(v[0], v[3]));
+		assert!(core.is_adjacent(v[2], v[4]));
+		assert!(!core.is_adjacent(v[4], v[5]));
+		assert_eq!(core.get_neighbors(v[2]).len(), 3);
+
+		// The self loop doesn't count towards the core, but is kept in the subgraph
+		let core = k_core(&graph, 0);
+		assert_eq!(core.get_vertices().len(), 6);
+		assert!(core.is_adjacent(v[6], v[6]));
+		assert!(k_core(&graph, 3).get_vertices().is_empty());
+	}
+
+	#[test]
+	fn test_degeneracy_ordering() {
+		for seed in 1..10 {
+			let graph = random_graph(40, 10 + seed * 5, seed);
+			let order = degeneracy_ordering(&graph);
+			let degeneracy = core_numbers(&graph).values().copied().max().unwrap();
+
+			let mut sorted = order.clone();
+			sorted.sort_unstable();
+			assert_eq!(sorted, graph.get_vertices());
+
+			// Every vertex has at most `degeneracy` neighbors later in the order, and some vertex has
+			// exactly that many
+			let later: Vec<usize> = order.iter().enumerate()
+				.map(|(i, &u)| graph.get_neighbors(u).iter().filter(|v| order[i + 1..].contains(v)).count())
+				.collect();
+			assert_eq!(later.into_iter().max(), Some(degeneracy));
+		}
+
+		assert!(degeneracy_ordering(&UndirectedGraph::<()>::new()).is_empty());
+	}
+}
@@ -0,0 +1,472 @@
+//! Independent sets, vertex covers and dominating sets of undirected graphs.
+//!
+//! The exact solvers take exponential time in the worst case and are meant for graphs of up to
+//! around a hundred vertices, depending on their structure. The approximations run in near-linear
+//! time on graphs of any size. Sets are returned as the IDs of their vertices in the order the
+//! vertices were added to the graph.
+//!
+//! A vertex with a self loop is adjacent to itself, so it is never in an independent set and is in
+//! every vertex cover.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+use super::util::{adjacency_matrix, BitSet, Indexed};
+use super::Graph;
+
+/// Maps a set of indices back to vertex IDs, in index order.
+fn to_ids(index: &Indexed, mut set: Vec<usize>) -> Vec<usize> {
+	set.sort_unstable();
+	set.into_iter().map(|u| index.ids[u]).collect()
+}
+
+/// Bounds the size of any independent set among the remaining vertices by greedily covering them
+/// with cliques: an independent set has at most one vertex in each clique.
+fn clique_cover_bound(adj: &[BitSet], remaining: &BitSet) -> usize {
+	let mut uncovered = remaining.clone();
+	let mut cliques = 0;
+	while let Some(u) = uncovered.first() {
+		uncovered.remove(u);
+		let mut candidates = uncovered.intersection(&adj[u]);
+		while let Some(v) = candidates.first() {
+			uncovered.remove(v);
+			candidates = candidates.intersection(&adj[v]);
+		}
+		cliques += 1;
+	}
+	cliques
+}
+
+/// Builds an independent set by repeatedly taking a vertex of minimum degree among the remaining
+/// vertices and leaving out its neighbors.
+fn greedy_independent_set(adj: &[BitSet], mut remaining: BitSet) -> Vec<usize> {
+	let mut set = Vec::new();
+	while let Some(u) = remaining.iter().min_by_key(|&u| adj[u].intersection_len(&remaining)) {
+		set.push(u);
+		remaining = remaining.difference(&adj[u]);
+		remaining.remove(u);
+	}
+	set
+}
+
+/// Splits a set of vertices into the vertex sets of the connected components they induce.
+fn components(adj: &[BitSet], remaining: &BitSet) -> Vec<BitSet> {
+	let mut left = remaining.clone();
+	let mut components = Vec::new();
+	while let Some(u) = left.first() {
+		left.remove(u);
+		let mut component = BitSet::from_indices(adj.len(), [u]);
+		let mut frontier = component.clone();
+		while !frontier.is_empty() {
+			let mut next = BitSet::new(adj.len());
+			for v in frontier.iter() {
+				next = next.union(&adj[v].intersection(&left));
+			}
+			left = left.difference(&next);
+			component = component.union(&next);
+			frontier = next;
+		}
+		components.push(component);
+	}
+	components
+}
+
+/// Searches for independent sets larger than the best one found so far, branching on whether a
+/// vertex of maximum degree is in the set.
+fn expand_independent_set(adj: &[BitSet], mut remaining: BitSet, set: &mut Vec<usize>, best: &mut Vec<usize>) {
+	let start = set.len();
+
+	// A vertex with at most one neighbor left is in some maximum independent set, since swapping
+	// its neighbor for it never makes a set smaller
+	loop {
+		let Some(u) = remaining.iter().find(|&u| adj[u].intersection_len(&remaining) <= 1) else { break };
+		set.push(u);
+		remaining = remaining.difference(&adj[u]);
+		remaining.remove(u);
+	}
+
+	let parts = components(adj, &remaining);
+	if parts.len() > 1 {
+		// The components don't affect each other, so each is solved on its own
+		for part in parts {
+			set.extend(independent_set_within(adj, part));
+		}
+		if set.len() > best.len() {
+			best.clone_from(set);
+		}
+	} else if remaining.is_empty() {
+		if set.len() > best.len() {
+			best.clone_from(set);
+		}
+	} else if set.len() + clique_cover_bound(adj, &remaining) > best.len() {
+		let u = remaining.iter().max_by_key(|&u| adj[u].intersection_len(&remaining)).unwrap();
+
+		// Either the vertex is in the set and its neighbors are not...
+		set.push(u);
+		let mut rest = remaining.difference(&adj[u]);
+		rest.remove(u);
+		expand_independent_set(adj, rest, set, best);
+		set.pop();
+
+		// ...or it is not
+		remaining.remove(u);
+		expand_independent_set(adj, remaining, set, best);
+	}
+
+	set.truncate(start);
+}
+
+/// Finds a maximum independent set among some of the vertices of a graph.
+fn independent_set_within(adj: &[BitSet], remaining: BitSet) -> Vec<usize> {
+	let mut best = greedy_independent_set(adj, remaining.clone());
+	expand_independent_set(adj, remaining, &mut Vec::new(), &mut best);
+	best
+}
+
+/// Finds the indices of a maximum independent set of an indexed graph.
+fn independent_set_indices(index: &Indexed) -> Vec<usize> {
+	let adj = adjacency_matrix(index);
+	let remaining = BitSet::from_indices(index.len(), (0..index.len()).filter(|&u| !index.adj[u].contains(&u)));
+	independent_set_within(&adj, remaining)
+}
+
+/// Finds a maximum independent set of a graph: a set with as many vertices as possible, no two of
+/// which are adjacent.
+///
+/// Uses a branch and reduce search, which takes vertices with at most one remaining neighbor
+/// without branching, and prunes with a bound from a greedy clique cover of the remaining vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to find an independent set of.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedSparseGraph};
+/// use istos::covering::maximum_independent_set;
+///
+/// // A cycle of five vertices
+/// let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+/// let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+/// for i in 0..5 {
+///     graph.add_edge(v[i], v[(i + 1) % 5]);
+/// }
+///
+/// assert_eq!(maximum_independent_set(&graph).len(), 2);
+/// ```
+pub fn maximum_independent_set<T: Clone, G: Graph<T>>(graph: &G) -> Vec<usize> {
+	let index = Indexed::new(graph);
+	let set = independent_set_indices(&index);
+	to_ids(&index, set)
+}
+
+/// Finds a minimum vertex cover of a graph: a set with as few vertices as possible that contains an
+/// endpoint of every edge.
+///
+/// The vertices outside a vertex cover form an independent set, so this is the complement of the
+/// set found by [`maximum_independent_set`], and it takes exponential time in the worst case. Use
+/// [`approximate_vertex_cover`] for large graphs.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to find a vertex cover of.
+pub fn minimum_vertex_cover<T: Clone, G: Graph<T>>(graph: &G) -> Vec<usize> {
+	let index = Indexed::new(graph);
+	let independent = BitSet::from_indices(index.len(), independent_set_indices(&index));
+	to_ids(&index, (0..index.len()).filter(|&u| !independent.contains(u)).collect())
+}
+
+/// Finds a vertex cover of a graph with at most twice as many vertices as a minimum one.
+///
+/// Takes both endpoints of every edge of a greedily built maximal matching. Any vertex cover has to
+/// contain an endpoint of each of the matched edges, which share no endpoints. Runs in `O(V + E)`
+/// time.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to find a vertex cover of.
+pub fn approximate_vertex_cover<T: Clone, G: Graph<T>>(graph: &G) -> Vec<usize> {
+	let index = Indexed::new(graph);
+	let mut covered = vec![false; index.len()];
+
+	for u in 0..index.len() {
+		if !covered[u] {
+			if let Some(&v) = index.adj[u].iter().find(|&&v| !covered[v]) {
+				covered[u] = true;
+				covered[v] = true;
+			}
+		}
+	}
+
+	to_ids(&index, (0..index.len()).filter(|&u| covered[u]).collect())
+}
+
+/// Builds a dominating set by repeatedly taking the vertex that dominates the most vertices not
+/// dominated yet, given the closed neighborhood of every vertex.
+fn greedy_domination(closed: &[Vec<usize>]) -> Vec<usize> {
+	let mut gain: Vec<usize> = closed.iter().map(|c| c.len()).collect();
+	let mut dominated = vec![false; closed.len()];
+	let mut heap: BinaryHeap<(usize, Reverse<usize>)> = (0..closed.len()).map(|w| (gain[w], Reverse(w))).collect();
+	let mut set = Vec::new();
+
+	while let Some((g, Reverse(w))) = heap.pop() {
+		// Skip entries for vertices whose gain has dropped since
+		if g != gain[w] {
+			continue;
+		}
+		if g == 0 {
+			break;
+		}
+
+		set.push(w);
+		for &v in &closed[w] {
+			if !dominated[v] {
+				dominated[v] = true;
+				for &x in &closed[v] {
+					gain[x] -= 1;
+					heap.push((gain[x], Reverse(x)));
+				}
+			}
+		}
+	}
+
+	set
+}
+
+/// Searches for dominating sets smaller than the best one found so far. Some allowed vertex has to
+/// dominate the undominated vertex with the fewest allowed dominators, so the search branches on
+/// which one. Each dominator that has been tried is disallowed in the later branches.
+fn expand_dominating_set(
+	closed: &[BitSet],
+	undominated: BitSet,
+	mut allowed: BitSet,
+	set: &mut Vec<usize>,
+	best: &mut Vec<usize>,
+) {
+	if undominated.is_empty() {
+		if set.len() < best.len() {
+			best.clone_from(set);
+		}
+		return;
+	}
+
+	// Every new vertex dominates at most as many vertices as the best one does now
+	let most = allowed.iter().map(|w| closed[w].intersection_len(&undominated)).max().unwrap_or(0);
+	if most == 0 || set.len() + undominated.len().div_ceil(most) >= best.len() {
+		return;
+	}
+
+	let u = undominated.iter().min_by_key(|&u| closed[u].intersection_len(&allowed)).unwrap();
+	let mut dominators: Vec<usize> = closed[u].intersection(&allowed).iter().collect();
+	dominators.sort_by_key(|&w| Reverse(closed[w].intersection_len(&undominated)));
+
+	for w in dominators {
+		set.push(w);
+		expand_dominating_set(closed, undominated.difference(&closed[w]), allowed.clone(), set, best);
+		set.pop();
+		allowed.remove(w);
+	}
+}
+
+/// Builds the closed neighborhood of every vertex: the vertex and its neighbors.
+fn closed_neighborhoods(index: &Indexed) -> Vec<Vec<usize>> {
+	index.adj.iter().enumerate()
+		.map(|(u, neighbors)| std::iter::once(u).chain(neighbors.iter().copied().filter(|&v| v != u)).collect())
+		.collect()
+}
+
+/// Finds a minimum dominating set of a graph: a set with as few vertices as possible such that
+/// every vertex is in the set or adjacent to a vertex in it.
+///
+/// Starts from the set found by [`greedy_dominating_set`], and then searches for smaller ones with
+/// a branch and bound search. This takes exponential time in the worst case, so it is only suitable
+/// for small graphs (up to a few dozen vertices, depending on their structure).
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to find a dominating set of.
+pub fn minimum_dominating_set<T: Clone, G: Graph<T>>(graph: &G) -> Vec<usize> {
+	let index = Indexed::new(graph);
+	let n = index.len();
+	let lists = closed_neighborhoods(&index);
+	let closed: Vec<BitSet> = lists.iter().map(|c| BitSet::from_indices(n, c.iter().copied())).collect();
+
+	let mut best = greedy_domination(&lists);
+	let all = BitSet::from_indices(n, 0..n);
+	expand_dominating_set(&closed, all.clone(), all, &mut Vec::new(), &mut best);
+
+	to_ids(&index, best)
+}
+
+/// Finds a dominating set of a graph greedily: repeatedly takes the vertex that dominates the most
+/// vertices that are not dominated yet.
+///
+/// The set has at most `ln(Δ + 1) + 1` times as many vertices as a minimum one, where `Δ` is the
+/// maximum degree of the graph. Runs in `O((V + E) log V)` time.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to find a dominating set of.
+pub fn greedy_dominating_set<T: Clone, G: Graph<T>>(graph: &G) -> Vec<usize> {
+	let index = Indexed::new(graph);
+	let set = greedy_domination(&closed_neighborhoods(&index));
+	to_ids(&index, set)
+}
+
+/// Checks whether a set of vertices is an independent set of a graph.
+///
+/// Returns `true` if every vertex in the set exists and no two of them (nor any vertex and itself)
+/// are adjacent.
+///
+/// # Arguments
+///
+/// - `graph`: The graph the vertices belong to.
+/// - `set`: The IDs of the vertices in the set.
+pub fn is_independent_set<T: Clone, G: Graph<T>>(graph: &G, set: &[usize]) -> bool {
+	let vertices: HashSet<usize> = graph.get_vertices().into_iter().collect();
+	let members: HashSet<usize> = set.iter().copied().collect();
+	set.iter().all(|u| vertices.contains(u) && graph.get_neighbors(*u).iter().all(|v| !members.contains(v)))
+}
+
+/// Checks whether a set of vertices is a vertex cover of a graph.
+///
+/// Returns `true` if every vertex in the set exists and every edge of the graph has an endpoint in
+/// the set.
+///
+/// # Arguments
+///
+/// - `graph`: The graph the vertices belong to.
+/// - `cover`: The IDs of the vertices in the cover.
+pub fn is_vertex_cover<T: Clone, G: Graph<T>>(graph: &G, cover: &[usize]) -> bool {
+	let vertices: HashSet<usize> = graph.get_vertices().into_iter().collect();
+	let members: HashSet<usize> = cover.iter().copied().collect();
+	members.is_subset(&vertices) && vertices.iter()
+		.filter(|u| !members.contains(u))
+		.all(|&u| graph.get_neighbors(u).iter().all(|v| members.contains(v)))
+}
+
+/// Checks whether a set of vertices is a dominating set of a graph.
+///
+/// Returns `true` if every vertex in the set exists and every vertex of the graph is in the set or
+/// adjacent to a vertex in it.
+///
+/// # Arguments
+///
+/// - `graph`: The graph the vertices belong to.
+/// - `set`: The IDs of the vertices in the set.
+pub fn is_dominating_set<T: Clone, G: Graph<T>>(graph: &G, set: &[usize]) -> bool {
+	let vertices: HashSet<usize> = graph.get_vertices().into_iter().collect();
+	let members: HashSet<usize> = set.iter().copied().collect();
+	members.is_subset(&vertices) && vertices.iter()
+		.all(|u| members.contains(u) || graph.get_neighbors(*u).iter().any(|v| members.contains(v)))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+	use crate::util::random_graph;
+
+	/// Finds the sizes of the smallest and largest subsets of the vertices satisfying a check.
+	fn brute_force(graph: &UndirectedSparseGraph<usize>, check: fn(&UndirectedSparseGraph<usize>, &[usize]) -> bool) -> (usize, usize) {
+		let v = graph.get_vertices();
+		let sizes: Vec<usize> = (0..1u32 << v.len())
+			.map(|mask| (0..v.len()).filter(|&i| mask & (1 << i) != 0).map(|i| v[i]).collect::<Vec<usize>>())
+			.filter(|set| check(graph, set))
+			.map(|set| set.len())
+			.collect();
+		(*sizes.iter().min().unwrap(), *sizes.iter().max().unwrap())
+	}
+
+	#[test]
+	fn test_independent_set_and_vertex_cover() {
+		for seed in 1..30 {
+			let graph = random_graph(10, 30, seed);
+			let set = maximum_independent_set(&graph);
+			let cover = minimum_vertex_cover(&graph);
+			let approximate = approximate_vertex_cover(&graph);
+
+			assert!(is_independent_set(&graph, &set));
+			assert!(is_vertex_cover(&graph, &cover));
+			assert!(is_vertex_cover(&graph, &approximate));
+			assert_eq!(set.len(), brute_force(&graph, is_independent_set).1);
+			assert_eq!(cover.len(), brute_force(&graph, is_vertex_cover).0);
+			assert_eq!(set.len() + cover.len(), 10);
+			assert!(approximate.len() <= 2 * cover.len());
+		}
+	}
+
+	#[test]
+	fn test_independent_set_special_cases() {
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		assert!(maximum_independent_set(&graph).is_empty());
+		assert!(minimum_vertex_cover(&graph).is_empty());
+
+		// A vertex with a self loop has to be in the cover
+		let v1 = graph.add_vertex(());
+		let v2 = graph.add_vertex(());
+		graph.add_edge(v1, v1);
+		assert_eq!(maximum_independent_set(&graph), vec![v2]);
+		assert_eq!(minimum_vertex_cover(&graph), vec![v1]);
+		assert_eq!(approximate_vertex_cover(&graph), vec![v1]);
+		assert!(!is_independent_set(&graph, &[v1]));
+		assert!(!is_vertex_cover(&graph, &[v2]));
+		assert!(!is_independent_set(&graph, &[v2, 99]));
+	}
+
+	#[test]
+	fn test_independent_set_large() {
+		// 30 disjoint pentagons and a cycle of 60 vertices
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..210).map(|_| graph.add_vertex(())).collect();
+		for p in 0..30 {
+			for i in 0..5 {
+				graph.add_edge(v[p * 5 + i], v[p * 5 + (i + 1) % 5]);
+			}
+		}
+		for i in 0..60 {
+			graph.add_edge(v[150 + i], v[150 + (i + 1) % 60]);
+		}
+
+		assert_eq!(maximum_independent_set(&graph).len(), 30 * 2 + 30);
+		assert_eq!(minimum_vertex_cover(&graph).len(), 30 * 3 + 30);
+	}
+
+	#[test]
+	fn test_dominating_set() {
+		for seed in 1..30 {
+			let graph = random_graph(10, 20, seed);
+			let set = minimum_dominating_set(&graph);
+			let greedy = greedy_dominating_set(&graph);
+
+			assert!(is_dominating_set(&graph, &set));
+			assert!(is_dominating_set(&graph, &greedy));
+			assert_eq!(set.len(), brute_force(&graph, is_dominating_set).0);
+		}
+
+		let graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		assert!(minimum_dominating_set(&graph).is_empty());
+	}
+
+	#[test]
+	fn test_dominating_set_greedy_is_not_optimal() {
+		// A spider with three legs of two edges: the body dominates the most vertices, but the
+		// middles of the legs dominate all of them
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let body = graph.add_vertex(());
+		let mut middles = Vec::new();
+		for _ in 0..3 {
+			let middle = graph.add_vertex(());
+			let foot = graph.add_vertex(());
+			graph.add_edge(body, middle);
+			graph.add_edge(middle, foot);
+			middles.push(middle);
+		}
+
+		let greedy = greedy_dominating_set(&graph);
+		assert_eq!(greedy.len(), 4);
+		assert_eq!(greedy[0], body);
+		assert_eq!(minimum_dominating_set(&graph), middles);
+	}
+}
@@ -0,0 +1,523 @@
+//! Distance measures: eccentricity, diameter, radius, center, periphery and girth.
+//!
+//! The eccentricity of a vertex is its distance to the vertex farthest from it. The diameter and
+//! radius of a graph are the largest and smallest eccentricities, and the periphery and center are
+//! the vertices that have them. These are only defined for connected graphs, so the functions
+//! return `None` for graphs that aren't connected or have no vertices.
+//!
+//! Every measure comes in an unweighted version, which counts edges with a breadth-first search
+//! from every vertex in `O(V E)` time, and a weighted version, which adds up the edge weights of a
+//! [`WeightedGraph`] with Dijkstra's algorithm from every vertex. Edge weights must not be
+//! negative. For large sparse graphs, [`ifub_diameter`] finds the unweighted diameter with far
+//! fewer searches.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::util::{dijkstra, Indexed};
+use super::{Graph, Weight, WeightedGraph};
+
+/// Finds the distance and the parent of every vertex reachable from a vertex by breadth-first
+/// search.
+fn bfs(adj: &[Vec<usize>], source: usize) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
+	let mut dist: Vec<Option<usize>> = vec![None; adj.len()];
+	let mut parent: Vec<Option<usize>> = vec![None; adj.len()];
+	let mut queue = VecDeque::from([source]);
+	dist[source] = Some(0);
+	while let Some(u) = queue.pop_front() {
+		let d = dist[u].unwrap() + 1;
+		for &v in &adj[u] {
+			if dist[v].is_none() {
+				dist[v] = Some(d);
+				parent[v] = Some(u);
+				queue.push_back(v);
+			}
+		}
+	}
+	(dist, parent)
+}
+
+/// Finds the largest of the distances from a vertex, or `None` if some vertex can't be reached.
+fn max_distance<K: PartialOrd + Copy>(dist: Vec<Option<K>>) -> Option<K> {
+	let dist: Vec<K> = dist.into_iter().collect::<Option<_>>()?;
+	dist.into_iter().reduce(|a, b| if b > a { b } else { a })
+}
+
+/// Computes the eccentricity of every vertex by breadth-first search, or `None` if the graph is not
+/// connected or has no vertices.
+fn eccentricities(index: &Indexed) -> Option<Vec<usize>> {
+	(0..index.len()).map(|u| max_distance(bfs(&index.adj, u).0)).collect::<Option<Vec<_>>>().filter(|e| !e.is_empty())
+}
+
+/// Computes the eccentricity of every vertex with Dijkstra's algorithm, or `None` if the graph is
+/// not connected or has no vertices.
+fn weighted_eccentricities<W: Weight>(index: &Indexed, weights: &[Vec<W>]) -> Option<Vec<W>> {
+	(0..index.len()).map(|u| max_distance(dijkstra(&index.adj, weights, u).0)).collect::<Option<Vec<_>>>().filter(|e| !e.is_empty())
+}
+
+/// Finds the largest or smallest eccentricity.
+fn extreme<K: PartialOrd + Copy>(eccentricities: &[K], largest: bool) -> K {
+	eccentricities.iter().copied()
+		.reduce(|a, b| if (largest && b > a) || (!largest && b < a) { b } else { a })
+		.unwrap()
+}
+
+/// Finds the IDs of the vertices with the largest or smallest eccentricity.
+fn vertices_with<K: PartialOrd + Copy>(index: &Indexed, eccentricities: &[K], largest: bool) -> Vec<usize> {
+	let target = extreme(eccentricities, largest);
+	eccentricities.iter().enumerate().filter(|&(_, &e)| e == target).map(|(u, _)| index.ids[u]).collect()
+}
+
+/// Computes the eccentricity of every vertex of a graph: the number of edges on a shortest path to
+/// the vertex farthest from it.
+///
+/// Returns a map from the IDs of the vertices to their eccentricities, or `None` if the graph is not
+/// connected or has no vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to compute the eccentricities in.
+pub fn eccentricity<T: Clone, G: Graph<T>>(graph: &G) -> Option<HashMap<usize, usize>> {
+	let index = Indexed::new(graph);
+	Some(index.ids.iter().copied().zip(eccentricities(&index)?).collect())
+}
+
+/// Computes the eccentricity of every vertex of a weighted graph: the total weight of a shortest
+/// path to the vertex farthest from it.
+///
+/// Returns a map from the IDs of the vertices to their eccentricities, or `None` if the graph is not
+/// connected or has no vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to compute the eccentricities in.
+pub fn weighted_eccentricity<T, W, G>(graph: &G) -> Option<HashMap<usize, W>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	Some(index.ids.iter().copied().zip(weighted_eccentricities(&index, &weights)?).collect())
+}
+
+/// Computes the diameter of a graph: the largest eccentricity of its vertices, which is the number
+/// of edges on the longest shortest path.
+///
+/// Returns `None` if the graph is not connected or has no vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to compute the diameter of.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::distance::{diameter, radius, center};
+///
+/// // A path of five vertices
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+/// for i in 0..4 {
+///     graph.add_edge(v[i], v[i + 1]);
+/// }
+///
+/// assert_eq!(diameter(&graph), Some(4));
+/// assert_eq!(radius(&graph), Some(2));
+/// assert_eq!(center(&graph), Some(vec![v[2]]));
+/// ```
+pub fn diameter<T: Clone, G: Graph<T>>(graph: &G) -> Option<usize> {
+	let index = Indexed::new(graph);
+	Some(extreme(&eccentricities(&index)?, true))
+}
+
+/// Computes the diameter of a weighted graph: the largest eccentricity of its vertices, which is the
+/// total weight of the longest shortest path.
+///
+/// Returns `None` if the graph is not connected or has no vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to compute the diameter of.
+pub fn weighted_diameter<T, W, G>(graph: &G) -> Option<W>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	Some(extreme(&weighted_eccentricities(&index, &weights)?, true))
+}
+
+/// Computes the radius of a graph: the smallest eccentricity of its vertices.
+///
+/// Returns `None` if the graph is not connected or has no vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to compute the radius of.
+pub fn radius<T: Clone, G: Graph<T>>(graph: &G) -> Option<usize> {
+	let index = Indexed::new(graph);
+	Some(extreme(&eccentricities(&index)?, false))
+}
+
+/// Computes the radius of a weighted graph: the smallest eccentricity of its vertices.
+///
+/// Returns `None` if the graph is not connected or has no vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to compute the radius of.
+pub fn weighted_radius<T, W, G>(graph: &G) -> Option<W>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	Some(extreme(&weighted_eccentricities(&index, &weights)?, false))
+}
+
+/// Finds the center of a graph: the vertices whose eccentricity equals the radius.
+///
+/// Returns the IDs of the vertices in the order they were added to the graph, or `None` if the
+/// graph is not connected or has no vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the center of.
+pub fn center<T: Clone, G: Graph<T>>(graph: &G) -> Option<Vec<usize>> {
+	let index = Indexed::new(graph);
+	Some(vertices_with(&index, &eccentricities(&index)?, false))
+}
+
+/// Finds the center of a weighted graph: the vertices whose eccentricity equals the radius.
+///
+/// Returns the IDs of the vertices in the order they were added to the graph, or `None` if the
+/// graph is not connected or has no vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find the center of.
+pub fn weighted_center<T, W, G>(graph: &G) -> Option<Vec<usize>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	Some(vertices_with(&index, &weighted_eccentricities(&index, &weights)?, false))
+}
+
+/// Finds the periphery of a graph: the vertices whose eccentricity equals the diameter.
+///
+/// Returns the IDs of the vertices in the order they were added to the graph, or `None` if the
+/// graph is not connected or has no vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the periphery of.
+pub fn periphery<T: Clone, G: Graph<T>>(graph: &G) -> Option<Vec<usize>> {
+	let index = Indexed::new(graph);
+	Some(vertices_with(&index, &eccentricities(&index)?, true))
+}
+
+/// Finds the periphery of a weighted graph: the vertices whose eccentricity equals the diameter.
+///
+/// Returns the IDs of the vertices in the order they were added to the graph, or `None` if the
+/// graph is not connected or has no vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find the periphery of.
+pub fn weighted_periphery<T, W, G>(graph: &G) -> Option<Vec<usize>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	Some(vertices_with(&index, &weighted_eccentricities(&index, &weights)?, true))
+}
+
+/// Computes the diameter of a graph with the iFUB algorithm of Crescenzi et al., which usually needs
+/// only a few breadth-first searches on large sparse graphs, such as road and social networks.
+///
+/// Starts from a vertex near the middle of a long shortest path, found by the 4-sweep heuristic, and
+/// goes through the vertices in order of decreasing distance from it. Since any two vertices at
+/// distance at most `i` from the start are at most `2i` apart, the search can stop once the largest
+/// eccentricity found is more than twice the distance of the remaining vertices. The result is
+/// exact, and takes `O(V E)` time in the worst case.
+///
+/// Returns `None` if the graph is not connected or has no vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to compute the diameter of.
+pub fn ifub_diameter<T: Clone, G: Graph<T>>(graph: &G) -> Option<usize> {
+	let index = Indexed::new(graph);
+	let n = index.len();
+	let adj = &index.adj;
+
+	// The vertex farthest from a vertex, its distance, and the vertex halfway along the path to it
+	let sweep = |source: usize| -> Option<(usize, usize, usize)> {
+		let (dist, parent) = bfs(adj, source);
+		let far = (0..n).try_fold(source, |far, u| Some(if dist[u]? > dist[far].unwrap() { u } else { far }))?;
+		let d = dist[far].unwrap();
+		let mut middle = far;
+		for _ in 0..d / 2 {
+			middle = parent[middle].unwrap();
+		}
+		Some((far, d, middle))
+	};
+
+	// The 4-sweep: two double sweeps, each from the middle of the path the last one found
+	let start = (0..n).max_by_key(|&u| adj[u].len())?;
+	let (a_1, mut lower, _) = sweep(start)?;
+	let (_, d, r_2) = sweep(a_1)?;
+	lower = lower.max(d);
+	let (a_2, d, _) = sweep(r_2)?;
+	lower = lower.max(d);
+	let (_, d, root) = sweep(a_2)?;
+	lower = lower.max(d);
+
+	// Go through the vertices by decreasing distance from the root
+	let (dist, _) = bfs(adj, root);
+	let eccentricity = dist.iter().map(|d| d.unwrap()).max().unwrap();
+	lower = lower.max(eccentricity);
+	let mut levels: Vec<Vec<usize>> = vec![Vec::new(); eccentricity + 1];
+	for u in 0..n {
+		levels[dist[u].unwrap()].push(u);
+	}
+
+	for i in (1..=eccentricity).rev() {
+		// The vertices left are at most `i` from the root, and so at most `2i` from each other
+		if lower >= 2 * i {
+			break;
+		}
+		for &u in &levels[i] {
+			let (far_dist, _) = bfs(adj, u);
+			lower = lower.max(far_dist.into_iter().map(|d| d.unwrap()).max().unwrap());
+		}
+	}
+
+	Some(lower)
+}
+
+/// Computes the girth of a graph: the number of edges on a shortest cycle.
+///
+/// Runs a breadth-first search from every vertex, where every edge outside the search tree closes a
+/// cycle through the root, or a shorter one. Takes `O(V E)` time. Self loops are ignored.
+///
+/// Returns `None` if the graph has no cycles.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to compute the girth of.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::distance::girth;
+///
+/// // A square with a diagonal has a triangle
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+/// for (a, b) in [(0, 1), (1, 2), (2, 3), (3, 0)] {
+///     graph.add_edge(v[a], v[b]);
+/// }
+/// assert_eq!(girth(&graph), Some(4));
+///
+/// graph.add_edge(v[0], v[2]);
+/// assert_eq!(girth(&graph), Some(3));
+/// ```
+pub fn girth<T: Clone, G: Graph<T>>(graph: &G) -> Option<usize> {
+	let index = Indexed::new(graph);
+	let mut best: Option<usize> = None;
+	for source in 0..index.len() {
+		let (dist, parent) = bfs(&index.adj, source);
+		for (u, neighbors) in index.adj.iter().enumerate() {
+			let Some(du) = dist[u] else { continue };
+			for &v in neighbors.iter().filter(|&&v| u < v && parent[u] != Some(v) && parent[v] != Some(u)) {
+				let length = du + dist[v].unwrap() + 1;
+				if best.is_none_or(|b| length < b) {
+					best = Some(length);
+				}
+			}
+		}
+	}
+	best
+}
+
+/// Computes the girth of a weighted graph: the total weight of a lightest cycle.
+///
+/// Runs Dijkstra's algorithm from every vertex, where every edge outside the shortest path tree
+/// closes a cycle through the root, or a lighter one. Self loops are ignored.
+///
+/// Returns `None` if the graph has no cycles.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to compute the girth of.
+pub fn weighted_girth<T, W, G>(graph: &G) -> Option<W>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let mut best: Option<W> = None;
+	for source in 0..index.len() {
+		let (dist, parent) = dijkstra(&index.adj, &weights, source);
+		for (u, neighbors) in index.adj.iter().enumerate() {
+			let Some(du) = dist[u] else { continue };
+			for (&v, &w) in neighbors.iter().zip(&weights[u]) {
+				if u < v && parent[u] != Some(v) && parent[v] != Some(u) {
+					let length = du + w + dist[v].unwrap();
+					if best.is_none_or(|b| length < b) {
+						best = Some(length);
+					}
+				}
+			}
+		}
+	}
+	best
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+	use crate::util::random_weighted_graph;
+
+	/// Builds the Petersen graph, which has diameter 2 and girth 5.
+	fn petersen() -> (UndirectedGraph<()>, Vec<usize>) {
+		let mut graph = UndirectedGraph::new();
+		let v: Vec<usize> = (0..10).map(|_| graph.add_vertex(())).collect();
+		for i in 0..5 {
+			graph.add_edge(v[i], v[(i + 1) % 5]);
+			graph.add_edge(v[i], v[i + 5]);
+			graph.add_edge(v[i + 5], v[(i + 2) % 5 + 5]);
+		}
+		(graph, v)
+	}
+
+	#[test]
+	fn test_unweighted() {
+		let (graph, v) = petersen();
+		assert!(eccentricity(&graph).unwrap().values().all(|&e| e == 2));
+		assert_eq!(diameter(&graph), Some(2));
+		assert_eq!(radius(&graph), Some(2));
+		assert_eq!(center(&graph), Some(v.clone()));
+		assert_eq!(periphery(&graph), Some(v.clone()));
+		assert_eq!(girth(&graph), Some(5));
+		assert_eq!(ifub_diameter(&graph), Some(2));
+
+		// A star with one long arm
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..6).map(|_| graph.add_vertex(())).collect();
+		for (a, b) in [(0, 1), (0, 2), (0, 3), (3, 4), (4, 5)] {
+			graph.add_edge(v[a], v[b]);
+		}
+		let eccentricities = eccentricity(&graph).unwrap();
+		assert_eq!(v.iter().map(|u| eccentricities[u]).collect::<Vec<_>>(), vec![3, 4, 4, 2, 3, 4]);
+		assert_eq!(center(&graph), Some(vec![v[3]]));
+		assert_eq!(periphery(&graph), Some(vec![v[1], v[2], v[5]]));
+		assert_eq!(girth(&graph), None);
+
+		// A parallel edge is not a cycle
+		graph.add_edge(v[1], v[0]);
+		assert_eq!(girth(&graph), None);
+
+		// Graphs that aren't connected and graphs without vertices
+		graph.add_vertex(());
+		assert_eq!(eccentricity(&graph), None);
+		assert_eq!(diameter(&graph), None);
+		assert_eq!(ifub_diameter(&graph), None);
+		assert_eq!(center(&graph), None);
+		let graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		assert_eq!(radius(&graph), None);
+		assert_eq!(ifub_diameter(&graph), None);
+		assert_eq!(girth(&graph), None);
+	}
+
+	#[test]
+	fn test_weighted() {
+		// A triangle where the direct edge 0–2 is longer than going around
+		let mut graph: UndirectedSparseGraph<(), f64> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+		for (a, b, w) in [(0, 1, 1.0), (1, 2, 1.5), (0, 2, 4.0), (2, 3, 0.5)] {
+			graph.set_edge_weight(v[a], v[b], w);
+		}
+
+		let eccentricities = weighted_eccentricity(&graph).unwrap();
+		assert_eq!(v.iter().map(|u| eccentricities[u]).collect::<Vec<_>>(), vec![3.0, 2.0, 2.5, 3.0]);
+		assert_eq!(weighted_diameter(&graph), Some(3.0));
+		assert_eq!(weighted_radius(&graph), Some(2.0));
+		assert_eq!(weighted_center(&graph), Some(vec![v[1]]));
+		assert_eq!(weighted_periphery(&graph), Some(vec![v[0], v[3]]));
+		assert_eq!(weighted_girth(&graph), Some(6.5));
+
+		graph.remove_edge(v[0], v[2]);
+		assert_eq!(weighted_girth(&graph), None);
+	}
+
+	#[test]
+	fn test_girth_random() {
+		for seed in 1..25 {
+			let graph = random_weighted_graph(12, 20, 9, seed);
+
+			// Brute force: the lightest path between the ends of every edge that avoids the edge
+			let mut expected: Option<u32> = None;
+			let v = graph.get_vertices();
+			for &a in &v {
+				for b in graph.get_neighbors(a).into_iter().filter(|&b| a < b) {
+					let w = graph.get_edge_weight(a, b).unwrap();
+					let mut without = graph.clone();
+					without.remove_edge(a, b);
+					let index = Indexed::new(&without);
+					let (dist, _) = dijkstra(&index.adj, &index.weights(&without), index.index[&a]);
+					if let Some(d) = dist[index.index[&b]] {
+						expected = Some(expected.map_or(d + w, |e| e.min(d + w)));
+					}
+				}
+			}
+			assert_eq!(weighted_girth(&graph), expected);
+
+			// With unit weights
+			let mut unit = graph.clone();
+			for &a in &v {
+				for b in graph.get_neighbors(a) {
+					unit.set_edge_weight(a, b, 1);
+				}
+			}
+			assert_eq!(girth(&graph).map(|g| g as u32), weighted_girth(&unit));
+		}
+	}
+
+	#[test]
+	fn test_ifub_diameter() {
+		for seed in 1..40 {
+			let graph = random_weighted_graph(60, 2 + seed % 6, 9, seed);
+			assert_eq!(ifub_diameter(&graph), diameter(&graph));
+		}
+
+		// A long cycle with chords
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..400).map(|_| graph.add_vertex(())).collect();
+		for i in 0..400 {
+			graph.add_edge(v[i], v[(i + 1) % 400]);
+			if i % 37 == 0 {
+				graph.add_edge(v[i], v[(i * 7 + 11) % 400]);
+			}
+		}
+		assert_eq!(ifub_diameter(&graph), diameter(&graph));
+	}
+}
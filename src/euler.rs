@@ -0,0 +1,458 @@
+//! Eulerian paths and circuits, and the route inspection problem.
+//!
+//! An Eulerian path traverses every edge of a graph exactly once, and an Eulerian circuit is one
+//! that ends where it started. Walks are returned as the sequence of vertex IDs they pass through,
+//! so a walk along `k` edges has `k + 1` vertices. A self loop adds two to the degree of its vertex.
+
+use std::error::Error;
+use std::fmt;
+
+use super::matching::max_weight_mates;
+use super::util::{dijkstra, Indexed};
+use super::{Graph, Weight, WeightedGraph};
+
+/// The error returned when a graph has no Eulerian path or circuit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EulerError {
+	/// The edges of the graph are not all in the same connected component.
+	Disconnected,
+	/// Too many vertices have an odd degree: a circuit allows none, and a path allows none or two.
+	OddDegree {
+		/// The IDs of the vertices with an odd degree, in the order they were added to the graph.
+		vertices: Vec<usize>,
+	},
+}
+
+impl fmt::Display for EulerError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Disconnected => write!(f, "the edges of the graph are not all connected"),
+			Self::OddDegree { vertices } => write!(f, "{} vertices have an odd degree: {:?}", vertices.len(), vertices),
+		}
+	}
+}
+
+impl Error for EulerError {}
+
+/// Lists the edges of an indexed graph once each, as pairs of indices.
+fn edge_list(index: &Indexed) -> Vec<(usize, usize)> {
+	index.adj.iter().enumerate()
+		.flat_map(|(u, neighbors)| neighbors.iter().filter(move |&&v| u <= v).map(move |&v| (u, v)))
+		.collect()
+}
+
+/// Finds the vertices with an odd number of edge ends.
+fn odd_vertices(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+	let mut odd = vec![false; n];
+	for &(u, v) in edges {
+		odd[u] = !odd[u];
+		odd[v] = !odd[v];
+	}
+	(0..n).filter(|&u| odd[u]).collect()
+}
+
+/// Walks every edge of a multigraph once with Hierholzer's algorithm, starting from a vertex.
+///
+/// Returns `None` if some edges can't be reached from the start. Otherwise the degrees are assumed
+/// to allow the walk.
+pub(crate) fn hierholzer(n: usize, edges: &[(usize, usize)], start: usize) -> Option<Vec<usize>> {
+	let mut incident: Vec<Vec<usize>> = vec![Vec::new(); n];
+	for (e, &(u, v)) in edges.iter().enumerate() {
+		incident[u].push(e);
+		if u != v {
+			incident[v].push(e);
+		}
+	}
+
+	let mut used = vec![false; edges.len()];
+	let mut next = vec![0; n]; // The position of the next edge to try at each vertex
+	let mut stack = vec![start];
+	let mut walk = Vec::with_capacity(edges.len() + 1);
+
+	// Follow unused edges until stuck, which can only happen back at the start of a detour. The
+	// vertices are added to the walk as the search backs out of them.
+	while let Some(&u) = stack.last() {
+		while next[u] < incident[u].len() && used[incident[u][next[u]]] {
+			next[u] += 1;
+		}
+
+		match incident[u].get(next[u]) {
+			Some(&e) => {
+				used[e] = true;
+				let (a, b) = edges[e];
+				stack.push(if a == u { b } else { a });
+			}
+			None => {
+				walk.push(u);
+				stack.pop();
+			}
+		}
+	}
+
+	walk.reverse();
+	(walk.len() == edges.len() + 1).then_some(walk)
+}
+
+/// Finds an Eulerian walk of an indexed graph, closed if `circuit` is set.
+fn eulerian_walk(index: &Indexed, circuit: bool) -> Result<Vec<usize>, EulerError> {
+	let edges = edge_list(index);
+	let odd = odd_vertices(index.len(), &edges);
+	if odd.len() > if circuit { 0 } else { 2 } {
+		return Err(EulerError::OddDegree { vertices: odd.into_iter().map(|u| index.ids[u]).collect() });
+	}
+
+	// A path has to start at a vertex of odd degree if there is one
+	let Some(start) = odd.first().copied().or_else(|| edges.first().map(|e| e.0)) else {
+		return Ok(Vec::new());
+	};
+
+	let walk = hierholzer(index.len(), &edges, start).ok_or(EulerError::Disconnected)?;
+	Ok(walk.into_iter().map(|u| index.ids[u]).collect())
+}
+
+/// Finds an Eulerian circuit of an undirected graph: a closed walk that traverses every edge
+/// exactly once.
+///
+/// A circuit exists if every vertex has an even degree and all edges are connected, and it is found
+/// with Hierholzer's algorithm in `O(V + E)` time. It starts and ends at the earliest added vertex
+/// with an edge. Vertices without edges are left out, and a graph without edges has an empty
+/// circuit.
+///
+/// Returns [`EulerError::OddDegree`] with the vertices of odd degree if there are any, or
+/// [`EulerError::Disconnected`] if the edges are not connected.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to find a circuit of.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::euler::eulerian_circuit;
+///
+/// // Two triangles sharing a vertex
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+/// for (a, b) in [(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 2)] {
+///     graph.add_edge(v[a], v[b]);
+/// }
+///
+/// let circuit = eulerian_circuit(&graph).unwrap();
+///
+/// assert_eq!(circuit.len(), 7);
+/// assert_eq!(circuit.first(), circuit.last());
+/// ```
+pub fn eulerian_circuit<T: Clone, G: Graph<T>>(graph: &G) -> Result<Vec<usize>, EulerError> {
+	eulerian_walk(&Indexed::new(graph), true)
+}
+
+/// Finds an Eulerian path of an undirected graph: a walk that traverses every edge exactly once.
+///
+/// A path exists if at most two vertices have an odd degree and all edges are connected. If there
+/// are two vertices of odd degree, the path goes from the earlier added one to the other; otherwise
+/// it is a circuit as found by [`eulerian_circuit`]. Runs in `O(V + E)` time.
+///
+/// Returns [`EulerError::OddDegree`] with the vertices of odd degree if there are more than two, or
+/// [`EulerError::Disconnected`] if the edges are not connected.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to find a path of.
+pub fn eulerian_path<T: Clone, G: Graph<T>>(graph: &G) -> Result<Vec<usize>, EulerError> {
+	eulerian_walk(&Indexed::new(graph), false)
+}
+
+/// A closed walk that traverses every edge of a graph at least once, as returned by
+/// [`chinese_postman`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PostmanTour<W> {
+	/// The IDs of the vertices along the walk, which starts and ends at the same vertex.
+	pub walk: Vec<usize>,
+	/// The total weight of the walk, counting every traversal of an edge.
+	pub weight: W,
+	/// The edges traversed more than once, as pairs of vertex IDs, once for every extra traversal.
+	pub repeated_edges: Vec<(usize, usize)>,
+}
+
+/// Solves the Chinese postman problem: finds a shortest closed walk that traverses every edge of
+/// a weighted undirected graph at least once.
+///
+/// If every vertex has an even degree, the walk is an Eulerian circuit. Otherwise some edges have
+/// to be walked again to pair up the vertices of odd degree, and the cheapest way to do so is along
+/// the shortest paths of a minimum weight perfect matching between them, found with Edmonds'
+/// blossom algorithm. Takes `O(V^3 + VE log V)` time. Edge weights must not be negative.
+///
+/// Returns `None` if the edges of the graph are not all connected. The walk starts and ends at the
+/// earliest added vertex with an edge, and it is empty if the graph has no edges.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted undirected graph to find a walk of.
+///
+/// # Panics
+///
+/// Panics if four times the longest shortest path between two vertices of odd degree overflows the
+/// weight type, which the matching needs room for.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph, WeightedGraph};
+/// use istos::euler::chinese_postman;
+///
+/// // A square with a diagonal, whose ends have an odd degree
+/// let mut graph: UndirectedGraph<(), u32> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+/// for (a, b, w) in [(0, 1, 2), (1, 2, 2), (2, 3, 2), (3, 0, 2), (0, 2, 3)] {
+///     graph.set_edge_weight(v[a], v[b], w);
+/// }
+///
+/// let tour = chinese_postman(&graph).unwrap();
+///
+/// assert_eq!(tour.weight, 14);
+/// assert_eq!(tour.repeated_edges, vec![(v[0], v[2])]);
+/// assert_eq!(tour.walk.len(), 7);
+/// ```
+pub fn chinese_postman<T, W, G>(graph: &G) -> Option<PostmanTour<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let n = index.len();
+
+	let mut edges = edge_list(&index);
+	let mut weight = W::zero();
+	for (u, neighbors) in index.adj.iter().enumerate() {
+		for (&v, &w) in neighbors.iter().zip(&weights[u]) {
+			if u <= v {
+				weight = weight + w;
+			}
+		}
+	}
+
+	// Find the shortest paths between the vertices of odd degree
+	let odd = odd_vertices(n, &edges);
+	let mut distances = Vec::with_capacity(odd.len());
+	let mut parents = Vec::with_capacity(odd.len());
+	for &u in &odd {
+		let (dist, parent) = dijkstra(&index.adj, &weights, u);
+		distances.push(dist);
+		parents.push(parent);
+	}
+
+	// Pair them up with a maximum weight matching on the distances subtracted from a weight larger
+	// than any of them. Every pair gets a positive weight, so two unmatched vertices could always be
+	// matched to each other, which makes the matching perfect
+	let farthest = distances.iter()
+		.flat_map(|dist| odd.iter().filter_map(move |&v| dist[v]))
+		.fold(W::zero(), |max, d| if d > max { d } else { max });
+	let bound = farthest + W::one();
+	let mut pairs = Vec::new();
+	for (i, dist) in distances.iter().enumerate() {
+		for (j, &v) in odd.iter().enumerate().skip(i + 1) {
+			pairs.push((i, j, bound - dist[v]?));
+		}
+	}
+	let mates = max_weight_mates(odd.len(), pairs);
+
+	// Walk the shortest path between every pair again
+	let mut repeated_edges = Vec::new();
+	for (i, mate) in mates.into_iter().enumerate() {
+		let j = mate.expect("the matching is perfect");
+		if i < j {
+			weight = weight + distances[i][odd[j]].unwrap();
+			let mut v = odd[j];
+			while let Some(p) = parents[i][v] {
+				edges.push((p, v));
+				repeated_edges.push((index.ids[p.min(v)], index.ids[p.max(v)]));
+				v = p;
+			}
+		}
+	}
+
+	let walk = match edges.first() {
+		Some(&(start, _)) => hierholzer(n, &edges, start)?.into_iter().map(|u| index.ids[u]).collect(),
+		None => Vec::new(),
+	};
+	repeated_edges.sort_unstable();
+
+	Some(PostmanTour { walk, weight, repeated_edges })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::{random_graph, Rng};
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+
+	/// Checks that a walk traverses the edges of a graph, and every edge once unless it is given
+	/// as repeated.
+	fn is_covering_walk<T: Clone, G: Graph<T>>(graph: &G, walk: &[usize], repeated: &[(usize, usize)]) -> bool {
+		let mut remaining: Vec<(usize, usize)> = graph.get_vertices().into_iter()
+			.flat_map(|u| graph.get_neighbors(u).into_iter().filter(move |&v| u <= v).map(move |v| (u, v)))
+			.chain(repeated.iter().copied())
+			.collect();
+
+		for step in walk.windows(2) {
+			let edge = (step[0].min(step[1]), step[0].max(step[1]));
+			let Some(position) = remaining.iter().position(|&e| e == edge) else {
+				return false;
+			};
+			remaining.swap_remove(position);
+		}
+		remaining.is_empty()
+	}
+
+	#[test]
+	fn test_eulerian_circuit() {
+		// Two squares sharing a vertex, with a self loop
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..8).map(|_| graph.add_vertex(())).collect();
+		for (a, b) in [(0, 1), (1, 2), (2, 3), (3, 0), (3, 4), (4, 5), (5, 6), (6, 3), (5, 5)] {
+			graph.add_edge(v[a], v[b]);
+		}
+
+		let circuit = eulerian_circuit(&graph).unwrap();
+		assert_eq!(circuit.first(), Some(&v[0]));
+		assert_eq!(circuit.last(), Some(&v[0]));
+		assert!(is_covering_walk(&graph, &circuit, &[]));
+		assert_eq!(eulerian_path(&graph).unwrap(), circuit);
+
+		// An edge between the squares makes two vertices odd
+		graph.add_edge(v[1], v[6]);
+		assert_eq!(eulerian_circuit(&graph), Err(EulerError::OddDegree { vertices: vec![v[1], v[6]] }));
+
+		let path = eulerian_path(&graph).unwrap();
+		assert_eq!(path.first(), Some(&v[1]));
+		assert_eq!(path.last(), Some(&v[6]));
+		assert!(is_covering_walk(&graph, &path, &[]));
+	}
+
+	#[test]
+	fn test_eulerian_errors() {
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		assert_eq!(eulerian_circuit(&graph), Ok(Vec::new()));
+
+		// A star with three leaves
+		let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+		assert_eq!(eulerian_path(&graph), Ok(Vec::new()));
+		for i in 1..4 {
+			graph.add_edge(v[0], v[i]);
+		}
+		let error = eulerian_path(&graph).unwrap_err();
+		assert_eq!(error, EulerError::OddDegree { vertices: v.clone() });
+		assert_eq!(error.to_string(), format!("4 vertices have an odd degree: {:?}", v));
+
+		// Two separate triangles
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..6).map(|_| graph.add_vertex(())).collect();
+		for i in 0..6 {
+			graph.add_edge(v[i], v[i / 3 * 3 + (i + 1) % 3]);
+		}
+		assert_eq!(eulerian_circuit(&graph), Err(EulerError::Disconnected));
+		assert_eq!(eulerian_path(&graph), Err(EulerError::Disconnected));
+	}
+
+	#[test]
+	fn test_eulerian_path_random() {
+		for seed in 0..50 {
+			let graph = random_graph(8, 45, seed);
+			let v = graph.get_vertices();
+
+			let odd = v.iter().filter(|&&u| graph.get_neighbors(u).len() % 2 == 1).count();
+			let connected = crate::connectivity::connected_components(&graph).iter().filter(|c| c.len() > 1).count() <= 1;
+			match eulerian_path(&graph) {
+				Ok(path) => {
+					assert!(odd <= 2 && connected);
+					assert!(is_covering_walk(&graph, &path, &[]));
+				}
+				Err(EulerError::OddDegree { vertices }) => assert!(vertices.len() == odd && odd > 2),
+				Err(EulerError::Disconnected) => assert!(!connected),
+			}
+		}
+	}
+
+	#[test]
+	fn test_chinese_postman() {
+		// A path of three edges has to be walked back entirely
+		let mut graph: UndirectedSparseGraph<(), f64> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+		graph.set_edge_weight(v[0], v[1], 1.0);
+		graph.set_edge_weight(v[1], v[2], 2.5);
+		graph.set_edge_weight(v[2], v[3], 0.5);
+
+		let tour = chinese_postman(&graph).unwrap();
+		assert_eq!(tour.weight, 8.0);
+		assert_eq!(tour.walk.first(), tour.walk.last());
+		assert!(is_covering_walk(&graph, &tour.walk, &tour.repeated_edges));
+
+		// Edges in separate components can't be walked in one go
+		let u = graph.add_vertex(());
+		let w = graph.add_vertex(());
+		graph.set_edge_weight(u, w, 1.0);
+		assert!(chinese_postman(&graph).is_none());
+
+		let graph: UndirectedSparseGraph<(), u32> = UndirectedSparseGraph::new();
+		assert_eq!(chinese_postman(&graph), Some(PostmanTour { walk: Vec::new(), weight: 0, repeated_edges: Vec::new() }));
+
+		// A star with six leaves, whose total fits in a small weight type
+		let mut graph: UndirectedSparseGraph<(), u8> = UndirectedSparseGraph::new();
+		let center = graph.add_vertex(());
+		for _ in 0..6 {
+			let leaf = graph.add_vertex(());
+			graph.set_edge_weight(center, leaf, 7);
+		}
+		let tour = chinese_postman(&graph).unwrap();
+		assert_eq!(tour.weight, 84);
+		assert_eq!(tour.repeated_edges.len(), 6);
+	}
+
+	#[test]
+	fn test_chinese_postman_random() {
+		let mut rng = Rng::new(11);
+
+		for _ in 0..30 {
+			let mut graph: UndirectedSparseGraph<(), u64> = UndirectedSparseGraph::new();
+			let v: Vec<usize> = (0..9).map(|_| graph.add_vertex(())).collect();
+			for i in 1..9 {
+				// A random tree keeps the graph connected, and random edges add cycles
+				let parent = v[rng.below(i)];
+				graph.set_edge_weight(v[i], parent, rng.below(10) as u64);
+			}
+			for _ in 0..6 {
+				let (a, b) = (v[rng.below(9)], v[rng.below(9)]);
+				if a != b && !graph.is_adjacent(a, b) {
+					graph.set_edge_weight(a, b, rng.below(10) as u64);
+				}
+			}
+
+			// Pair up the odd vertices in every possible way
+			let paths = crate::shortest_paths::floyd_warshall(&graph).unwrap();
+			let odd: Vec<usize> = v.iter().copied().filter(|&u| graph.get_neighbors(u).len() % 2 == 1).collect();
+			fn cheapest(odd: &[usize], distance: &dyn Fn(usize, usize) -> u64) -> u64 {
+				let Some((&first, rest)) = odd.split_first() else { return 0 };
+				(0..rest.len())
+					.map(|i| {
+						let others: Vec<usize> = rest.iter().enumerate().filter(|&(j, _)| j != i).map(|(_, &u)| u).collect();
+						distance(first, rest[i]) + cheapest(&others, distance)
+					})
+					.min()
+					.unwrap()
+			}
+			let edge_total: u64 = v.iter()
+				.flat_map(|&u| graph.get_neighbors(u).into_iter().filter(move |&w| u < w).map(move |w| (u, w)))
+				.map(|(u, w)| graph.get_edge_weight(u, w).unwrap())
+				.sum();
+			let expected = edge_total + cheapest(&odd, &|a, b| paths.distance(a, b).unwrap());
+
+			let tour = chinese_postman(&graph).unwrap();
+			assert_eq!(tour.weight, expected);
+			assert_eq!(tour.walk.first(), tour.walk.last());
+			assert!(is_covering_walk(&graph, &tour.walk, &tour.repeated_edges));
+		}
+	}
+}
@@ -0,0 +1,1000 @@
+//! Maximum flow, minimum s–t cut and minimum cost flow.
+//!
+//! The algorithms in this module take the capacities (and costs) either from the edge weights of a
+//! [`WeightedGraph`], or from a closure that returns them for the edge from one vertex to another.
+//! Every vertex returned by [`Graph::get_neighbors`] is the head of an edge, so an undirected edge
+//! can carry flow in both directions, and with a closure the two directions may differ. Capacities
+//! must not be negative.
+
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+use super::util::{Indexed, MinScored};
+use super::{Graph, Weight, WeightedGraph};
+
+/// A maximum flow between two vertices, with a minimum cut separating them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaxFlow<W> {
+	/// The total amount of flow from the source to the sink.
+	pub value: W,
+	/// The flow along the edges that carry any, as `(from, to, amount)` with vertex IDs. Flow in
+	/// opposite directions between the same two vertices is cancelled out, so there is at most one
+	/// entry for every pair of vertices.
+	pub flows: Vec<(usize, usize, W)>,
+	/// The IDs of the vertices on the source side of a minimum cut: those that can still be reached
+	/// from the source through edges with spare capacity.
+	pub source_side: Vec<usize>,
+	/// The IDs of the remaining vertices, which are on the sink side of the cut.
+	pub sink_side: Vec<usize>,
+}
+
+/// A residual network. Arcs are stored in pairs, so the reverse of arc `a` is arc `a ^ 1`.
+#[derive(Clone, Debug)]
+pub(crate) struct Network<W> {
+	pub heads: Vec<usize>, // The vertex each arc leads to
+	pub residual: Vec<W>, // The remaining capacity of each arc
+	pub capacity: Vec<W>, // The original capacity of each arc
+	pub arcs: Vec<Vec<usize>>, // The arcs leaving each vertex
+}
+
+impl<W: Weight> Network<W> {
+	/// Creates a network without arcs.
+	pub fn new(n: usize) -> Self {
+		Self {
+			heads: Vec::new(),
+			residual: Vec::new(),
+			capacity: Vec::new(),
+			arcs: vec![Vec::new(); n],
+		}
+	}
+
+	/// Builds the network of an indexed graph, with a capacity for every edge in `index.adj`.
+	pub fn from_indexed(index: &Indexed, mut capacity: impl FnMut(usize, usize) -> W) -> Self {
+		let mut network = Self::new(index.len());
+		for (u, neighbors) in index.adj.iter().enumerate() {
+			for &v in neighbors {
+				network.add_arc(u, v, capacity(u, v));
+			}
+		}
+		network
+	}
+
+	/// Builds the network of an indexed graph, with the weight of every edge as its capacity.
+	pub fn from_weights(index: &Indexed, weights: &[Vec<W>]) -> Self {
+		let mut network = Self::new(index.len());
+		for (u, neighbors) in index.adj.iter().enumerate() {
+			for (&v, &w) in neighbors.iter().zip(&weights[u]) {
+				network.add_arc(u, v, w);
+			}
+		}
+		network
+	}
+
+	/// The number of vertices.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.arcs.len()
+	}
+
+	/// Adds an arc and its reverse, which starts out without capacity.
+	pub fn add_arc(&mut self, from: usize, to: usize, capacity: W) -> usize {
+		let arc = self.heads.len();
+
+		self.heads.push(to);
+		self.residual.push(capacity);
+		self.capacity.push(capacity);
+		self.arcs[from].push(arc);
+
+		self.heads.push(from);
+		self.residual.push(W::zero());
+		self.capacity.push(W::zero());
+		self.arcs[to].push(arc + 1);
+
+		arc
+	}
+
+	/// Moves flow along an arc.
+	#[inline]
+	fn push(&mut self, arc: usize, amount: W) {
+		self.residual[arc] = self.residual[arc] - amount;
+		self.residual[arc ^ 1] = self.residual[arc ^ 1] + amount;
+	}
+
+	/// Gets the flow on an arc.
+	#[inline]
+	pub fn flow(&self, arc: usize) -> W {
+		self.capacity[arc] - self.residual[arc]
+	}
+
+	/// Finds the vertices reachable from a source through arcs with residual capacity.
+	pub fn reachable(&self, source: usize) -> Vec<bool> {
+		let mut reached = vec![false; self.len()];
+		let mut queue = VecDeque::from([source]);
+		reached[source] = true;
+
+		while let Some(u) = queue.pop_front() {
+			for &arc in &self.arcs[u] {
+				let v = self.heads[arc];
+				if !reached[v] && self.residual[arc] > W::zero() {
+					reached[v] = true;
+					queue.push_back(v);
+				}
+			}
+		}
+
+		reached
+	}
+
+	/// Computes a maximum flow by augmenting along shortest paths (Edmonds–Karp).
+	pub fn edmonds_karp(&mut self, source: usize, sink: usize) {
+		loop {
+			// Find a shortest augmenting path with a breadth-first search
+			let mut parent_arc: Vec<Option<usize>> = vec![None; self.len()];
+			let mut queue = VecDeque::from([source]);
+			let mut found = false;
+
+			'search: while let Some(u) = queue.pop_front() {
+				for &arc in &self.arcs[u] {
+					let v = self.heads[arc];
+					if v != source && parent_arc[v].is_none() && self.residual[arc] > W::zero() {
+						parent_arc[v] = Some(arc);
+						if v == sink {
+							found = true;
+							break 'search;
+						}
+						queue.push_back(v);
+					}
+				}
+			}
+
+			if !found {
+				return;
+			}
+
+			// Find the bottleneck and push that much flow along the path
+			let path = self.path_to(sink, &parent_arc);
+			let amount = self.bottleneck(&path);
+			for &arc in &path {
+				self.push(arc, amount);
+			}
+		}
+	}
+
+	/// Computes a maximum flow with Dinic's algorithm, which augments along blocking flows in the
+	/// level graph of the residual network.
+	pub fn dinic(&mut self, source: usize, sink: usize) {
+		let n = self.len();
+
+		loop {
+			// Assign levels with a breadth-first search from the source
+			let mut level: Vec<Option<usize>> = vec![None; n];
+			let mut queue = VecDeque::from([source]);
+			level[source] = Some(0);
+			while let Some(u) = queue.pop_front() {
+				for &arc in &self.arcs[u] {
+					let v = self.heads[arc];
+					if level[v].is_none() && self.residual[arc] > W::zero() {
+						level[v] = Some(level[u].unwrap() + 1);
+						queue.push_back(v);
+					}
+				}
+			}
+
+			if level[sink].is_none() {
+				return;
+			}
+
+			// Find a blocking flow with depth-first searches that only go down one level at a time.
+			// The next arc to try from each vertex is remembered, so every arc is skipped at most once.
+			let mut next_arc = vec![0; n];
+			let mut path: Vec<usize> = Vec::new();
+			let mut u = source;
+			loop {
+				if u == sink {
+					let amount = self.bottleneck(&path);
+					for &arc in &path {
+						self.push(arc, amount);
+					}
+					path.clear();
+					u = source;
+					continue;
+				}
+
+				let advance = self.arcs[u][next_arc[u]..].iter().position(|&arc| {
+					let v = self.heads[arc];
+					self.residual[arc] > W::zero() && level[v].is_some() && level[v] == level[u].map(|l| l + 1)
+				});
+
+				match advance {
+					Some(offset) => {
+						next_arc[u] += offset;
+						let arc = self.arcs[u][next_arc[u]];
+						path.push(arc);
+						u = self.heads[arc];
+					}
+					None => {
+						// Dead end, so remove u from the level graph and retreat
+						next_arc[u] = self.arcs[u].len();
+						level[u] = None;
+						let Some(arc) = path.pop() else { break; };
+						u = self.heads[arc ^ 1];
+						next_arc[u] += 1;
+					}
+				}
+			}
+		}
+	}
+
+	/// Computes a maximum flow with the highest-label push–relabel algorithm.
+	pub fn push_relabel(&mut self, source: usize, sink: usize) {
+		let n = self.len();
+
+		let mut height = vec![0; n];
+		let mut excess = vec![W::zero(); n];
+		let mut next_arc = vec![0; n];
+
+		// The active vertices (those with excess other than the source and sink) by height. Heights
+		// never exceed 2n - 1.
+		let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); 2 * n];
+		let mut highest = 0;
+
+		// Saturate all arcs leaving the source
+		height[source] = n;
+		for i in 0..self.arcs[source].len() {
+			let arc = self.arcs[source][i];
+			let amount = self.residual[arc];
+			if amount > W::zero() {
+				let v = self.heads[arc];
+				self.push(arc, amount);
+				if v != source && v != sink && excess[v] <= W::zero() {
+					buckets[0].push(v);
+				}
+				excess[v] = excess[v] + amount;
+			}
+		}
+
+		loop {
+			// Select an active vertex with the highest label
+			while highest > 0 && buckets[highest].is_empty() {
+				highest -= 1;
+			}
+			let Some(u) = buckets[highest].pop() else { return; };
+
+			// Discharge the vertex: push its excess to lower neighbors, and relabel it when it has
+			// none left
+			while excess[u] > W::zero() {
+				if next_arc[u] == self.arcs[u].len() {
+					height[u] = self.arcs[u].iter()
+						.filter(|&&arc| self.residual[arc] > W::zero())
+						.map(|&arc| height[self.heads[arc]] + 1)
+						.min()
+						.expect("a vertex with excess has a residual arc back towards the source");
+					next_arc[u] = 0;
+					continue;
+				}
+
+				let arc = self.arcs[u][next_arc[u]];
+				let v = self.heads[arc];
+				if self.residual[arc] > W::zero() && height[u] == height[v] + 1 {
+					let amount = if excess[u] < self.residual[arc] { excess[u] } else { self.residual[arc] };
+					self.push(arc, amount);
+
+					if v != source && v != sink && excess[v] <= W::zero() {
+						buckets[height[v]].push(v);
+						highest = highest.max(height[v]);
+					}
+					excess[v] = excess[v] + amount;
+					excess[u] = excess[u] - amount;
+				} else {
+					next_arc[u] += 1;
+				}
+			}
+		}
+	}
+
+	/// Follows the parent arcs back from a vertex to the start of the search.
+	fn path_to(&self, mut vertex: usize, parent_arc: &[Option<usize>]) -> Vec<usize> {
+		let mut path = Vec::new();
+		while let Some(arc) = parent_arc[vertex] {
+			path.push(arc);
+			vertex = self.heads[arc ^ 1];
+		}
+		path
+	}
+
+	/// Finds the smallest residual capacity along a path.
+	fn bottleneck(&self, path: &[usize]) -> W {
+		path.iter()
+			.map(|&arc| self.residual[arc])
+			.reduce(|a, b| if b < a { b } else { a })
+			.expect("augmenting paths are not empty")
+	}
+
+	/// Gets the net amount of flow leaving a vertex.
+	pub fn outflow(&self, vertex: usize) -> W {
+		// The flow on a reverse arc is the negated flow on its arc
+		self.arcs[vertex].iter().fold(W::zero(), |total, &arc| total + self.flow(arc))
+	}
+
+	/// Collects the flow between every pair of vertices that carries any, with vertex IDs.
+	fn net_flows(&self, index: &Indexed) -> Vec<(usize, usize, W)> {
+		// Sum up the flow from every vertex to every other vertex
+		let mut totals: HashMap<(usize, usize), W> = HashMap::new();
+		let mut pairs: Vec<(usize, usize)> = Vec::new();
+		for arc in (0..self.heads.len()).step_by(2) {
+			let pair = (self.heads[arc + 1], self.heads[arc]);
+			let flow = self.flow(arc);
+			if flow > W::zero() {
+				let total = totals.entry(pair).or_insert_with(|| {
+					pairs.push(pair);
+					W::zero()
+				});
+				*total = *total + flow;
+			}
+		}
+
+		// Cancel out flow in opposite directions between the same vertices
+		let mut flows = Vec::new();
+		for (u, v) in pairs {
+			let forward = totals.get(&(u, v)).copied().unwrap_or(W::zero());
+			let backward = totals.get(&(v, u)).copied().unwrap_or(W::zero());
+			if forward > backward {
+				flows.push((index.ids[u], index.ids[v], forward - backward));
+			}
+		}
+
+		flows
+	}
+
+	/// Collects the result after computing a maximum flow.
+	fn max_flow(&self, index: &Indexed, source: usize) -> MaxFlow<W> {
+		let reached = self.reachable(source);
+		let (source_side, sink_side): (Vec<usize>, Vec<usize>) = (0..self.len()).partition(|&u| reached[u]);
+
+		MaxFlow {
+			value: self.outflow(source),
+			flows: self.net_flows(index),
+			source_side: source_side.into_iter().map(|u| index.ids[u]).collect(),
+			sink_side: sink_side.into_iter().map(|u| index.ids[u]).collect(),
+		}
+	}
+}
+
+/// The algorithms that can compute a maximum flow on a network.
+#[derive(Clone, Copy)]
+enum Algorithm {
+	EdmondsKarp,
+	Dinic,
+	PushRelabel,
+}
+
+/// Sets up the network, runs an algorithm on it and collects the result.
+fn run<T, W, G, F>(graph: &G, source: usize, sink: usize, mut capacity: F, algorithm: Algorithm) -> Option<MaxFlow<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: Graph<T>,
+	F: FnMut(usize, usize) -> W,
+{
+	let index = Indexed::new(graph);
+	let s = *index.index.get(&source)?;
+	let t = *index.index.get(&sink)?;
+	if s == t {
+		return None;
+	}
+
+	let mut network = Network::from_indexed(&index, |u, v| capacity(index.ids[u], index.ids[v]));
+	match algorithm {
+		Algorithm::EdmondsKarp => network.edmonds_karp(s, t),
+		Algorithm::Dinic => network.dinic(s, t),
+		Algorithm::PushRelabel => network.push_relabel(s, t),
+	}
+
+	Some(network.max_flow(&index, s))
+}
+
+/// Looks up the weight of an edge as its capacity.
+fn edge_weight<T: Clone, W: Clone, G: WeightedGraph<T, W>>(graph: &G) -> impl FnMut(usize, usize) -> W + '_ {
+	|u, v| graph.get_edge_weight(u, v).expect("neighbors are connected by an edge")
+}
+
+/// Computes a maximum flow using the Edmonds–Karp algorithm, with the edge weights as capacities.
+///
+/// Repeatedly augments the flow along a shortest path with spare capacity, which takes `O(VE^2)`
+/// time.
+///
+/// Returns `None` if either vertex does not exist or if they are the same vertex.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find a flow in.
+/// - `source`: The ID of the vertex the flow starts at.
+/// - `sink`: The ID of the vertex the flow ends at.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedSparseGraph, WeightedGraph};
+/// use istos::flow::edmonds_karp;
+///
+/// let mut graph: UndirectedSparseGraph<(), u32> = UndirectedSparseGraph::new();
+/// let s = graph.add_vertex(());
+/// let a = graph.add_vertex(());
+/// let b = graph.add_vertex(());
+/// let t = graph.add_vertex(());
+///
+/// graph.set_edge_weight(s, a, 3);
+/// graph.set_edge_weight(s, b, 2);
+/// graph.set_edge_weight(a, t, 2);
+/// graph.set_edge_weight(b, t, 3);
+/// graph.set_edge_weight(a, b, 1);
+///
+/// let flow = edmonds_karp(&graph, s, t).unwrap();
+///
+/// assert_eq!(flow.value, 5);
+/// assert_eq!(flow.source_side, vec![s]);
+/// ```
+pub fn edmonds_karp<T, W, G>(graph: &G, source: usize, sink: usize) -> Option<MaxFlow<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	run(graph, source, sink, edge_weight(graph), Algorithm::EdmondsKarp)
+}
+
+/// Computes a maximum flow using the Edmonds–Karp algorithm, with capacities given by a closure.
+///
+/// Returns `None` if either vertex does not exist or if they are the same vertex.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find a flow in.
+/// - `source`: The ID of the vertex the flow starts at.
+/// - `sink`: The ID of the vertex the flow ends at.
+/// - `capacity`: A function that returns the capacity of the edge from one vertex ID to another.
+pub fn edmonds_karp_with<T, W, G, F>(graph: &G, source: usize, sink: usize, capacity: F) -> Option<MaxFlow<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: Graph<T>,
+	F: FnMut(usize, usize) -> W,
+{
+	run(graph, source, sink, capacity, Algorithm::EdmondsKarp)
+}
+
+/// Computes a maximum flow using Dinic's algorithm, with the edge weights as capacities.
+///
+/// Augments the flow along blocking flows of the level graph, which takes `O(V^2 E)` time.
+///
+/// Returns `None` if either vertex does not exist or if they are the same vertex.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find a flow in.
+/// - `source`: The ID of the vertex the flow starts at.
+/// - `sink`: The ID of the vertex the flow ends at.
+pub fn dinic<T, W, G>(graph: &G, source: usize, sink: usize) -> Option<MaxFlow<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	run(graph, source, sink, edge_weight(graph), Algorithm::Dinic)
+}
+
+/// Computes a maximum flow using Dinic's algorithm, with capacities given by a closure.
+///
+/// Returns `None` if either vertex does not exist or if they are the same vertex.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find a flow in.
+/// - `source`: The ID of the vertex the flow starts at.
+/// - `sink`: The ID of the vertex the flow ends at.
+/// - `capacity`: A function that returns the capacity of the edge from one vertex ID to another.
+pub fn dinic_with<T, W, G, F>(graph: &G, source: usize, sink: usize, capacity: F) -> Option<MaxFlow<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: Graph<T>,
+	F: FnMut(usize, usize) -> W,
+{
+	run(graph, source, sink, capacity, Algorithm::Dinic)
+}
+
+/// Computes a maximum flow using the highest-label push–relabel algorithm, with the edge weights as
+/// capacities.
+///
+/// Always discharges the active vertex with the highest label, which takes `O(V^2 sqrt(E))` time.
+///
+/// Returns `None` if either vertex does not exist or if they are the same vertex.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find a flow in.
+/// - `source`: The ID of the vertex the flow starts at.
+/// - `sink`: The ID of the vertex the flow ends at.
+pub fn push_relabel<T, W, G>(graph: &G, source: usize, sink: usize) -> Option<MaxFlow<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	run(graph, source, sink, edge_weight(graph), Algorithm::PushRelabel)
+}
+
+/// Computes a maximum flow using the highest-label push–relabel algorithm, with capacities given by
+/// a closure.
+///
+/// Returns `None` if either vertex does not exist or if they are the same vertex.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find a flow in.
+/// - `source`: The ID of the vertex the flow starts at.
+/// - `sink`: The ID of the vertex the flow ends at.
+/// - `capacity`: A function that returns the capacity of the edge from one vertex ID to another.
+pub fn push_relabel_with<T, W, G, F>(graph: &G, source: usize, sink: usize, capacity: F) -> Option<MaxFlow<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: Graph<T>,
+	F: FnMut(usize, usize) -> W,
+{
+	run(graph, source, sink, capacity, Algorithm::PushRelabel)
+}
+
+/// The capacity and cost of an edge in a minimum cost flow problem.
+///
+/// Use this as the weight type of a graph to find a minimum cost flow in it with
+/// [`min_cost_flow`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FlowEdge<W> {
+	/// The maximum amount of flow the edge can carry.
+	pub capacity: W,
+	/// The cost of sending one unit of flow along the edge.
+	pub cost: W,
+}
+
+/// A minimum cost flow between two vertices.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinCostFlow<W> {
+	/// The total cost of the flow.
+	pub cost: W,
+	/// The flow along the edges that carry any, as `(from, to, amount)` with vertex IDs. There is at
+	/// most one entry for every pair of vertices.
+	pub flows: Vec<(usize, usize, W)>,
+}
+
+/// The error returned when a minimum cost flow can't be found.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MinCostFlowError<W> {
+	/// The source or the sink does not exist, or they are the same vertex.
+	InvalidVertices,
+	/// The edges contain a cycle of negative total cost, so the cost can be lowered indefinitely.
+	/// In an undirected graph, every edge with a negative cost forms such a cycle.
+	NegativeCycle,
+	/// The demand can't be met, because the maximum flow from the source to the sink is lower.
+	Infeasible {
+		/// The maximum flow from the source to the sink.
+		max_flow: W,
+	},
+}
+
+impl<W: fmt::Debug> fmt::Display for MinCostFlowError<W> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::InvalidVertices => write!(f, "the source and sink must be two different vertices of the graph"),
+			Self::NegativeCycle => write!(f, "the graph contains a cycle of negative cost"),
+			Self::Infeasible { max_flow } => write!(f, "the demand exceeds the maximum flow of {:?}", max_flow),
+		}
+	}
+}
+
+impl<W: fmt::Debug> Error for MinCostFlowError<W> {}
+
+/// Sends `demand` units of flow from the source to the sink at minimum cost, using successive
+/// shortest paths. `costs` holds the cost of every arc of the network (not its reverse).
+fn successive_shortest_paths<W: Weight>(
+	network: &mut Network<W>,
+	costs: &[W],
+	source: usize,
+	sink: usize,
+	demand: W,
+) -> Result<(), MinCostFlowError<W>> {
+	let n = network.len();
+
+	// Find initial potentials with Bellman-Ford, which also handles negative costs. Only the arcs
+	// themselves have capacity at this point, not their reverses.
+	let mut potential = vec![W::zero(); n];
+	for round in 0..=n {
+		let mut changed = false;
+		for arc in (0..network.heads.len()).step_by(2) {
+			let (u, v) = (network.heads[arc + 1], network.heads[arc]);
+			if network.residual[arc] > W::zero() && potential[u] + costs[arc / 2] < potential[v] {
+				potential[v] = potential[u] + costs[arc / 2];
+				changed = true;
+			}
+		}
+
+		if !changed {
+			break;
+		}
+		if round == n {
+			return Err(MinCostFlowError::NegativeCycle);
+		}
+	}
+
+	let mut sent = W::zero();
+	while sent < demand {
+		// Find a cheapest augmenting path with Dijkstra's algorithm on the costs reduced by the
+		// potentials, which are never negative. The reduced cost of an arc from u to v is
+		// cost + potential[u] - potential[v], where a reverse arc has the negated cost.
+		let reduced = |network: &Network<W>, potential: &[W], arc: usize| {
+			let (u, v) = (network.heads[arc ^ 1], network.heads[arc]);
+			let cost = costs[arc / 2];
+			if arc.is_multiple_of(2) {
+				cost + potential[u] - potential[v]
+			} else {
+				potential[u] - (cost + potential[v])
+			}
+		};
+
+		let mut dist: Vec<Option<W>> = vec![None; n];
+		let mut parent_arc: Vec<Option<usize>> = vec![None; n];
+		let mut settled = vec![false; n];
+		let mut heap = BinaryHeap::new();
+		dist[source] = Some(W::zero());
+		heap.push(MinScored(W::zero(), source));
+
+		while let Some(MinScored(d, u)) = heap.pop() {
+			if settled[u] {
+				continue;
+			}
+			settled[u] = true;
+			if u == sink {
+				break;
+			}
+
+			for &arc in &network.arcs[u] {
+				let v = network.heads[arc];
+				if settled[v] || network.residual[arc] <= W::zero() {
+					continue;
+				}
+
+				let candidate = d + reduced(network, &potential, arc);
+				if dist[v].is_none_or(|dv| candidate < dv) {
+					dist[v] = Some(candidate);
+					parent_arc[v] = Some(arc);
+					heap.push(MinScored(candidate, v));
+				}
+			}
+		}
+
+		let Some(sink_dist) = dist[sink] else {
+			return Err(MinCostFlowError::Infeasible { max_flow: sent });
+		};
+
+		// Update the potentials, capping the distances at the distance of the sink so that the
+		// reduced costs stay non-negative for vertices the search didn't settle
+		for v in 0..n {
+			let d = match dist[v] {
+				Some(d) if settled[v] && d < sink_dist => d,
+				_ => sink_dist,
+			};
+			potential[v] = potential[v] + d;
+		}
+
+		// Push as much as the path and the remaining demand allow
+		let path = network.path_to(sink, &parent_arc);
+		let bottleneck = network.bottleneck(&path);
+		let remaining = demand - sent;
+		let amount = if remaining < bottleneck { remaining } else { bottleneck };
+		for &arc in &path {
+			network.push(arc, amount);
+		}
+		sent = sent + amount;
+	}
+
+	Ok(())
+}
+
+/// Computes a minimum cost flow, with the capacities and costs given by the edge weights.
+///
+/// Sends `demand` units of flow from the source to the sink as cheaply as possible, using the
+/// successive shortest path algorithm. The costs are reduced with vertex potentials (as in
+/// Johnson's algorithm), so every shortest path can be found with Dijkstra's algorithm. Costs may
+/// be negative, as long as they don't form a negative cycle.
+///
+/// Returns an error if either vertex does not exist or they are the same vertex, if there is a
+/// negative cycle, or if the demand exceeds the maximum flow.
+///
+/// # Arguments
+///
+/// - `graph`: The graph with [`FlowEdge`] weights to find a flow in.
+/// - `source`: The ID of the vertex the flow starts at.
+/// - `sink`: The ID of the vertex the flow ends at.
+/// - `demand`: The amount of flow to send from the source to the sink.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedSparseGraph, WeightedGraph};
+/// use istos::flow::{min_cost_flow, FlowEdge, MinCostFlowError};
+///
+/// let mut graph: UndirectedSparseGraph<(), FlowEdge<u32>> = UndirectedSparseGraph::new();
+/// let s = graph.add_vertex(());
+/// let a = graph.add_vertex(());
+/// let t = graph.add_vertex(());
+///
+/// // A cheap but narrow route, and an expensive but wide one
+/// graph.set_edge_weight(s, a, FlowEdge { capacity: 1, cost: 1 });
+/// graph.set_edge_weight(a, t, FlowEdge { capacity: 1, cost: 1 });
+/// graph.set_edge_weight(s, t, FlowEdge { capacity: 5, cost: 3 });
+///
+/// assert_eq!(min_cost_flow(&graph, s, t, 1).unwrap().cost, 2);
+/// assert_eq!(min_cost_flow(&graph, s, t, 3).unwrap().cost, 8);
+/// assert_eq!(min_cost_flow(&graph, s, t, 7), Err(MinCostFlowError::Infeasible { max_flow: 6 }));
+/// ```
+pub fn min_cost_flow<T, W, G>(graph: &G, source: usize, sink: usize, demand: W) -> Result<MinCostFlow<W>, MinCostFlowError<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, FlowEdge<W>>,
+{
+	min_cost_flow_with(graph, source, sink, demand, edge_weight(graph))
+}
+
+/// Computes a minimum cost flow, with the capacities and costs given by a closure.
+///
+/// See [`min_cost_flow`] for details.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find a flow in.
+/// - `source`: The ID of the vertex the flow starts at.
+/// - `sink`: The ID of the vertex the flow ends at.
+/// - `demand`: The amount of flow to send from the source to the sink.
+/// - `edge`: A function that returns the capacity and cost of the edge from one vertex ID to another.
+pub fn min_cost_flow_with<T, W, G, F>(
+	graph: &G,
+	source: usize,
+	sink: usize,
+	demand: W,
+	mut edge: F,
+) -> Result<MinCostFlow<W>, MinCostFlowError<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: Graph<T>,
+	F: FnMut(usize, usize) -> FlowEdge<W>,
+{
+	let index = Indexed::new(graph);
+	let (Some(&s), Some(&t)) = (index.index.get(&source), index.index.get(&sink)) else {
+		return Err(MinCostFlowError::InvalidVertices);
+	};
+	if s == t {
+		return Err(MinCostFlowError::InvalidVertices);
+	}
+
+	let mut network = Network::new(index.len());
+	let mut costs = Vec::new();
+	for (u, neighbors) in index.adj.iter().enumerate() {
+		for &v in neighbors {
+			let FlowEdge { capacity, cost } = edge(index.ids[u], index.ids[v]);
+			network.add_arc(u, v, capacity);
+			costs.push(cost);
+		}
+	}
+
+	successive_shortest_paths(&mut network, &costs, s, t, demand)?;
+
+	let cost = (0..costs.len()).fold(W::zero(), |total, i| total + network.flow(2 * i) * costs[i]);
+	Ok(MinCostFlow {
+		cost,
+		flows: network.net_flows(&index),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use super::*;
+	use crate::util::TestDigraph;
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+
+	type Solver = fn(&TestDigraph, usize, usize, &HashMap<(usize, usize), i32>) -> Option<MaxFlow<i32>>;
+
+	const SOLVERS: [Solver; 3] = [
+		|g, s, t, c| edmonds_karp_with(g, s, t, |u, v| c[&(u, v)]),
+		|g, s, t, c| dinic_with(g, s, t, |u, v| c[&(u, v)]),
+		|g, s, t, c| push_relabel_with(g, s, t, |u, v| c[&(u, v)]),
+	];
+
+	/// Builds the flow network from CLRS, with vertex 0 as the source and vertex 5 as the sink.
+	fn build_network() -> (TestDigraph, HashMap<(usize, usize), i32>) {
+		let edges = [
+			(0, 1, 16), (0, 2, 13), (1, 3, 12), (2, 1, 4), (2, 4, 14),
+			(3, 2, 9), (3, 5, 20), (4, 3, 7), (4, 5, 4),
+		];
+
+		let graph = TestDigraph::with_edges(6, &edges.map(|(u, v, _)| (u, v)));
+		let capacities = edges.iter().map(|&(u, v, c)| ((u, v), c)).collect();
+		(graph, capacities)
+	}
+
+	/// Checks that the flows respect the capacities and are conserved at every other vertex.
+	fn check_flow<W: Weight + Into<f64>>(flow: &MaxFlow<W>, source: usize, sink: usize, capacity: impl Fn(usize, usize) -> W) {
+		let mut balance: HashMap<usize, f64> = HashMap::new();
+		for &(u, v, f) in &flow.flows {
+			assert!(f > W::zero() && f <= capacity(u, v));
+			*balance.entry(u).or_default() -= f.into();
+			*balance.entry(v).or_default() += f.into();
+		}
+
+		for (&v, &b) in &balance {
+			if v == source {
+				assert_eq!(b, -flow.value.into());
+			} else if v == sink {
+				assert_eq!(b, flow.value.into());
+			} else {
+				assert_eq!(b, 0.0);
+			}
+		}
+	}
+
+	#[test]
+	fn test_directed() {
+		let (graph, capacities) = build_network();
+
+		for solver in SOLVERS {
+			let flow = solver(&graph, 0, 5, &capacities).unwrap();
+
+			assert_eq!(flow.value, 23);
+			assert_eq!(flow.source_side, vec![0, 1, 2, 4]);
+			assert_eq!(flow.sink_side, vec![3, 5]);
+			check_flow(&flow, 0, 5, |u, v| capacities[&(u, v)]);
+		}
+	}
+
+	#[test]
+	fn test_undirected() {
+		let mut graph: UndirectedSparseGraph<(), u32> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..6).map(|_| graph.add_vertex(())).collect();
+		for (a, b, c) in [(0, 1, 10), (0, 2, 10), (1, 2, 2), (1, 3, 4), (1, 4, 8), (2, 4, 9), (3, 5, 10), (4, 3, 6), (4, 5, 10)] {
+			graph.set_edge_weight(v[a], v[b], c);
+		}
+
+		for flow in [edmonds_karp(&graph, v[0], v[5]), dinic(&graph, v[0], v[5]), push_relabel(&graph, v[0], v[5])] {
+			let flow = flow.unwrap();
+			assert_eq!(flow.value, 20);
+
+			check_flow(&flow, v[0], v[5], |a, b| graph.get_edge_weight(a, b).unwrap());
+
+			// The cut separates the source from the sink and its capacity is the flow value
+			let cut: u32 = flow.source_side.iter()
+				.flat_map(|&a| flow.sink_side.iter().map(move |&b| (a, b)))
+				.filter_map(|(a, b)| graph.get_edge_weight(a, b))
+				.sum();
+			assert_eq!(cut, 20);
+			assert!(flow.source_side.contains(&v[0]));
+			assert!(flow.sink_side.contains(&v[5]));
+		}
+	}
+
+	#[test]
+	fn test_dense_graph() {
+		let mut graph: UndirectedGraph<(), f64> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+		graph.set_edge_weight(v[0], v[1], 1.5);
+		graph.set_edge_weight(v[0], v[2], 2.0);
+		graph.set_edge_weight(v[1], v[3], 2.5);
+		graph.set_edge_weight(v[2], v[3], 0.5);
+
+		assert_eq!(edmonds_karp(&graph, v[0], v[3]).unwrap().value, 2.0);
+		assert_eq!(dinic(&graph, v[0], v[3]).unwrap().value, 2.0);
+		assert_eq!(push_relabel(&graph, v[0], v[3]).unwrap().value, 2.0);
+	}
+
+	#[test]
+	fn test_disconnected() {
+		let graph = TestDigraph::with_edges(3, &[(0, 1)]);
+		let capacities = HashMap::from([((0, 1), 5)]);
+
+		for solver in SOLVERS {
+			let flow = solver(&graph, 0, 2, &capacities).unwrap();
+
+			assert_eq!(flow.value, 0);
+			assert!(flow.flows.is_empty());
+			assert_eq!(flow.source_side, vec![0, 1]);
+			assert_eq!(flow.sink_side, vec![2]);
+		}
+	}
+
+	#[test]
+	fn test_invalid_vertices() {
+		let (graph, capacities) = build_network();
+
+		for solver in SOLVERS {
+			assert!(solver(&graph, 0, 0, &capacities).is_none());
+			assert!(solver(&graph, 0, 99, &capacities).is_none());
+		}
+	}
+
+	/// Builds a small network with vertex 0 as the source and vertex 3 as the sink.
+	fn build_cost_network() -> (TestDigraph, HashMap<(usize, usize), FlowEdge<i32>>) {
+		let edges = [(0, 1, 2, 1), (0, 2, 1, 2), (1, 2, 1, 1), (1, 3, 1, 3), (2, 3, 2, 1)];
+
+		let graph = TestDigraph::with_edges(4, &edges.map(|(u, v, _, _)| (u, v)));
+		let edges = edges.iter().map(|&(u, v, capacity, cost)| ((u, v), FlowEdge { capacity, cost })).collect();
+		(graph, edges)
+	}
+
+	#[test]
+	fn test_min_cost_flow() {
+		let (graph, edges) = build_cost_network();
+
+		let flow = min_cost_flow_with(&graph, 0, 3, 2, |u, v| edges[&(u, v)]).unwrap();
+		assert_eq!(flow.cost, 6);
+
+		let flow = min_cost_flow_with(&graph, 0, 3, 3, |u, v| edges[&(u, v)]).unwrap();
+		assert_eq!(flow.cost, 10);
+		check_flow(&MaxFlow { value: 3, flows: flow.flows, source_side: vec![], sink_side: vec![] }, 0, 3, |u, v| edges[&(u, v)].capacity);
+
+		let flow = min_cost_flow_with(&graph, 0, 3, 0, |u, v| edges[&(u, v)]).unwrap();
+		assert_eq!(flow.cost, 0);
+		assert!(flow.flows.is_empty());
+	}
+
+	#[test]
+	fn test_min_cost_flow_errors() {
+		let (graph, edges) = build_cost_network();
+
+		assert_eq!(min_cost_flow_with(&graph, 0, 3, 4, |u, v| edges[&(u, v)]), Err(MinCostFlowError::Infeasible { max_flow: 3 }));
+		assert_eq!(min_cost_flow_with(&graph, 0, 0, 1, |u, v| edges[&(u, v)]), Err(MinCostFlowError::InvalidVertices));
+		assert_eq!(min_cost_flow_with(&graph, 0, 9, 1, |u, v| edges[&(u, v)]), Err(MinCostFlowError::InvalidVertices));
+
+		// Both directions of an undirected edge with a negative cost form a negative cycle
+		let mut graph: UndirectedSparseGraph<(), FlowEdge<i32>> = UndirectedSparseGraph::new();
+		let s = graph.add_vertex(());
+		let t = graph.add_vertex(());
+		graph.set_edge_weight(s, t, FlowEdge { capacity: 1, cost: -1 });
+		assert_eq!(min_cost_flow(&graph, s, t, 1), Err(MinCostFlowError::NegativeCycle));
+	}
+
+	#[test]
+	fn test_min_cost_flow_negative_costs() {
+		// The longer route is cheaper thanks to a rebate on one of its edges
+		let edges = HashMap::from([
+			((0, 1), FlowEdge { capacity: 1, cost: 4 }),
+			((1, 3), FlowEdge { capacity: 1, cost: -3 }),
+			((0, 2), FlowEdge { capacity: 1, cost: 2 }),
+			((2, 3), FlowEdge { capacity: 1, cost: 2 }),
+		]);
+		let graph = TestDigraph::with_edges(4, &[(0, 1), (1, 3), (0, 2), (2, 3)]);
+
+		let flow = min_cost_flow_with(&graph, 0, 3, 1, |u, v| edges[&(u, v)]).unwrap();
+		assert_eq!(flow.cost, 1);
+		assert_eq!(flow.flows, vec![(0, 1, 1), (1, 3, 1)]);
+
+		let flow = min_cost_flow_with(&graph, 0, 3, 2, |u, v| edges[&(u, v)]).unwrap();
+		assert_eq!(flow.cost, 5);
+	}
+
+	#[test]
+	fn test_min_cost_flow_undirected() {
+		let mut graph: UndirectedGraph<(), FlowEdge<f64>> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+		graph.set_edge_weight(v[0], v[1], FlowEdge { capacity: 2.0, cost: 1.0 });
+		graph.set_edge_weight(v[1], v[3], FlowEdge { capacity: 1.0, cost: 1.5 });
+		graph.set_edge_weight(v[1], v[2], FlowEdge { capacity: 2.0, cost: 0.5 });
+		graph.set_edge_weight(v[2], v[3], FlowEdge { capacity: 2.0, cost: 0.5 });
+
+		let flow = min_cost_flow(&graph, v[0], v[3], 2.0).unwrap();
+		assert_eq!(flow.cost, 4.0);
+		assert_eq!(flow.flows, vec![(v[0], v[1], 2.0), (v[1], v[2], 2.0), (v[2], v[3], 2.0)]);
+	}
+}
@@ -0,0 +1,588 @@
+//! Graph isomorphism and induced subgraph isomorphism.
+//!
+//! The searches follow VF2: they extend a partial mapping between the vertices of two graphs one
+//! pair at a time, and cut off branches that can't be completed by comparing the numbers of mapped,
+//! frontier and unexplored neighbors of the two vertices of a pair. As in VF2++, the vertices of
+//! the first graph are mapped in breadth-first order, preferring vertices with many neighbors that
+//! are mapped already, so that every new pair is constrained as early as possible.
+//!
+//! A self loop is part of the structure of a graph: a vertex with a self loop is only mapped to
+//! vertices with one.
+
+use std::collections::HashMap;
+
+use super::util::Indexed;
+use super::Graph;
+
+/// Orders the vertices of a graph for matching: component by component, starting from a vertex of
+/// maximum degree, level by level in breadth-first order. Within a level, each next vertex is the
+/// one with the most neighbors ordered already, then the highest degree.
+///
+/// Returns the order, and for each position an earlier neighbor of the vertex there, if any.
+fn matching_order(adj: &[Vec<usize>]) -> (Vec<usize>, Vec<Option<usize>>) {
+	let n = adj.len();
+	let mut order = Vec::with_capacity(n);
+	let mut seen = vec![false; n];
+	let mut connections = vec![0; n]; // The number of ordered neighbors of each vertex
+
+	while order.len() < n {
+		let root = (0..n).filter(|&u| !seen[u]).max_by_key(|&u| (adj[u].len(), std::cmp::Reverse(u))).unwrap();
+		seen[root] = true;
+		let mut level = vec![root];
+
+		while !level.is_empty() {
+			let mut next_level = Vec::new();
+			while !level.is_empty() {
+				let i = (0..level.len()).max_by_key(|&i| (connections[level[i]], adj[level[i]].len())).unwrap();
+				let u = level.swap_remove(i);
+				order.push(u);
+				for &v in &adj[u] {
+					connections[v] += 1;
+					if !seen[v] {
+						seen[v] = true;
+						next_level.push(v);
+					}
+				}
+			}
+			level = next_level;
+		}
+	}
+
+	let mut position = vec![0; n];
+	for (i, &u) in order.iter().enumerate() {
+		position[u] = i;
+	}
+	let parents = order.iter().enumerate()
+		.map(|(i, &u)| adj[u].iter().copied().filter(|&v| position[v] < i).min_by_key(|&v| position[v]))
+		.collect();
+
+	(order, parents)
+}
+
+/// A side of a VF2 search: one of the two graphs, and its part of the partial mapping.
+#[derive(Clone, Debug)]
+struct Side {
+	ids: Vec<usize>,
+	adj: Vec<Vec<usize>>, // Sorted, without self loops
+	loops: Vec<bool>, // Whether each vertex has a self loop
+	core: Vec<Option<usize>>, // The vertex of the other graph each vertex is mapped to
+	frontier: Vec<usize>, // The depth at which each vertex joined the mapped vertices or their neighbors, or zero
+}
+
+impl Side {
+	fn new(index: Indexed) -> Self {
+		let n = index.len();
+		let loops = (0..n).map(|u| index.adj[u].contains(&u)).collect();
+		let adj = index.adj.into_iter().enumerate()
+			.map(|(u, neighbors)| {
+				let mut neighbors: Vec<usize> = neighbors.into_iter().filter(|&v| v != u).collect();
+				neighbors.sort_unstable();
+				neighbors
+			})
+			.collect();
+
+		Self { ids: index.ids, adj, loops, core: vec![None; n], frontier: vec![0; n] }
+	}
+
+	/// Counts the neighbors of a vertex that are mapped, on the frontier, and neither.
+	fn neighbor_counts(&self, u: usize) -> (usize, usize, usize) {
+		let mut counts = (0, 0, 0);
+		for &v in &self.adj[u] {
+			if self.core[v].is_some() {
+				counts.0 += 1;
+			} else if self.frontier[v] > 0 {
+				counts.1 += 1;
+			} else {
+				counts.2 += 1;
+			}
+		}
+		counts
+	}
+
+	fn map(&mut self, u: usize, v: usize, depth: usize) {
+		self.core[u] = Some(v);
+		for w in std::iter::once(u).chain(self.adj[u].iter().copied()) {
+			if self.frontier[w] == 0 {
+				self.frontier[w] = depth;
+			}
+		}
+	}
+
+	fn unmap(&mut self, u: usize, depth: usize) {
+		self.core[u] = None;
+		for w in std::iter::once(u).chain(self.adj[u].iter().copied()) {
+			if self.frontier[w] == depth {
+				self.frontier[w] = 0;
+			}
+		}
+	}
+}
+
+/// A step of the search: the vertices of the second graph to try for the next vertex of the first.
+#[derive(Clone, Debug)]
+struct Frame {
+	candidates: Vec<usize>,
+	next: usize, // The position of the next candidate
+}
+
+/// The state of a VF2 search for mappings from the vertices of one graph into another.
+#[derive(Clone, Debug)]
+struct Vf2 {
+	pattern: Side,
+	target: Side,
+	order: Vec<usize>,
+	parents: Vec<Option<usize>>,
+	exact: bool, // Whether the mapping has to be onto, as for an isomorphism
+	stack: Vec<Frame>,
+	started: bool,
+}
+
+impl Vf2 {
+	fn new(pattern: Indexed, target: Indexed, exact: bool) -> Self {
+		let pattern = Side::new(pattern);
+		let target = Side::new(target);
+		let (order, parents) = matching_order(&pattern.adj);
+
+		Self { pattern, target, order, parents, exact, stack: Vec::new(), started: false }
+	}
+
+	/// The candidates for the vertex at a depth: the unmapped neighbors of the image of its parent,
+	/// or every unmapped vertex if it has none.
+	fn candidates(&self, depth: usize) -> Vec<usize> {
+		match self.parents[depth] {
+			Some(p) => {
+				let image = self.pattern.core[p].unwrap();
+				self.target.adj[image].iter().copied().filter(|&v| self.target.core[v].is_none()).collect()
+			}
+			None => (0..self.target.ids.len()).filter(|&v| self.target.core[v].is_none()).collect(),
+		}
+	}
+
+	/// Checks whether mapping `u` to `v` keeps the mapping extendable, as far as the neighborhoods
+	/// of the two vertices tell.
+	fn is_feasible(&self, u: usize, v: usize) -> bool {
+		let (pattern, target) = (&self.pattern, &self.target);
+		if pattern.loops[u] != target.loops[v] {
+			return false;
+		}
+		if (self.exact && pattern.adj[u].len() != target.adj[v].len()) || pattern.adj[u].len() > target.adj[v].len() {
+			return false;
+		}
+
+		// The mapped neighbors of `u` must map to neighbors of `v`, and those must be all of them
+		let mapped_neighbors_map = pattern.adj[u].iter()
+			.filter_map(|&w| pattern.core[w])
+			.all(|x| target.adj[v].binary_search(&x).is_ok());
+		if !mapped_neighbors_map {
+			return false;
+		}
+
+		let (mapped_1, frontier_1, rest_1) = pattern.neighbor_counts(u);
+		let (mapped_2, frontier_2, rest_2) = target.neighbor_counts(v);
+		if self.exact {
+			mapped_1 == mapped_2 && frontier_1 == frontier_2 && rest_1 == rest_2
+		} else {
+			mapped_1 == mapped_2 && frontier_1 <= frontier_2 && rest_1 <= rest_2
+		}
+	}
+
+	/// Finds the next complete mapping, if there is one. The search continues from the previous
+	/// mapping on the next call.
+	fn next_mapping(&mut self, compatible: &mut impl FnMut(usize, usize) -> bool) -> Option<HashMap<usize, usize>> {
+		let n = self.order.len();
+		if !self.started {
+			self.started = true;
+			if n == 0 {
+				return Some(HashMap::new());
+			}
+			if n > self.target.ids.len() {
+				return None;
+			}
+			self.stack.push(Frame { candidates: self.candidates(0), next: 0 });
+		}
+
+		loop {
+			let depth = self.stack.len().checked_sub(1)?;
+			let u = self.order[depth];
+
+			// Undo the pair tried last at this depth
+			if let Some(v) = self.pattern.core[u] {
+				self.pattern.unmap(u, depth + 1);
+				self.target.unmap(v, depth + 1);
+			}
+
+			let frame = &mut self.stack[depth];
+			let Some(&v) = frame.candidates.get(frame.next) else {
+				self.stack.pop();
+				continue;
+			};
+			frame.next += 1;
+
+			if self.target.core[v].is_some() || !self.is_feasible(u, v) || !compatible(u, v) {
+				continue;
+			}
+
+			self.pattern.map(u, v, depth + 1);
+			self.target.map(v, u, depth + 1);
+			if depth + 1 == n {
+				let mapping = (0..n)
+					.map(|u| (self.pattern.ids[u], self.target.ids[self.pattern.core[u].unwrap()]))
+					.collect();
+				return Some(mapping);
+			}
+			self.stack.push(Frame { candidates: self.candidates(depth + 1), next: 0 });
+		}
+	}
+}
+
+/// Checks the invariants that any two isomorphic graphs share: the same number of vertices, the
+/// same degrees and the same number of self loops.
+fn may_be_isomorphic(graph_1: &Indexed, graph_2: &Indexed) -> bool {
+	let degrees = |index: &Indexed| {
+		let mut degrees: Vec<(usize, bool)> = index.adj.iter().enumerate()
+			.map(|(u, neighbors)| (neighbors.len(), neighbors.contains(&u)))
+			.collect();
+		degrees.sort_unstable();
+		degrees
+	};
+
+	graph_1.len() == graph_2.len() && degrees(graph_1) == degrees(graph_2)
+}
+
+/// Checks whether two undirected graphs are isomorphic: whether there is a one-to-one mapping
+/// between their vertices under which two vertices are adjacent in one graph exactly when their
+/// images are adjacent in the other. The data of the vertices is not compared.
+///
+/// Uses the VF2 algorithm with a VF2++ matching order, after checking that the degrees of the
+/// graphs agree. The search takes exponential time in the worst case, but is fast on most graphs.
+///
+/// # Arguments
+///
+/// - `graph_1`: The first graph.
+/// - `graph_2`: The second graph.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph, UndirectedSparseGraph};
+/// use istos::isomorphism::is_isomorphic;
+///
+/// // A path of three vertices, built in two different orders
+/// let mut graph_1: UndirectedGraph<()> = UndirectedGraph::new();
+/// let a: Vec<usize> = (0..3).map(|_| graph_1.add_vertex(())).collect();
+/// graph_1.add_edge(a[0], a[1]);
+/// graph_1.add_edge(a[1], a[2]);
+///
+/// let mut graph_2: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+/// let b: Vec<usize> = (0..3).map(|_| graph_2.add_vertex(())).collect();
+/// graph_2.add_edge(b[0], b[2]);
+/// graph_2.add_edge(b[2], b[1]);
+///
+/// assert!(is_isomorphic(&graph_1, &graph_2));
+///
+/// graph_2.add_edge(b[0], b[1]);
+/// assert!(!is_isomorphic(&graph_1, &graph_2));
+/// ```
+pub fn is_isomorphic<T1, T2, G1, G2>(graph_1: &G1, graph_2: &G2) -> bool
+where
+	T1: Clone,
+	T2: Clone,
+	G1: Graph<T1>,
+	G2: Graph<T2>,
+{
+	is_isomorphic_matching(graph_1, graph_2, |_, _| true)
+}
+
+/// Checks whether two undirected graphs are isomorphic with a mapping that only pairs vertices
+/// whose data matches.
+///
+/// See [`is_isomorphic`] for the search. This is the check to use for labeled graphs, such as
+/// molecules whose vertices are atoms.
+///
+/// # Arguments
+///
+/// - `graph_1`: The first graph.
+/// - `graph_2`: The second graph.
+/// - `node_eq`: A function that takes the data of a vertex of each graph, and returns whether the
+///   two vertices may be mapped to each other.
+pub fn is_isomorphic_matching<T1, T2, G1, G2, F>(graph_1: &G1, graph_2: &G2, mut node_eq: F) -> bool
+where
+	T1: Clone,
+	T2: Clone,
+	G1: Graph<T1>,
+	G2: Graph<T2>,
+	F: FnMut(&T1, &T2) -> bool,
+{
+	let index_1 = Indexed::new(graph_1);
+	let index_2 = Indexed::new(graph_2);
+	if !may_be_isomorphic(&index_1, &index_2) {
+		return false;
+	}
+
+	let data_1: Vec<T1> = index_1.ids.iter().map(|&id| graph_1.get_vertex_data(id).expect("vertices have data")).collect();
+	let data_2: Vec<T2> = index_2.ids.iter().map(|&id| graph_2.get_vertex_data(id).expect("vertices have data")).collect();
+
+	let mut search = Vf2::new(index_1, index_2, true);
+	search.next_mapping(&mut |u, v| node_eq(&data_1[u], &data_2[v])).is_some()
+}
+
+/// A lazy iterator over the induced subgraph isomorphisms from one graph into another, returned by
+/// [`subgraph_isomorphisms`].
+///
+/// The iterator works on snapshots of the graphs taken when it was created.
+#[derive(Clone, Debug)]
+pub struct SubgraphIsomorphisms {
+	search: Vf2,
+}
+
+impl Iterator for SubgraphIsomorphisms {
+	type Item = HashMap<usize, usize>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.search.next_mapping(&mut |_, _| true)
+	}
+}
+
+/// Lists the ways a pattern graph occurs as an induced subgraph of a target graph, lazily.
+///
+/// Every item is a one-to-one mapping from the vertex IDs of the pattern to vertex IDs of the
+/// target, under which two pattern vertices are adjacent exactly when their images are. A pattern
+/// with symmetries occurs once for each of them. The mappings are found with the VF2 algorithm.
+///
+/// # Arguments
+///
+/// - `pattern`: The graph to look for.
+/// - `target`: The graph to look in.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedSparseGraph};
+/// use istos::isomorphism::subgraph_isomorphisms;
+///
+/// // A path of three vertices occurs in a square in 8 ways, but not in a triangle
+/// let mut path: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+/// let p: Vec<usize> = (0..3).map(|_| path.add_vertex(())).collect();
+/// path.add_edge(p[0], p[1]);
+/// path.add_edge(p[1], p[2]);
+///
+/// let mut square: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+/// let s: Vec<usize> = (0..4).map(|_| square.add_vertex(())).collect();
+/// for i in 0..4 {
+///     square.add_edge(s[i], s[(i + 1) % 4]);
+/// }
+///
+/// assert_eq!(subgraph_isomorphisms(&path, &square).count(), 8);
+///
+/// square.add_edge(s[0], s[2]);
+/// square.remove_vertex(s[3]);
+/// assert_eq!(subgraph_isomorphisms(&path, &square).count(), 0);
+/// ```
+pub fn subgraph_isomorphisms<T1, T2, G1, G2>(pattern: &G1, target: &G2) -> SubgraphIsomorphisms
+where
+	T1: Clone,
+	T2: Clone,
+	G1: Graph<T1>,
+	G2: Graph<T2>,
+{
+	SubgraphIsomorphisms {
+		search: Vf2::new(Indexed::new(pattern), Indexed::new(target), false),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+	use crate::util::{random_graph, Rng};
+
+	/// Copies a graph with its vertices added in a shuffled order.
+	fn shuffled<T: Clone>(graph: &UndirectedSparseGraph<T>, seed: u64) -> UndirectedGraph<T> {
+		let mut vertices = graph.get_vertices();
+		Rng::new(seed).shuffle(&mut vertices);
+
+		let mut copy = UndirectedGraph::new();
+		let new_ids: HashMap<usize, usize> = vertices.iter()
+			.map(|&u| (u, copy.add_vertex(graph.get_vertex_data(u).unwrap())))
+			.collect();
+		for &u in &vertices {
+			for v in graph.get_neighbors(u) {
+				if !copy.is_adjacent(new_ids[&u], new_ids[&v]) {
+					copy.add_edge(new_ids[&u], new_ids[&v]);
+				}
+			}
+		}
+		copy
+	}
+
+	/// Checks that a mapping is an induced subgraph isomorphism.
+	fn is_embedding<T: Clone, G1: Graph<T>, G2: Graph<T>>(pattern: &G1, target: &G2, mapping: &HashMap<usize, usize>) -> bool {
+		let vertices = pattern.get_vertices();
+		let mut images: Vec<usize> = mapping.values().copied().collect();
+		images.sort_unstable();
+		images.dedup();
+
+		mapping.len() == vertices.len() && images.len() == vertices.len() && vertices.iter().all(|&a| {
+			vertices.iter().all(|&b| pattern.is_adjacent(a, b) == target.is_adjacent(mapping[&a], mapping[&b]))
+		})
+	}
+
+	/// Checks whether two graphs are isomorphic by trying every mapping between their vertices.
+	fn brute_force_isomorphic(graph_1: &UndirectedSparseGraph<usize>, graph_2: &UndirectedSparseGraph<usize>) -> bool {
+		fn permute(images: &mut Vec<usize>, k: usize, check: &mut impl FnMut(&[usize]) -> bool) -> bool {
+			if k == images.len() {
+				return check(images);
+			}
+			(k..images.len()).any(|i| {
+				images.swap(k, i);
+				let found = permute(images, k + 1, check);
+				images.swap(k, i);
+				found
+			})
+		}
+
+		let v1 = graph_1.get_vertices();
+		let mut images = graph_2.get_vertices();
+		v1.len() == images.len() && permute(&mut images, 0, &mut |images| {
+			let mapping: HashMap<usize, usize> = v1.iter().copied().zip(images.iter().copied()).collect();
+			is_embedding(graph_1, graph_2, &mapping)
+		})
+	}
+
+	#[test]
+	fn test_is_isomorphic() {
+		for seed in 1..20 {
+			let graph = random_graph(30, 20, seed);
+			assert!(is_isomorphic(&graph, &shuffled(&graph, seed)));
+		}
+
+		// Small random graphs with the same number of edges are often, but not always, isomorphic
+		let mut isomorphic = 0;
+		for seed in 1..200 {
+			let graph_1 = random_graph(6, 50, seed);
+			let graph_2 = random_graph(6, 50, seed + 1000);
+			let expected = brute_force_isomorphic(&graph_1, &graph_2);
+			assert_eq!(is_isomorphic(&graph_1, &graph_2), expected);
+			isomorphic += expected as usize;
+		}
+		assert!(isomorphic > 0);
+	}
+
+	#[test]
+	fn test_is_isomorphic_regular() {
+		// The cube and the Wagner graph are both 3-regular on 8 vertices, but only the cube is
+		// bipartite
+		let mut cube: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let c: Vec<usize> = (0..8).map(|_| cube.add_vertex(())).collect();
+		for a in 0..8 {
+			for bit in [1, 2, 4] {
+				if a & bit == 0 {
+					cube.add_edge(c[a], c[a | bit]);
+				}
+			}
+		}
+
+		let mut wagner: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let d: Vec<usize> = (0..8).map(|_| wagner.add_vertex(())).collect();
+		for i in 0..8 {
+			wagner.add_edge(d[i], d[(i + 1) % 8]);
+		}
+		for i in 0..4 {
+			wagner.add_edge(d[i], d[i + 4]);
+		}
+
+		assert!(is_isomorphic(&cube, &shuffled(&cube, 3)));
+		assert!(!is_isomorphic(&cube, &wagner));
+
+		// The cube has 48 automorphisms
+		assert_eq!(subgraph_isomorphisms(&cube, &cube).count(), 48);
+	}
+
+	#[test]
+	fn test_is_isomorphic_special_cases() {
+		let empty: UndirectedGraph<()> = UndirectedGraph::new();
+		assert!(is_isomorphic(&empty, &UndirectedSparseGraph::<()>::new()));
+
+		// Self loops are part of the structure
+		let mut graph_1: UndirectedGraph<()> = UndirectedGraph::new();
+		let a = graph_1.add_vertex(());
+		let b = graph_1.add_vertex(());
+		graph_1.add_edge(a, b);
+		let mut graph_2 = graph_1.clone();
+		graph_1.add_edge(a, a);
+		graph_2.add_edge(b, b);
+		assert!(is_isomorphic(&graph_1, &graph_2));
+		graph_2.remove_edge(b, b);
+		assert!(!is_isomorphic(&graph_1, &graph_2));
+	}
+
+	#[test]
+	fn test_is_isomorphic_matching() {
+		// Ethanol and dimethyl ether have the same shape, but not the same atoms in the same places
+		let molecule = |atoms: [&'static str; 3]| {
+			let mut graph: UndirectedSparseGraph<&str> = UndirectedSparseGraph::new();
+			let v: Vec<usize> = atoms.iter().map(|&atom| graph.add_vertex(atom)).collect();
+			graph.add_edge(v[0], v[1]);
+			graph.add_edge(v[1], v[2]);
+			graph
+		};
+		let ethanol = molecule(["C", "C", "O"]);
+		let ether = molecule(["C", "O", "C"]);
+		let reversed = molecule(["O", "C", "C"]);
+
+		assert!(is_isomorphic(&ethanol, &ether));
+		assert!(!is_isomorphic_matching(&ethanol, &ether, |a, b| a == b));
+		assert!(is_isomorphic_matching(&ethanol, &reversed, |a, b| a == b));
+	}
+
+	#[test]
+	fn test_subgraph_isomorphisms() {
+		for seed in 1..10 {
+			let target = random_graph(9, 50, seed);
+			let pattern = random_graph(4, 50, seed + 100);
+
+			// Count the induced embeddings by trying every injective mapping
+			let p = pattern.get_vertices();
+			let t = target.get_vertices();
+			let mut expected = 0;
+			for a in 0..9 {
+				for b in 0..9 {
+					for c in 0..9 {
+						for d in 0..9 {
+							let images = [t[a], t[b], t[c], t[d]];
+							let distinct = images.iter().enumerate().all(|(i, x)| !images[..i].contains(x));
+							let mapping: HashMap<usize, usize> = p.iter().copied().zip(images).collect();
+							if distinct && is_embedding(&pattern, &target, &mapping) {
+								expected += 1;
+							}
+						}
+					}
+				}
+			}
+
+			let mappings: Vec<HashMap<usize, usize>> = subgraph_isomorphisms(&pattern, &target).collect();
+			assert!(mappings.iter().all(|m| is_embedding(&pattern, &target, m)));
+			assert_eq!(mappings.len(), expected);
+		}
+	}
+
+	#[test]
+	fn test_subgraph_isomorphisms_special_cases() {
+		let pattern: UndirectedGraph<()> = UndirectedGraph::new();
+		let mut target: UndirectedGraph<()> = UndirectedGraph::new();
+		assert_eq!(subgraph_isomorphisms(&pattern, &target).collect::<Vec<_>>(), vec![HashMap::new()]);
+
+		// A larger pattern never occurs
+		let mut pattern = pattern;
+		pattern.add_vertex(());
+		pattern.add_vertex(());
+		target.add_vertex(());
+		assert_eq!(subgraph_isomorphisms(&pattern, &target).count(), 0);
+
+		// Two isolated vertices occur in a pair of isolated vertices, but not in an edge
+		let v = target.add_vertex(());
+		assert_eq!(subgraph_isomorphisms(&pattern, &target).count(), 2);
+		target.add_edge(target.get_vertices()[0], v);
+		assert_eq!(subgraph_isomorphisms(&pattern, &target).count(), 0);
+	}
+}
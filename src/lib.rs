@@ -1,13 +1,38 @@
 //! Graphs
 
-#![allow(incomplete_features)]
-#![feature(adt_const_params)]
-
+pub mod canonical;
+pub mod centrality;
+pub mod cliques;
+pub mod clustering;
+pub mod coloring;
+pub mod community;
+pub mod connectivity;
+pub mod cores;
+pub mod covering;
+pub mod distance;
+pub mod euler;
+pub mod flow;
+pub mod isomorphism;
+pub mod matching;
+pub mod min_cut;
+pub mod pagerank;
+pub mod planarity;
+pub mod shortest_paths;
+pub mod spanning_tree;
+pub mod strongly_connected;
+pub mod toposort;
+pub mod tsp;
 pub mod undirected_graph;
 pub mod undirected_sparse_graph;
+pub mod union_find;
+pub mod weight;
+
+mod util;
 
 pub use undirected_graph::UndirectedGraph;
 pub use undirected_sparse_graph::UndirectedSparseGraph;
+pub use union_find::UnionFind;
+pub use weight::Weight;
 
 /// A trait representing a generic graph.
 ///
@@ -46,7 +71,7 @@ pub trait Graph<T: Clone>: Clone {
     /// Removes an edge between two vertices in the graph.
     ///
     /// Does nothing if the edge does not exist.
-
+    ///
     /// # Arguments
     ///
     /// - `vertex_id_1`: The ID of the first vertex to disconnect.
@@ -89,6 +114,15 @@ pub trait Graph<T: Clone>: Clone {
     ///
     /// - `vertex_id`: The ID of the vertex to get the neighbors of.
     fn get_neighbors(&self, vertex_id: usize) -> Vec<usize>;
+
+    /// Gets the IDs of all vertices in the graph.
+    ///
+    /// Returns a vector containing the IDs of all vertices, in the order they were added.
+    ///
+    /// This method was added in version 0.1.0 and has no default, since none of the other methods
+    /// can find the vertices of a graph, so types outside this crate that implement [`Graph`] have
+    /// to provide it. The algorithms in this crate need it to visit every vertex.
+    fn get_vertices(&self) -> Vec<usize>;
 }
 
 /// A trait representing a weighted graph.
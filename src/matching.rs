@@ -0,0 +1,1610 @@
+//! Matchings in graphs.
+//!
+//! A matching is a set of edges without common vertices. The bipartite algorithms in this module
+//! take the vertices of one side as an argument; every other vertex is on the other side. The
+//! blossom algorithms work on any undirected graph.
+
+use std::collections::{HashSet, VecDeque};
+
+use super::util::Indexed;
+use super::{Graph, Weight, WeightedGraph};
+
+/// A maximum matching of a bipartite graph, with a minimum vertex cover proving that it is maximum.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BipartiteMatching {
+	/// The matched edges as `(left, right)` pairs of vertex IDs, in the order the left vertices were
+	/// added to the graph.
+	pub edges: Vec<(usize, usize)>,
+	/// The IDs of the vertices of a minimum vertex cover, in the order they were added to the graph.
+	/// Every edge has an end in the cover, and by Kőnig's theorem the cover is exactly as large as the
+	/// matching.
+	pub vertex_cover: Vec<usize>,
+}
+
+/// A matching with its total weight.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightedMatching<W> {
+	/// The matched edges as pairs of vertex IDs.
+	pub edges: Vec<(usize, usize)>,
+	/// The sum of the weights of the matched edges.
+	pub total_weight: W,
+}
+
+/// An optimal assignment of rows to columns of a cost matrix.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assignment<W> {
+	/// The column assigned to each row, or `None` if the row is left over because there are more rows
+	/// than columns.
+	pub columns: Vec<Option<usize>>,
+	/// The sum of the costs of the assigned cells.
+	pub total_cost: W,
+}
+
+/// An indexed bipartite graph, with the vertices of each side numbered from zero.
+struct Bipartite {
+	left: Vec<usize>, // The index in the graph of each left vertex
+	right: Vec<usize>, // The index in the graph of each right vertex
+	adj: Vec<Vec<(usize, usize)>>, // The right neighbors of each left vertex, with the position in `Indexed::adj`
+}
+
+impl Bipartite {
+	/// Splits an indexed graph into the given left vertices and the rest. Returns `None` if a left
+	/// vertex does not exist or an edge joins two vertices on the same side.
+	fn new(index: &Indexed, left_ids: &[usize]) -> Option<Self> {
+		let n = index.len();
+		let mut is_left = vec![false; n];
+		for id in left_ids {
+			is_left[*index.index.get(id)?] = true;
+		}
+
+		// The position of every vertex on its own side
+		let mut position = vec![0; n];
+		let (mut left, mut right) = (Vec::new(), Vec::new());
+		for u in 0..n {
+			let side = if is_left[u] { &mut left } else { &mut right };
+			position[u] = side.len();
+			side.push(u);
+		}
+
+		let mut adj = vec![Vec::new(); left.len()];
+		for (u, neighbors) in index.adj.iter().enumerate() {
+			for (k, &v) in neighbors.iter().enumerate() {
+				if is_left[u] == is_left[v] {
+					return None;
+				}
+				if is_left[u] {
+					adj[position[u]].push((position[v], k));
+				}
+			}
+		}
+
+		Some(Self { left, right, adj })
+	}
+}
+
+/// Finds a maximum matching of a bipartite graph using the Hopcroft–Karp algorithm.
+///
+/// Every phase finds a maximal set of shortest augmenting paths at once, so only `O(√V)` phases are
+/// needed and the algorithm runs in `O(E√V)` time. The result includes a minimum vertex cover,
+/// derived from the matching with Kőnig's theorem, which certifies that no larger matching exists.
+///
+/// Returns `None` if any of the left vertices does not exist, or if an edge joins two vertices on the
+/// same side.
+///
+/// # Arguments
+///
+/// - `graph`: The bipartite graph to find a matching in.
+/// - `left`: The IDs of the vertices on one side of the graph. All other vertices are on the other side.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedSparseGraph};
+/// use istos::matching::hopcroft_karp;
+///
+/// let mut graph: UndirectedSparseGraph<&str> = UndirectedSparseGraph::new();
+/// let alice = graph.add_vertex("alice");
+/// let bob = graph.add_vertex("bob");
+/// let cooking = graph.add_vertex("cooking");
+/// let cleaning = graph.add_vertex("cleaning");
+///
+/// graph.add_edge(alice, cooking);
+/// graph.add_edge(alice, cleaning);
+/// graph.add_edge(bob, cooking);
+///
+/// let matching = hopcroft_karp(&graph, &[alice, bob]).unwrap();
+///
+/// assert_eq!(matching.edges, vec![(alice, cleaning), (bob, cooking)]);
+/// assert_eq!(matching.vertex_cover.len(), 2);
+/// ```
+pub fn hopcroft_karp<T: Clone, G: Graph<T>>(graph: &G, left: &[usize]) -> Option<BipartiteMatching> {
+	let index = Indexed::new(graph);
+	let bipartite = Bipartite::new(&index, left)?;
+	let (nl, nr) = (bipartite.left.len(), bipartite.right.len());
+
+	let mut match_left: Vec<Option<usize>> = vec![None; nl];
+	let mut match_right: Vec<Option<usize>> = vec![None; nr];
+	let mut layer: Vec<usize> = vec![usize::MAX; nl];
+
+	loop {
+		// Layer the left vertices by the length of the shortest alternating path from a free one
+		let mut queue: VecDeque<usize> = VecDeque::new();
+		for u in 0..nl {
+			layer[u] = if match_left[u].is_none() { 0 } else { usize::MAX };
+			if match_left[u].is_none() {
+				queue.push_back(u);
+			}
+		}
+
+		let mut found = false;
+		while let Some(u) = queue.pop_front() {
+			for &(v, _) in &bipartite.adj[u] {
+				match match_right[v] {
+					None => found = true,
+					Some(w) if layer[w] == usize::MAX => {
+						layer[w] = layer[u] + 1;
+						queue.push_back(w);
+					}
+					Some(_) => {}
+				}
+			}
+		}
+
+		if !found {
+			break;
+		}
+
+		// Augment along vertex-disjoint shortest paths, found with a depth-first search through
+		// the layers. Vertices that lead nowhere are taken out of the layering.
+		let mut next = vec![0; nl];
+		for root in 0..nl {
+			if match_left[root].is_some() {
+				continue;
+			}
+
+			let mut path: Vec<usize> = vec![root]; // The left vertices on the path
+			let mut via: Vec<usize> = Vec::new(); // The right vertex after each left vertex on the path
+			while let Some(&u) = path.last() {
+				let Some(&(v, _)) = bipartite.adj[u].get(next[u]) else {
+					layer[u] = usize::MAX;
+					path.pop();
+					via.pop();
+					continue;
+				};
+				next[u] += 1;
+
+				match match_right[v] {
+					None => {
+						via.push(v);
+						for (&u, &v) in path.iter().zip(&via) {
+							match_left[u] = Some(v);
+							match_right[v] = Some(u);
+						}
+						break;
+					}
+					Some(w) if layer[w] == layer[u] + 1 => {
+						path.push(w);
+						via.push(v);
+					}
+					Some(_) => {}
+				}
+			}
+		}
+	}
+
+	// Kőnig's theorem: with Z the vertices reachable from the free left vertices by alternating
+	// paths, the left vertices outside Z and the right vertices in Z form a minimum vertex cover
+	let mut reached_left = vec![false; nl];
+	let mut reached_right = vec![false; nr];
+	let mut queue: VecDeque<usize> = (0..nl).filter(|&u| match_left[u].is_none()).collect();
+	for &u in &queue {
+		reached_left[u] = true;
+	}
+	while let Some(u) = queue.pop_front() {
+		for &(v, _) in &bipartite.adj[u] {
+			if !reached_right[v] {
+				reached_right[v] = true;
+				let w = match_right[v].expect("a maximum matching leaves no augmenting path");
+				if !reached_left[w] {
+					reached_left[w] = true;
+					queue.push_back(w);
+				}
+			}
+		}
+	}
+
+	let mut in_cover = vec![false; index.len()];
+	for u in 0..nl {
+		in_cover[bipartite.left[u]] = !reached_left[u];
+	}
+	for v in 0..nr {
+		in_cover[bipartite.right[v]] = reached_right[v];
+	}
+
+	Some(BipartiteMatching {
+		edges: (0..nl)
+			.filter_map(|u| Some((index.ids[bipartite.left[u]], index.ids[bipartite.right[match_left[u]?]])))
+			.collect(),
+		vertex_cover: (0..index.len()).filter(|&u| in_cover[u]).map(|u| index.ids[u]).collect(),
+	})
+}
+
+/// Finds a minimum cost matching of maximum size between rows and columns, where `None` marks a
+/// row and column that can't be matched. Returns the column matched to each row.
+///
+/// This is the successive shortest path algorithm on the bipartite graph: every round augments the
+/// matching along a cheapest alternating path from any unmatched row, found with Dijkstra's
+/// algorithm on costs reduced by potentials that keep them non-negative. Unmatched rows keep their
+/// initial potential of zero, so they can all start the search at distance zero, and unmatched
+/// columns all gain the same amount every round, so the closest one is also the cheapest to end the
+/// path at. Each round takes `O(rows * columns)` time.
+fn assign<W: Weight>(costs: &[Vec<Option<W>>], columns: usize) -> Vec<Option<usize>> {
+	let rows = costs.len();
+
+	// Start every column at the cost of the cheapest cell, so no reduced cost is negative and the
+	// columns are all alike until they are matched
+	let cheapest = costs.iter().flatten().flatten().copied().reduce(|a, b| if b < a { b } else { a });
+	let mut row_potential = vec![W::zero(); rows];
+	let mut column_potential = vec![cheapest.unwrap_or(W::zero()); columns];
+
+	let mut match_row: Vec<Option<usize>> = vec![None; rows];
+	let mut match_column: Vec<Option<usize>> = vec![None; columns];
+
+	loop {
+		let mut row_dist: Vec<Option<W>> = vec![None; rows];
+		let mut column_dist: Vec<Option<W>> = vec![None; columns];
+		let mut column_parent: Vec<usize> = vec![0; columns]; // The row each column is reached from
+		let mut settled = vec![false; columns];
+
+		let relax = |i: usize, row_dist: &[Option<W>], column_dist: &mut [Option<W>], column_parent: &mut [usize], settled: &[bool]| {
+			let d = row_dist[i].unwrap();
+			for j in 0..columns {
+				let Some(cost) = costs[i][j] else { continue };
+				if settled[j] {
+					continue;
+				}
+
+				let candidate = d + (cost + row_potential[i] - column_potential[j]);
+				if column_dist[j].is_none_or(|dj| candidate < dj) {
+					column_dist[j] = Some(candidate);
+					column_parent[j] = i;
+				}
+			}
+		};
+
+		for i in 0..rows {
+			if match_row[i].is_none() {
+				row_dist[i] = Some(W::zero());
+				relax(i, &row_dist, &mut column_dist, &mut column_parent, &settled);
+			}
+		}
+
+		// Settle the closest column until reaching one that is still free
+		let free = loop {
+			let mut closest: Option<usize> = None;
+			for j in 0..columns {
+				if !settled[j] && column_dist[j].is_some() && closest.is_none_or(|c| column_dist[j] < column_dist[c]) {
+					closest = Some(j);
+				}
+			}
+
+			let Some(j) = closest else { break None };
+			settled[j] = true;
+			match match_column[j] {
+				None => break Some(j),
+				Some(i) => {
+					// The matched edge is tight, so the row is at the same distance
+					row_dist[i] = column_dist[j];
+					relax(i, &row_dist, &mut column_dist, &mut column_parent, &settled);
+				}
+			}
+		};
+
+		// Without an augmenting path, the matching has maximum size
+		let Some(mut j) = free else { break };
+
+		// Update the potentials, capping the distances at the distance of the free column so that
+		// the reduced costs stay non-negative for vertices the search didn't settle
+		let limit = column_dist[j].unwrap();
+		for i in 0..rows {
+			row_potential[i] = row_potential[i] + row_dist[i].unwrap_or(limit);
+		}
+		for c in 0..columns {
+			let d = if settled[c] { column_dist[c].unwrap() } else { limit };
+			column_potential[c] = column_potential[c] + d;
+		}
+
+		// Flip the matching along the path
+		loop {
+			let i = column_parent[j];
+			let previous = match_row[i];
+			match_row[i] = Some(j);
+			match_column[j] = Some(i);
+			match previous {
+				Some(p) => j = p,
+				None => break,
+			}
+		}
+	}
+
+	match_row
+}
+
+/// Solves the assignment problem for a cost matrix using the Hungarian method.
+///
+/// Assigns every row to a different column so that the total cost of the assigned cells is as low
+/// as possible. The matrix may be rectangular: if there are more rows than columns, some rows are
+/// left unassigned, and otherwise some columns are unused. Runs in `O(n^2 m)` time for `n` rows and
+/// `m` columns with `n <= m`. Costs may be negative.
+///
+/// Returns `None` if the rows of the matrix don't all have the same length.
+///
+/// # Arguments
+///
+/// - `costs`: The cost matrix, with `costs[i][j]` the cost of assigning row `i` to column `j`.
+///
+/// # Example
+///
+/// ```
+/// use istos::matching::hungarian;
+///
+/// let costs = vec![
+///     vec![4, 1, 3],
+///     vec![2, 0, 5],
+///     vec![3, 2, 2],
+/// ];
+///
+/// let assignment = hungarian(&costs).unwrap();
+///
+/// assert_eq!(assignment.columns, vec![Some(1), Some(0), Some(2)]);
+/// assert_eq!(assignment.total_cost, 5);
+/// ```
+pub fn hungarian<W: Weight>(costs: &[Vec<W>]) -> Option<Assignment<W>> {
+	let columns = costs.first().map_or(0, |row| row.len());
+	if costs.iter().any(|row| row.len() != columns) {
+		return None;
+	}
+
+	let options: Vec<Vec<Option<W>>> = costs.iter().map(|row| row.iter().map(|&c| Some(c)).collect()).collect();
+	let assigned = assign(&options, columns);
+
+	let total_cost = assigned.iter().enumerate()
+		.filter_map(|(i, j)| Some(costs[i][(*j)?]))
+		.fold(W::zero(), |total, c| total + c);
+
+	Some(Assignment { columns: assigned, total_cost })
+}
+
+/// Finds a minimum weight matching among the largest matchings of a bipartite graph.
+///
+/// The matching has as many edges as a maximum matching, and among all such matchings its total
+/// weight is the lowest. This is the Hungarian method restricted to the edges of the graph. Weights
+/// may be negative.
+///
+/// Returns `None` if any of the left vertices does not exist, or if an edge joins two vertices on the
+/// same side.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted bipartite graph to find a matching in.
+/// - `left`: The IDs of the vertices on one side of the graph. All other vertices are on the other side.
+pub fn min_weight_bipartite_matching<T, W, G>(graph: &G, left: &[usize]) -> Option<WeightedMatching<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let bipartite = Bipartite::new(&index, left)?;
+
+	let mut costs: Vec<Vec<Option<W>>> = vec![vec![None; bipartite.right.len()]; bipartite.left.len()];
+	for (u, neighbors) in bipartite.adj.iter().enumerate() {
+		for &(v, k) in neighbors {
+			costs[u][v] = Some(weights[bipartite.left[u]][k]);
+		}
+	}
+
+	let mut edges = Vec::new();
+	let mut total_weight = W::zero();
+	for (u, v) in assign(&costs, bipartite.right.len()).into_iter().enumerate() {
+		if let Some(v) = v {
+			edges.push((index.ids[bipartite.left[u]], index.ids[bipartite.right[v]]));
+			total_weight = total_weight + costs[u][v].unwrap();
+		}
+	}
+
+	Some(WeightedMatching { edges, total_weight })
+}
+
+/// Checks whether a set of vertex pairs is a matching of a graph.
+///
+/// Returns `true` if every pair is an edge of the graph between two different vertices, and no
+/// vertex is in more than one pair.
+///
+/// # Arguments
+///
+/// - `graph`: The graph the matching belongs to.
+/// - `edges`: The matched pairs of vertex IDs.
+pub fn is_matching<T: Clone, G: Graph<T>>(graph: &G, edges: &[(usize, usize)]) -> bool {
+	let mut matched = HashSet::new();
+	edges.iter().all(|&(u, v)| u != v && graph.is_adjacent(u, v) && matched.insert(u) && matched.insert(v))
+}
+
+/// Searches for an augmenting path from a free vertex with Edmonds' algorithm, and flips the
+/// matching along it. Returns whether the matching grew.
+///
+/// The search grows an alternating tree from the root. When an edge closes an odd cycle (a
+/// blossom), the cycle is contracted into its base, which is done by pointing the `base` of all its
+/// vertices there and making them all even.
+fn augment_from(adj: &[Vec<usize>], mate: &mut [Option<usize>], root: usize) -> bool {
+	let n = adj.len();
+	let mut base: Vec<usize> = (0..n).collect(); // The base of the blossom each vertex is in
+	let mut parent: Vec<Option<usize>> = vec![None; n]; // The previous vertex on the path to each odd vertex
+	let mut even = vec![false; n];
+	let mut queue = VecDeque::from([root]);
+	even[root] = true;
+
+	while let Some(v) = queue.pop_front() {
+		for &to in &adj[v] {
+			if base[v] == base[to] || mate[v] == Some(to) {
+				continue;
+			}
+
+			if to == root || mate[to].is_some_and(|m| parent[m].is_some()) {
+				// Both ends are even, so the edge closes a blossom. Find its base: the first
+				// blossom base shared by the paths from both ends to the root.
+				let mut on_path = vec![false; n];
+				let mut x = v;
+				loop {
+					x = base[x];
+					on_path[x] = true;
+					match mate[x] {
+						Some(m) => x = parent[m].unwrap(),
+						None => break,
+					}
+				}
+				let mut lca = to;
+				loop {
+					lca = base[lca];
+					if on_path[lca] {
+						break;
+					}
+					lca = parent[mate[lca].unwrap()].unwrap();
+				}
+
+				// Walk both sides of the cycle down to the base, pointing the odd vertices back
+				// across the closing edge, so that paths through the blossom can go either way
+				let mut in_blossom = vec![false; n];
+				for (start, across) in [(v, to), (to, v)] {
+					let (mut x, mut child) = (start, across);
+					while base[x] != lca {
+						let m = mate[x].unwrap();
+						in_blossom[base[x]] = true;
+						in_blossom[base[m]] = true;
+						parent[x] = Some(child);
+						child = m;
+						x = parent[m].unwrap();
+					}
+				}
+
+				for u in 0..n {
+					if in_blossom[base[u]] {
+						base[u] = lca;
+						if !even[u] {
+							even[u] = true;
+							queue.push_back(u);
+						}
+					}
+				}
+			} else if parent[to].is_none() {
+				parent[to] = Some(v);
+				match mate[to] {
+					Some(m) => {
+						even[m] = true;
+						queue.push_back(m);
+					}
+					None => {
+						// Found a free vertex: flip the matching along the path back to the root
+						let mut x = Some(to);
+						while let Some(u) = x {
+							let p = parent[u].unwrap();
+							x = mate[p];
+							mate[u] = Some(p);
+							mate[p] = Some(u);
+						}
+						return true;
+					}
+				}
+			}
+		}
+	}
+
+	false
+}
+
+/// Finds a maximum matching of a graph using Edmonds' blossom algorithm.
+///
+/// Unlike [`hopcroft_karp`], this works on graphs that are not bipartite: odd cycles are
+/// contracted into single vertices while searching for augmenting paths. Runs in `O(V^3)` time.
+///
+/// Returns the matched pairs of vertex IDs, each with the vertex that was added first on the left,
+/// in the order of those vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to find a matching in.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedSparseGraph};
+/// use istos::matching::{is_matching, maximum_matching};
+///
+/// // A triangle with a tail on two of its corners
+/// let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+/// let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+/// for (a, b) in [(0, 1), (1, 2), (2, 0), (1, 3), (2, 4)] {
+///     graph.add_edge(v[a], v[b]);
+/// }
+///
+/// let matching = maximum_matching(&graph);
+///
+/// assert_eq!(matching.len(), 2);
+/// assert!(is_matching(&graph, &matching));
+/// ```
+pub fn maximum_matching<T: Clone, G: Graph<T>>(graph: &G) -> Vec<(usize, usize)> {
+	let index = Indexed::new(graph);
+	let n = index.len();
+
+	let mut mate: Vec<Option<usize>> = vec![None; n];
+	for root in 0..n {
+		if mate[root].is_none() {
+			augment_from(&index.adj, &mut mate, root);
+		}
+	}
+
+	(0..n)
+		.filter_map(|u| mate[u].filter(|&v| u < v).map(|v| (index.ids[u], index.ids[v])))
+		.collect()
+}
+
+/// The labels of vertices and blossoms in the weighted blossom algorithm.
+const FREE: u8 = 0;
+const OUTER: u8 = 1; // At an even distance from a free vertex
+const INNER: u8 = 2; // At an odd distance from a free vertex
+const VISITED: u8 = 4; // Set on outer blossoms while looking for the base of a new blossom
+
+/// The state of the weighted blossom algorithm.
+///
+/// The edges are numbered, and the two endpoints of edge `k` are numbered `2k` and `2k + 1`, so the
+/// other endpoint of endpoint `p` is `p ^ 1`. Vertices are numbered `0..n`, and the blossoms that
+/// are formed along the way take the numbers `n..2n`.
+struct WeightedBlossom<W> {
+	n: usize,
+	edges: Vec<(usize, usize, W)>,
+	neighbor_ends: Vec<Vec<usize>>, // The far endpoints of the edges of each vertex
+	mate: Vec<Option<usize>>, // The far endpoint of the matched edge of each vertex
+	label: Vec<u8>, // The label of each vertex and top-level blossom
+	label_end: Vec<Option<usize>>, // The endpoint through which each vertex or blossom got its label
+	in_blossom: Vec<usize>, // The top-level blossom each vertex is in
+	blossom_parent: Vec<Option<usize>>, // The blossom directly containing each vertex or blossom
+	blossom_children: Vec<Vec<usize>>, // The sub-blossoms of each blossom, in order around the cycle
+	blossom_ends: Vec<Vec<usize>>, // The endpoints of the edges between consecutive sub-blossoms
+	blossom_base: Vec<Option<usize>>, // The base vertex of each vertex or blossom in use
+	best_edge: Vec<Option<usize>>, // The least-slack edge to a different outer blossom
+	blossom_best_edges: Vec<Option<Vec<usize>>>, // The least-slack edges to neighboring outer blossoms
+	unused_blossoms: Vec<usize>,
+	dual: Vec<W>, // The dual variable of each vertex and blossom
+	allow_edge: Vec<bool>, // Whether each edge is known to have zero slack
+	queue: Vec<usize>, // The outer vertices still to scan
+}
+
+impl<W: Weight> WeightedBlossom<W> {
+	/// Sets up the algorithm for the edges between `n` vertices.
+	fn new(n: usize, edges: Vec<(usize, usize, W)>) -> Self {
+		let mut neighbor_ends = vec![Vec::new(); n];
+		for (k, &(i, j, _)) in edges.iter().enumerate() {
+			neighbor_ends[i].push(2 * k + 1);
+			neighbor_ends[j].push(2 * k);
+		}
+
+		// Every vertex starts with a dual of the maximum weight, so every edge has a slack. The dual
+		// of a vertex never exceeds twice the maximum weight, so slacks need room for four times it
+		let max_weight = edges.iter().map(|e| e.2).fold(W::zero(), |max, w| if w > max { w } else { max });
+		let headroom = max_weight.checked_add(max_weight).and_then(|double| double.checked_add(double));
+		assert!(headroom.is_some(), "four times the largest weight must fit in the weight type");
+		let mut dual = vec![max_weight; n];
+		dual.resize(2 * n, W::zero());
+
+		Self {
+			n,
+			neighbor_ends,
+			mate: vec![None; n],
+			label: vec![FREE; 2 * n],
+			label_end: vec![None; 2 * n],
+			in_blossom: (0..n).collect(),
+			blossom_parent: vec![None; 2 * n],
+			blossom_children: vec![Vec::new(); 2 * n],
+			blossom_ends: vec![Vec::new(); 2 * n],
+			blossom_base: (0..n).map(Some).chain((0..n).map(|_| None)).collect(),
+			best_edge: vec![None; 2 * n],
+			blossom_best_edges: vec![None; 2 * n],
+			unused_blossoms: (n..2 * n).rev().collect(),
+			dual,
+			allow_edge: vec![false; edges.len()],
+			queue: Vec::new(),
+			edges,
+		}
+	}
+
+	/// Gets the vertex at an endpoint.
+	#[inline]
+	fn endpoint(&self, p: usize) -> usize {
+		let (i, j, _) = self.edges[p / 2];
+		if p & 1 == 0 { i } else { j }
+	}
+
+	/// Gets the slack of an edge, which is zero for edges that may be in the matching.
+	#[inline]
+	fn slack(&self, k: usize) -> W {
+		let (i, j, w) = self.edges[k];
+		(self.dual[i] + self.dual[j]) - (w + w)
+	}
+
+	/// Gets the vertices inside a vertex or blossom.
+	fn leaves(&self, b: usize) -> Vec<usize> {
+		let mut leaves = Vec::new();
+		let mut stack = vec![b];
+		while let Some(x) = stack.pop() {
+			if x < self.n {
+				leaves.push(x);
+			} else {
+				stack.extend(self.blossom_children[x].iter().rev());
+			}
+		}
+		leaves
+	}
+
+	/// Labels the top-level blossom containing vertex `w`, reached through endpoint `p`. An inner
+	/// blossom is matched to an outer one, which is labeled as well.
+	fn assign_label(&mut self, mut w: usize, mut label: u8, mut p: Option<usize>) {
+		loop {
+			let b = self.in_blossom[w];
+			self.label[w] = label;
+			self.label[b] = label;
+			self.label_end[w] = p;
+			self.label_end[b] = p;
+			self.best_edge[w] = None;
+			self.best_edge[b] = None;
+
+			if label == OUTER {
+				let leaves = self.leaves(b);
+				self.queue.extend(leaves);
+				return;
+			}
+
+			let base = self.blossom_base[b].unwrap();
+			let m = self.mate[base].expect("the base of an inner blossom is matched");
+			w = self.endpoint(m);
+			label = OUTER;
+			p = Some(m ^ 1);
+		}
+	}
+
+	/// Traces back from two outer vertices joined by an edge. Returns the base of the new blossom
+	/// if the paths meet, or `None` if they reach two different free vertices.
+	fn scan_blossom(&mut self, v: usize, w: usize) -> Option<usize> {
+		let mut path = Vec::new();
+		let mut base = None;
+		let (mut v, mut w) = (Some(v), Some(w));
+
+		while let Some(x) = v {
+			let b = self.in_blossom[x];
+			if self.label[b] & VISITED != 0 {
+				base = self.blossom_base[b];
+				break;
+			}
+
+			path.push(b);
+			self.label[b] = OUTER | VISITED;
+			v = self.label_end[b].map(|e| {
+				let t = self.in_blossom[self.endpoint(e)];
+				self.endpoint(self.label_end[t].unwrap())
+			});
+
+			// Alternate between the two paths
+			if w.is_some() {
+				std::mem::swap(&mut v, &mut w);
+			}
+		}
+
+		for b in path {
+			self.label[b] = OUTER;
+		}
+		base
+	}
+
+	/// Forms a new blossom with the given base, closed by edge `k` between two outer vertices.
+	fn add_blossom(&mut self, base: usize, k: usize) {
+		let (v, w, _) = self.edges[k];
+		let bb = self.in_blossom[base];
+		let mut bv = self.in_blossom[v];
+		let mut bw = self.in_blossom[w];
+
+		let b = self.unused_blossoms.pop().expect("there are at most n / 2 blossoms");
+		self.blossom_base[b] = Some(base);
+		self.blossom_parent[b] = None;
+		self.blossom_parent[bb] = Some(b);
+
+		// Collect the sub-blossoms around the cycle, starting at the base
+		let mut children = Vec::new();
+		let mut ends = Vec::new();
+		while bv != bb {
+			self.blossom_parent[bv] = Some(b);
+			children.push(bv);
+			let e = self.label_end[bv].unwrap();
+			ends.push(e);
+			bv = self.in_blossom[self.endpoint(e)];
+		}
+		children.push(bb);
+		children.reverse();
+		ends.reverse();
+		ends.push(2 * k);
+		while bw != bb {
+			self.blossom_parent[bw] = Some(b);
+			children.push(bw);
+			let e = self.label_end[bw].unwrap();
+			ends.push(e ^ 1);
+			bw = self.in_blossom[self.endpoint(e)];
+		}
+
+		self.label[b] = OUTER;
+		self.label_end[b] = self.label_end[bb];
+		self.dual[b] = W::zero();
+		self.blossom_children[b] = children.clone();
+		self.blossom_ends[b] = ends;
+
+		// The inner vertices become outer, so they need to be scanned
+		for v in self.leaves(b) {
+			if self.label[self.in_blossom[v]] == INNER {
+				self.queue.push(v);
+			}
+			self.in_blossom[v] = b;
+		}
+
+		// Merge the least-slack edges of the sub-blossoms
+		let mut best_to: Vec<Option<usize>> = vec![None; 2 * self.n];
+		for &child in &children {
+			let lists: Vec<Vec<usize>> = match self.blossom_best_edges[child].take() {
+				Some(list) => vec![list],
+				None => self.leaves(child).into_iter()
+					.map(|v| self.neighbor_ends[v].iter().map(|p| p / 2).collect())
+					.collect(),
+			};
+
+			for k in lists.into_iter().flatten() {
+				let (i, j, _) = self.edges[k];
+				let j = if self.in_blossom[j] == b { i } else { j };
+				let bj = self.in_blossom[j];
+				if bj != b && self.label[bj] == OUTER && best_to[bj].is_none_or(|e| self.slack(k) < self.slack(e)) {
+					best_to[bj] = Some(k);
+				}
+			}
+			self.best_edge[child] = None;
+		}
+
+		let best: Vec<usize> = best_to.into_iter().flatten().collect();
+		self.best_edge[b] = None;
+		for &k in &best {
+			if self.best_edge[b].is_none_or(|e| self.slack(k) < self.slack(e)) {
+				self.best_edge[b] = Some(k);
+			}
+		}
+		self.blossom_best_edges[b] = Some(best);
+	}
+
+	/// Dissolves a blossom into its sub-blossoms. In the middle of a stage, an inner blossom's
+	/// sub-blossoms are relabeled so that the alternating tree stays intact. At the end of a stage,
+	/// sub-blossoms with a zero dual are expanded as well.
+	fn expand_blossom(&mut self, b: usize, end_stage: bool) {
+		let children = std::mem::take(&mut self.blossom_children[b]);
+		let ends = std::mem::take(&mut self.blossom_ends[b]);
+
+		for &s in &children {
+			self.blossom_parent[s] = None;
+			if s < self.n {
+				self.in_blossom[s] = s;
+			} else if end_stage && self.dual[s] == W::zero() {
+				self.expand_blossom(s, end_stage);
+			} else {
+				for v in self.leaves(s) {
+					self.in_blossom[v] = s;
+				}
+			}
+		}
+
+		if !end_stage && self.label[b] == INNER {
+			// Walk around the cycle from the sub-blossom the blossom was entered through to the
+			// base, in the direction that gives an even-length path
+			let len = children.len() as isize;
+			let at = |j: isize| j.rem_euclid(len) as usize;
+			let entry_end = self.label_end[b].unwrap();
+			let entry_child = self.in_blossom[self.endpoint(entry_end ^ 1)];
+			let mut j = children.iter().position(|&c| c == entry_child).unwrap() as isize;
+			let (step, trick) = if j & 1 == 1 {
+				j -= len;
+				(1, 0)
+			} else {
+				(-1, 1)
+			};
+
+			// Relabel the sub-blossoms along that path, alternating inner and outer
+			let mut p = entry_end;
+			while j != 0 {
+				let q = self.endpoint(p ^ 1);
+				self.label[q] = FREE;
+				let r = self.endpoint(ends[at(j - trick as isize)] ^ trick ^ 1);
+				self.label[r] = FREE;
+				self.assign_label(q, INNER, Some(p));
+				self.allow_edge[ends[at(j - trick as isize)] / 2] = true;
+				j += step;
+				p = ends[at(j - trick as isize)] ^ trick;
+				self.allow_edge[p / 2] = true;
+				j += step;
+			}
+
+			// The base sub-blossom becomes inner without labeling its mate, which is already outer
+			let bv = children[at(j)];
+			let q = self.endpoint(p ^ 1);
+			self.label[q] = INNER;
+			self.label[bv] = INNER;
+			self.label_end[q] = Some(p);
+			self.label_end[bv] = Some(p);
+			self.best_edge[bv] = None;
+			j += step;
+
+			// The sub-blossoms on the other side of the cycle become free again, unless they were
+			// reached from outside the blossom
+			while children[at(j)] != entry_child {
+				let bv = children[at(j)];
+				j += step;
+				if self.label[bv] == OUTER {
+					continue;
+				}
+
+				if let Some(v) = self.leaves(bv).into_iter().find(|&v| self.label[v] != FREE) {
+					self.label[v] = FREE;
+					let m = self.mate[self.blossom_base[bv].unwrap()].unwrap();
+					let x = self.endpoint(m);
+					self.label[x] = FREE;
+					self.assign_label(v, INNER, self.label_end[v]);
+				}
+			}
+		}
+
+		self.label[b] = FREE;
+		self.label_end[b] = None;
+		self.blossom_base[b] = None;
+		self.blossom_best_edges[b] = None;
+		self.best_edge[b] = None;
+		self.unused_blossoms.push(b);
+	}
+
+	/// Flips the matching along the even-length path from vertex `v` to the base of blossom `b`,
+	/// and rotates the blossom so that `v` becomes its base.
+	fn augment_blossom(&mut self, b: usize, v: usize) {
+		// Find the sub-blossom containing v
+		let mut t = v;
+		while self.blossom_parent[t] != Some(b) {
+			t = self.blossom_parent[t].unwrap();
+		}
+		if t >= self.n {
+			self.augment_blossom(t, v);
+		}
+
+		let children = self.blossom_children[b].clone();
+		let ends = self.blossom_ends[b].clone();
+		let len = children.len() as isize;
+		let at = |j: isize| j.rem_euclid(len) as usize;
+
+		let i = children.iter().position(|&c| c == t).unwrap();
+		let mut j = i as isize;
+		let (step, trick) = if i & 1 == 1 {
+			j -= len;
+			(1, 0)
+		} else {
+			(-1, 1)
+		};
+
+		while j != 0 {
+			j += step;
+			let t = children[at(j)];
+			let p = ends[at(j - trick as isize)] ^ trick;
+			if t >= self.n {
+				self.augment_blossom(t, self.endpoint(p));
+			}
+			j += step;
+			let t = children[at(j)];
+			if t >= self.n {
+				self.augment_blossom(t, self.endpoint(p ^ 1));
+			}
+
+			let (x, y) = (self.endpoint(p), self.endpoint(p ^ 1));
+			self.mate[x] = Some(p ^ 1);
+			self.mate[y] = Some(p);
+		}
+
+		self.blossom_children[b].rotate_left(i);
+		self.blossom_ends[b].rotate_left(i);
+		self.blossom_base[b] = self.blossom_base[self.blossom_children[b][0]];
+	}
+
+	/// Flips the matching along the augmenting path through edge `k`, which joins the alternating
+	/// trees of two different free vertices.
+	fn augment_matching(&mut self, k: usize) {
+		let (v, w, _) = self.edges[k];
+		for (mut s, mut p) in [(v, 2 * k + 1), (w, 2 * k)] {
+			loop {
+				let bs = self.in_blossom[s];
+				if bs >= self.n {
+					self.augment_blossom(bs, s);
+				}
+				self.mate[s] = Some(p);
+
+				// Stop at the free vertex at the root of the tree
+				let Some(e) = self.label_end[bs] else { break };
+				let t = self.endpoint(e);
+				let bt = self.in_blossom[t];
+				let e = self.label_end[bt].unwrap();
+				s = self.endpoint(e);
+				let j = self.endpoint(e ^ 1);
+				if bt >= self.n {
+					self.augment_blossom(bt, j);
+				}
+				self.mate[j] = Some(e);
+				p = e ^ 1;
+			}
+		}
+	}
+
+	/// Runs the algorithm. Every stage either augments the matching or proves it optimal.
+	fn solve(&mut self) {
+		let n = self.n;
+		let two = W::one() + W::one();
+
+		for _ in 0..n {
+			self.label.fill(FREE);
+			self.best_edge.fill(None);
+			self.blossom_best_edges[n..].fill(None);
+			self.allow_edge.fill(false);
+			self.queue.clear();
+
+			// Every free vertex is the root of an alternating tree
+			for v in 0..n {
+				if self.mate[v].is_none() && self.label[self.in_blossom[v]] == FREE {
+					self.assign_label(v, OUTER, None);
+				}
+			}
+
+			let mut augmented = false;
+			loop {
+				// Grow the trees along edges with zero slack
+				while let Some(v) = self.queue.pop() {
+					for p in self.neighbor_ends[v].clone() {
+						let k = p / 2;
+						let w = self.endpoint(p);
+						if self.in_blossom[v] == self.in_blossom[w] {
+							continue;
+						}
+
+						let slack = self.slack(k);
+						if slack <= W::zero() {
+							self.allow_edge[k] = true;
+						}
+
+						let bw = self.in_blossom[w];
+						if self.allow_edge[k] {
+							if self.label[bw] == FREE {
+								self.assign_label(w, INNER, Some(p ^ 1));
+							} else if self.label[bw] == OUTER {
+								match self.scan_blossom(v, w) {
+									Some(base) => self.add_blossom(base, k),
+									None => {
+										self.augment_matching(k);
+										augmented = true;
+										break;
+									}
+								}
+							} else if self.label[w] == FREE {
+								// w is inside an inner blossom, but wasn't reached yet
+								self.label[w] = INNER;
+								self.label_end[w] = Some(p ^ 1);
+							}
+						} else if self.label[bw] == OUTER {
+							let b = self.in_blossom[v];
+							if self.best_edge[b].is_none_or(|e| slack < self.slack(e)) {
+								self.best_edge[b] = Some(k);
+							}
+						} else if self.label[w] == FREE && self.best_edge[w].is_none_or(|e| slack < self.slack(e)) {
+							self.best_edge[w] = Some(k);
+						}
+					}
+
+					if augmented {
+						break;
+					}
+				}
+
+				if augmented {
+					break;
+				}
+
+				// No tight edge is left, so change the duals by the largest amount that keeps them
+				// feasible. The choices are the smallest outer vertex dual (after which the matching
+				// is optimal), the slack of an edge from an outer to a free vertex, half the slack of
+				// an edge between two outer blossoms, or the dual of an inner blossom.
+				let mut delta = (0..n).map(|v| self.dual[v]).fold(self.dual[0], |min, d| if d < min { d } else { min });
+				let mut delta_type = 1;
+				let mut delta_edge = None;
+				let mut delta_blossom = None;
+
+				for v in 0..n {
+					if self.label[self.in_blossom[v]] == FREE {
+						if let Some(e) = self.best_edge[v] {
+							let d = self.slack(e);
+							if d < delta {
+								(delta, delta_type, delta_edge) = (d, 2, Some(e));
+							}
+						}
+					}
+				}
+				for b in 0..2 * n {
+					if self.blossom_parent[b].is_none() && self.label[b] == OUTER {
+						if let Some(e) = self.best_edge[b] {
+							let d = self.slack(e) / two;
+							if d < delta {
+								(delta, delta_type, delta_edge) = (d, 3, Some(e));
+							}
+						}
+					}
+				}
+				for b in n..2 * n {
+					let top_level = self.blossom_base[b].is_some() && self.blossom_parent[b].is_none();
+					if top_level && self.label[b] == INNER && self.dual[b] < delta {
+						(delta, delta_type, delta_blossom) = (self.dual[b], 4, Some(b));
+					}
+				}
+
+				for v in 0..n {
+					match self.label[self.in_blossom[v]] {
+						OUTER => self.dual[v] = self.dual[v] - delta,
+						INNER => self.dual[v] = self.dual[v] + delta,
+						_ => {}
+					}
+				}
+				for b in n..2 * n {
+					if self.blossom_base[b].is_some() && self.blossom_parent[b].is_none() {
+						match self.label[b] {
+							OUTER => self.dual[b] = self.dual[b] + delta,
+							INNER => self.dual[b] = self.dual[b] - delta,
+							_ => {}
+						}
+					}
+				}
+
+				match delta_type {
+					1 => break,
+					2 => {
+						let e = delta_edge.unwrap();
+						self.allow_edge[e] = true;
+						let (i, j, _) = self.edges[e];
+						self.queue.push(if self.label[self.in_blossom[i]] == FREE { j } else { i });
+					}
+					3 => {
+						let e = delta_edge.unwrap();
+						self.allow_edge[e] = true;
+						self.queue.push(self.edges[e].0);
+					}
+					_ => self.expand_blossom(delta_blossom.unwrap(), false),
+				}
+			}
+
+			if !augmented {
+				break;
+			}
+
+			// Outer blossoms whose dual dropped to zero are no longer needed
+			for b in n..2 * n {
+				let top_level = self.blossom_base[b].is_some() && self.blossom_parent[b].is_none();
+				if top_level && self.label[b] == OUTER && self.dual[b] == W::zero() {
+					self.expand_blossom(b, true);
+				}
+			}
+		}
+	}
+}
+
+/// Finds a maximum weight matching of a weighted graph using Edmonds' blossom algorithm.
+///
+/// The matching has the highest total weight of all matchings, regardless of its size, so edges
+/// with a negative weight are never matched. This is the primal-dual method of Edmonds and Galil,
+/// which runs in `O(V^3)` time. Weights of an integer type give exact results.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted undirected graph to find a matching in.
+///
+/// # Panics
+///
+/// Panics if four times the largest weight overflows the weight type, since the dual variables of
+/// the algorithm need that much room. For example, `u8` weights must be at most 63.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph, WeightedGraph};
+/// use istos::matching::{is_matching, max_weight_matching};
+///
+/// // A path where matching both ends beats matching the heavy middle edge
+/// let mut graph: UndirectedGraph<(), u32> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+/// graph.set_edge_weight(v[0], v[1], 5);
+/// graph.set_edge_weight(v[1], v[2], 8);
+/// graph.set_edge_weight(v[2], v[3], 5);
+///
+/// let matching = max_weight_matching(&graph);
+///
+/// assert_eq!(matching.edges, vec![(v[0], v[1]), (v[2], v[3])]);
+/// assert_eq!(matching.total_weight, 10);
+/// assert!(is_matching(&graph, &matching.edges));
+/// ```
+pub fn max_weight_matching<T, W, G>(graph: &G) -> WeightedMatching<W>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let n = index.len();
+
+	let mut edges = Vec::new();
+	for (u, neighbors) in index.adj.iter().enumerate() {
+		for (&v, &w) in neighbors.iter().zip(&weights[u]) {
+			// Edges with a negative weight would only lower the total
+			if u < v && w >= W::zero() {
+				edges.push((u, v, w));
+			}
+		}
+	}
+
+	let mut matching = WeightedMatching { edges: Vec::new(), total_weight: W::zero() };
+	if edges.is_empty() {
+		return matching;
+	}
+
+	let mut blossom = WeightedBlossom::new(n, edges);
+	blossom.solve();
+
+	for u in 0..n {
+		if let Some(p) = blossom.mate[u] {
+			let v = blossom.endpoint(p);
+			if u < v {
+				matching.edges.push((index.ids[u], index.ids[v]));
+				matching.total_weight = matching.total_weight + blossom.edges[p / 2].2;
+			}
+		}
+	}
+
+	matching
+}
+
+/// Finds a maximum weight matching among the edges between `n` vertices, as the vertex each vertex
+/// is matched to.
+pub(crate) fn max_weight_mates<W: Weight>(n: usize, edges: Vec<(usize, usize, W)>) -> Vec<Option<usize>> {
+	if edges.is_empty() {
+		return vec![None; n];
+	}
+
+	let mut blossom = WeightedBlossom::new(n, edges);
+	blossom.solve();
+	(0..n).map(|u| blossom.mate[u].map(|p| blossom.endpoint(p))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::{Rng, TestDigraph};
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+
+	/// Checks that a matching is valid and that its vertex cover covers every edge.
+	fn check_matching<T: Clone, G: Graph<T>>(graph: &G, matching: &BipartiteMatching) {
+		assert!(is_matching(graph, &matching.edges));
+		assert_eq!(matching.vertex_cover.len(), matching.edges.len());
+		for u in graph.get_vertices() {
+			for v in graph.get_neighbors(u) {
+				assert!(matching.vertex_cover.contains(&u) || matching.vertex_cover.contains(&v));
+			}
+		}
+	}
+
+	#[test]
+	fn test_hopcroft_karp() {
+		// Five workers and five tasks, where only four tasks can be covered
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let workers: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+		let tasks: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+
+		let edges = [(0, 0), (0, 1), (1, 0), (1, 4), (2, 2), (2, 3), (3, 0), (3, 4), (4, 1), (4, 3)];
+		for (w, t) in edges {
+			graph.add_edge(workers[w], tasks[t]);
+		}
+
+		let matching = hopcroft_karp(&graph, &workers).unwrap();
+		check_matching(&graph, &matching);
+		assert_eq!(matching.edges.len(), 5);
+
+		// Without task 4, workers 1 and 3 compete for task 0
+		graph.remove_vertex(tasks[4]);
+		let matching = hopcroft_karp(&graph, &workers).unwrap();
+		check_matching(&graph, &matching);
+		assert_eq!(matching.edges.len(), 4);
+
+		// The sides can be swapped
+		let matching = hopcroft_karp(&graph, &tasks[..4]).unwrap();
+		check_matching(&graph, &matching);
+		assert_eq!(matching.edges.len(), 4);
+	}
+
+	#[test]
+	fn test_hopcroft_karp_long_paths() {
+		// Left vertex i can take right vertex i or i + 1, which has the lower ID and is tried first.
+		// That leaves the last left vertex without a partner, and the only augmenting path runs
+		// through the whole graph.
+		let n = 2_000;
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let mut right: Vec<usize> = (0..n).map(|_| graph.add_vertex(())).collect();
+		right.reverse();
+		let left: Vec<usize> = (0..n).map(|_| graph.add_vertex(())).collect();
+		for i in 0..n {
+			graph.add_edge(left[i], right[i]);
+			if i + 1 < n {
+				graph.add_edge(left[i], right[i + 1]);
+			}
+		}
+
+		let matching = hopcroft_karp(&graph, &left).unwrap();
+		assert_eq!(matching.edges, (0..n).map(|i| (left[i], right[i])).collect::<Vec<_>>());
+		assert_eq!(matching.vertex_cover.len(), n);
+	}
+
+	#[test]
+	fn test_hopcroft_karp_directed() {
+		// Only the edges leaving the left vertices count
+		let graph = TestDigraph::with_edges(4, &[(0, 2), (0, 3), (1, 2)]);
+		let matching = hopcroft_karp(&graph, &[0, 1]).unwrap();
+
+		check_matching(&graph, &matching);
+		assert_eq!(matching.edges, vec![(0, 3), (1, 2)]);
+	}
+
+	#[test]
+	fn test_hopcroft_karp_invalid() {
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..3).map(|_| graph.add_vertex(())).collect();
+		graph.add_edge(v[0], v[1]);
+		graph.add_edge(v[1], v[2]);
+
+		assert!(hopcroft_karp(&graph, &[v[1]]).is_some());
+		assert!(hopcroft_karp(&graph, &[v[0]]).is_none());
+		assert!(hopcroft_karp(&graph, &[v[1], 99]).is_none());
+	}
+
+	#[test]
+	fn test_hungarian() {
+		let costs = vec![
+			vec![9, 2, 7, 8],
+			vec![6, 4, 3, 7],
+			vec![5, 8, 1, 8],
+			vec![7, 6, 9, 4],
+		];
+		let assignment = hungarian(&costs).unwrap();
+		assert_eq!(assignment.columns, vec![Some(1), Some(0), Some(2), Some(3)]);
+		assert_eq!(assignment.total_cost, 13);
+
+		// More rows than columns leaves the most expensive row out
+		let costs = vec![vec![1.5, 9.0], vec![2.0, 1.0], vec![0.5, 0.5]];
+		let assignment = hungarian(&costs).unwrap();
+		assert_eq!(assignment.columns, vec![None, Some(1), Some(0)]);
+		assert_eq!(assignment.total_cost, 1.5);
+
+		// More columns than rows leaves the most expensive columns unused
+		let assignment = hungarian(&[vec![5u32, 4, 3, 2, 1]]).unwrap();
+		assert_eq!(assignment.columns, vec![Some(4)]);
+		assert_eq!(assignment.total_cost, 1);
+		let costs = vec![vec![3, 8, 1, 6], vec![2, 9, 1, 7]];
+		let assignment = hungarian(&costs).unwrap();
+		assert_eq!(assignment.columns, vec![Some(2), Some(0)]);
+		assert_eq!(assignment.total_cost, 3);
+
+		// Negative costs
+		let costs = vec![vec![-1, -5], vec![-3, -4]];
+		assert_eq!(hungarian(&costs).unwrap().total_cost, -8);
+		assert_eq!(hungarian(&[vec![7, -2, 0]]).unwrap().total_cost, -2);
+
+		assert_eq!(hungarian::<u32>(&[]).unwrap().columns, vec![]);
+		assert!(hungarian(&[vec![1, 2], vec![3]]).is_none());
+	}
+
+	#[test]
+	fn test_min_weight_bipartite_matching() {
+		let mut graph: UndirectedGraph<(), u32> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+
+		// Matching 0 with 3 is cheap, but leaves 1 without a partner
+		graph.set_edge_weight(v[0], v[3], 1);
+		graph.set_edge_weight(v[0], v[4], 10);
+		graph.set_edge_weight(v[1], v[3], 5);
+
+		let matching = min_weight_bipartite_matching(&graph, &[v[0], v[1], v[2]]).unwrap();
+		assert_eq!(matching.edges, vec![(v[0], v[4]), (v[1], v[3])]);
+		assert_eq!(matching.total_weight, 15);
+
+		// Among the largest matchings, the lightest one wins
+		graph.set_edge_weight(v[2], v[4], 3);
+		let matching = min_weight_bipartite_matching(&graph, &[v[0], v[1], v[2]]).unwrap();
+		assert_eq!(matching.edges, vec![(v[0], v[3]), (v[2], v[4])]);
+		assert_eq!(matching.total_weight, 4);
+
+		assert!(min_weight_bipartite_matching(&graph, &[v[0], v[3]]).is_none());
+
+		// A single left vertex takes its lightest edge
+		let mut graph: UndirectedGraph<(), i32> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..3).map(|_| graph.add_vertex(())).collect();
+		graph.set_edge_weight(v[0], v[1], 0);
+		graph.set_edge_weight(v[0], v[2], -3);
+		let matching = min_weight_bipartite_matching(&graph, &[v[0]]).unwrap();
+		assert_eq!(matching.edges, vec![(v[0], v[2])]);
+		assert_eq!(matching.total_weight, -3);
+	}
+
+	#[test]
+	fn test_assign_brute_force() {
+		// Finds the size and the cheapest cost of the largest assignments by trying every one
+		fn brute_force(costs: &[Vec<Option<i64>>], used: &mut Vec<bool>, i: usize) -> (usize, i64) {
+			let Some(row) = costs.get(i) else { return (0, 0) };
+			let mut best = brute_force(costs, used, i + 1);
+			for (j, &cost) in row.iter().enumerate() {
+				let Some(cost) = cost else { continue };
+				if used[j] {
+					continue;
+				}
+				used[j] = true;
+				let (size, total) = brute_force(costs, used, i + 1);
+				used[j] = false;
+				if size + 1 > best.0 || (size + 1 == best.0 && total + cost < best.1) {
+					best = (size + 1, total + cost);
+				}
+			}
+			best
+		}
+
+		// Matrices of every shape up to 5 by 5, with some cells missing and some costs negative
+		for rows in 1..=5 {
+			for columns in 1..=5 {
+				for seed in 0..8 {
+					let costs: Vec<Vec<Option<i64>>> = (0..rows)
+						.map(|i| (0..columns)
+							.map(|j| {
+								let x = (i * 7 + j * 13 + seed * 17 + i * j * seed) % 23;
+								(x % 5 != 0 || seed % 2 == 0).then_some(x as i64 - 6)
+							})
+							.collect())
+						.collect();
+
+					let assigned = assign(&costs, columns);
+					let size = assigned.iter().flatten().count();
+					let total: i64 = assigned.iter().enumerate().filter_map(|(i, j)| costs[i][(*j)?]).sum();
+					assert_eq!((size, total), brute_force(&costs, &mut vec![false; columns], 0), "{:?}", costs);
+
+					let mut columns_used: Vec<usize> = assigned.iter().flatten().copied().collect();
+					columns_used.sort_unstable();
+					columns_used.dedup();
+					assert_eq!(columns_used.len(), size);
+				}
+			}
+		}
+	}
+
+	/// Builds a graph with the given weighted edges between `n` vertices.
+	fn build_weighted(n: usize, edges: &[(usize, usize, i64)]) -> (UndirectedSparseGraph<(), i64>, Vec<usize>) {
+		let mut graph = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..n).map(|_| graph.add_vertex(())).collect();
+		for &(a, b, w) in edges {
+			graph.set_edge_weight(v[a], v[b], w);
+		}
+		(graph, v)
+	}
+
+	/// Finds the weight of a maximum weight matching by trying every matching.
+	fn brute_force(n: usize, edges: &[(usize, usize, i64)]) -> i64 {
+		fn search(matched: &mut [bool], edges: &[(usize, usize, i64)], u: usize) -> i64 {
+			let Some(u) = (u..matched.len()).find(|&u| !matched[u]) else { return 0 };
+
+			// Either leave u unmatched, or match it along one of its edges
+			matched[u] = true;
+			let mut best = search(matched, edges, u + 1);
+			for &(a, b, w) in edges {
+				let v = if a == u { b } else if b == u { a } else { continue };
+				if !matched[v] {
+					matched[v] = true;
+					best = best.max(w + search(matched, edges, u + 1));
+					matched[v] = false;
+				}
+			}
+			matched[u] = false;
+			best
+		}
+
+		search(&mut vec![false; n], edges, 0)
+	}
+
+	/// A number of vertices with weighted edges between them.
+	type Edges = (usize, Vec<(usize, usize, i64)>);
+
+	/// Generates random graphs with up to ten vertices.
+	fn random_graphs(count: usize, max_weight: i64) -> Vec<Edges> {
+		let mut rng = Rng::new(0x2545_f491_4f6c_dd1d);
+
+		(0..count)
+			.map(|_| {
+				let n = 2 + rng.below(9);
+				let mut edges = Vec::new();
+				for a in 0..n {
+					for b in a + 1..n {
+						if rng.below(100) < 40 {
+							edges.push((a, b, 1 + rng.below(max_weight as usize) as i64));
+						}
+					}
+				}
+				(n, edges)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn test_maximum_matching() {
+		// The Petersen graph has a perfect matching
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..10).map(|_| graph.add_vertex(())).collect();
+		for i in 0..5 {
+			graph.add_edge(v[i], v[(i + 1) % 5]);
+			graph.add_edge(v[i], v[i + 5]);
+			graph.add_edge(v[i + 5], v[(i + 2) % 5 + 5]);
+		}
+
+		let matching = maximum_matching(&graph);
+		assert_eq!(matching.len(), 5);
+		assert!(is_matching(&graph, &matching));
+
+		// An odd cycle leaves one vertex out
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..7).map(|_| graph.add_vertex(())).collect();
+		for i in 0..7 {
+			graph.add_edge(v[i], v[(i + 1) % 7]);
+		}
+		assert_eq!(maximum_matching(&graph).len(), 3);
+	}
+
+	#[test]
+	fn test_maximum_matching_random() {
+		for (n, edges) in random_graphs(200, 1) {
+			let (graph, _) = build_weighted(n, &edges);
+			let matching = maximum_matching(&graph);
+
+			assert!(is_matching(&graph, &matching));
+			assert_eq!(matching.len() as i64, brute_force(n, &edges));
+		}
+	}
+
+	#[test]
+	fn test_max_weight_matching() {
+		// Cases that need blossoms to be formed, relabeled and expanded
+		let cases = [
+			(4, vec![(0, 1, 8), (0, 2, 9), (1, 2, 10), (2, 3, 7)], vec![(0, 1), (2, 3)]),
+			(6, vec![(0, 1, 9), (0, 2, 8), (1, 2, 10), (0, 3, 5), (3, 4, 4), (0, 5, 3)], vec![(0, 5), (1, 2), (3, 4)]),
+			(6, vec![(0, 1, 9), (0, 2, 9), (1, 2, 10), (1, 3, 8), (2, 4, 8), (3, 4, 10), (4, 5, 6)], vec![(0, 2), (1, 3), (4, 5)]),
+			(
+				8,
+				vec![(0, 1, 23), (0, 4, 22), (0, 5, 15), (1, 2, 25), (2, 3, 22), (3, 4, 25), (3, 7, 14), (4, 6, 13)],
+				vec![(0, 5), (1, 2), (3, 7), (4, 6)],
+			),
+			(
+				8,
+				vec![(0, 1, 19), (0, 2, 20), (0, 7, 8), (1, 2, 25), (1, 3, 18), (2, 4, 18), (3, 4, 13), (3, 6, 7), (4, 5, 7)],
+				vec![(0, 7), (1, 2), (3, 6), (4, 5)],
+			),
+			(
+				10,
+				vec![
+					(0, 1, 40), (0, 2, 40), (1, 2, 60), (1, 3, 55), (2, 4, 55), (3, 4, 50), (0, 7, 15), (4, 6, 30),
+					(6, 5, 10), (7, 9, 10), (3, 8, 30),
+				],
+				vec![(0, 1), (2, 4), (3, 8), (5, 6), (7, 9)],
+			),
+		];
+
+		for (n, edges, expected) in cases {
+			let (graph, v) = build_weighted(n, &edges);
+			let matching = max_weight_matching(&graph);
+
+			let expected: Vec<(usize, usize)> = expected.iter().map(|&(a, b)| (v[a], v[b])).collect();
+			assert_eq!(matching.edges, expected);
+			assert_eq!(matching.total_weight, brute_force(n, &edges));
+		}
+	}
+
+	#[test]
+	fn test_max_weight_matching_random() {
+		for (n, edges) in random_graphs(300, 20) {
+			let (graph, _) = build_weighted(n, &edges);
+			let matching = max_weight_matching(&graph);
+
+			assert!(is_matching(&graph, &matching.edges));
+			assert_eq!(matching.total_weight, brute_force(n, &edges));
+		}
+	}
+
+	#[test]
+	fn test_max_weight_matching_types() {
+		// Negative edges are left out
+		let (graph, v) = build_weighted(3, &[(0, 1, -2), (1, 2, 3)]);
+		assert_eq!(max_weight_matching(&graph).edges, vec![(v[1], v[2])]);
+
+		let mut graph: UndirectedGraph<(), f64> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+		graph.set_edge_weight(v[0], v[1], 1.5);
+		graph.set_edge_weight(v[1], v[2], 2.5);
+		graph.set_edge_weight(v[2], v[3], 1.25);
+		graph.set_edge_weight(v[3], v[0], 0.5);
+		assert_eq!(max_weight_matching(&graph).total_weight, 3.0);
+
+		// The largest weight that leaves room for four times it
+		let mut graph: UndirectedSparseGraph<(), u8> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..3).map(|_| graph.add_vertex(())).collect();
+		graph.set_edge_weight(v[0], v[1], 63);
+		graph.set_edge_weight(v[1], v[2], 63);
+		graph.set_edge_weight(v[2], v[0], 63);
+		assert_eq!(max_weight_matching(&graph).total_weight, 63);
+
+		let graph: UndirectedSparseGraph<(), u8> = UndirectedSparseGraph::new();
+		assert!(max_weight_matching(&graph).edges.is_empty());
+	}
+
+	#[test]
+	#[should_panic(expected = "four times the largest weight")]
+	fn test_max_weight_matching_overflow() {
+		let mut graph: UndirectedSparseGraph<(), u8> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..3).map(|_| graph.add_vertex(())).collect();
+		graph.set_edge_weight(v[0], v[1], 200);
+		graph.set_edge_weight(v[1], v[2], 150);
+		max_weight_matching(&graph);
+	}
+
+	#[test]
+	fn test_is_matching() {
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+		graph.add_edge(v[0], v[1]);
+		graph.add_edge(v[1], v[2]);
+		graph.add_edge(v[2], v[3]);
+		graph.add_edge(v[3], v[3]);
+
+		assert!(is_matching(&graph, &[]));
+		assert!(is_matching(&graph, &[(v[0], v[1]), (v[3], v[2])]));
+		assert!(!is_matching(&graph, &[(v[0], v[1]), (v[1], v[2])]));
+		assert!(!is_matching(&graph, &[(v[0], v[2])]));
+		assert!(!is_matching(&graph, &[(v[3], v[3])]));
+	}
+}
@@ -0,0 +1,339 @@
+//! Global minimum cuts and Gomory–Hu trees of weighted undirected graphs.
+
+use std::collections::VecDeque;
+
+use super::flow::Network;
+use super::util::Indexed;
+use super::{UndirectedSparseGraph, Weight, WeightedGraph};
+
+/// A cut splitting the vertices of a graph into two non-empty sides.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MinCut<W> {
+	/// The total weight of the edges between the two sides.
+	pub weight: W,
+	/// The IDs of the vertices on one side of the cut.
+	pub side_1: Vec<usize>,
+	/// The IDs of the vertices on the other side of the cut.
+	pub side_2: Vec<usize>,
+}
+
+/// Finds a global minimum cut of a weighted undirected graph using the Stoer–Wagner algorithm.
+///
+/// Unlike a minimum s–t cut, the global minimum cut is the lightest cut over all ways to split the
+/// vertices in two. Runs in `O(V^3)` time. Edge weights must not be negative.
+///
+/// Returns `None` if the graph has fewer than two vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted undirected graph to cut.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph, WeightedGraph};
+/// use istos::min_cut::stoer_wagner;
+///
+/// // Two triangles joined by a light edge
+/// let mut graph: UndirectedGraph<(), u32> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..6).map(|_| graph.add_vertex(())).collect();
+/// for (a, b) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)] {
+///     graph.set_edge_weight(v[a], v[b], 5);
+/// }
+/// graph.set_edge_weight(v[2], v[3], 1);
+///
+/// let cut = stoer_wagner(&graph).unwrap();
+///
+/// assert_eq!(cut.weight, 1);
+/// assert_eq!(cut.side_1.len(), 3);
+/// ```
+pub fn stoer_wagner<T, W, G>(graph: &G) -> Option<MinCut<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let n = index.len();
+	if n < 2 {
+		return None;
+	}
+
+	// The weights between the merged vertices, ignoring self loops
+	let mut matrix: Vec<Vec<W>> = vec![vec![W::zero(); n]; n];
+	for u in 0..n {
+		for (&v, &w) in index.adj[u].iter().zip(&weights[u]) {
+			if u != v {
+				matrix[u][v] = w;
+			}
+		}
+	}
+
+	// The original vertices merged into each vertex
+	let mut groups: Vec<Vec<usize>> = (0..n).map(|u| vec![u]).collect();
+	let mut active: Vec<usize> = (0..n).collect();
+	let mut best: Option<(W, usize)> = None;
+	let mut best_group: Vec<usize> = Vec::new();
+
+	while active.len() > 1 {
+		// Order the vertices by maximum adjacency: each next vertex is the one most tightly
+		// connected to the vertices before it
+		let mut connection: Vec<W> = vec![W::zero(); n];
+		let mut added = vec![false; n];
+		let mut previous = active[0];
+		let mut last = active[0];
+
+		for i in 0..active.len() {
+			let mut next: Option<usize> = None;
+			for &v in &active {
+				if !added[v] && next.is_none_or(|x| connection[v] > connection[x]) {
+					next = Some(v);
+				}
+			}
+
+			let v = next.unwrap();
+			added[v] = true;
+			if i == active.len() - 1 {
+				last = v;
+			} else {
+				previous = v;
+				for &x in &active {
+					if !added[x] {
+						connection[x] = connection[x] + matrix[v][x];
+					}
+				}
+			}
+		}
+
+		// The last vertex, cut off from everything else, is a minimum cut between the last two
+		let cut_of_phase = connection[last];
+		if best.is_none_or(|(w, _)| cut_of_phase < w) {
+			best = Some((cut_of_phase, last));
+			best_group = groups[last].clone();
+		}
+
+		// Merge the last two vertices
+		for &x in &active {
+			matrix[previous][x] = matrix[previous][x] + matrix[last][x];
+			matrix[x][previous] = matrix[previous][x];
+		}
+		matrix[previous][previous] = W::zero();
+		let merged = std::mem::take(&mut groups[last]);
+		groups[previous].extend(merged);
+		active.retain(|&x| x != last);
+	}
+
+	let (weight, _) = best?;
+	let mut in_group = vec![false; n];
+	for &u in &best_group {
+		in_group[u] = true;
+	}
+	let (side_1, side_2): (Vec<usize>, Vec<usize>) = (0..n).partition(|&u| in_group[u]);
+
+	Some(MinCut {
+		weight,
+		side_1: side_1.into_iter().map(|u| index.ids[u]).collect(),
+		side_2: side_2.into_iter().map(|u| index.ids[u]).collect(),
+	})
+}
+
+/// Computes a Gomory–Hu tree of a weighted undirected graph using Gusfield's algorithm.
+///
+/// The Gomory–Hu tree has the same vertices as the graph, and for every pair of vertices the
+/// lightest edge on the tree path between them has the weight of a minimum cut between them in the
+/// graph. Removing that edge splits the tree into the two sides of such a cut. The tree is built
+/// with `V - 1` maximum flow computations. Edge weights must not be negative.
+///
+/// Returns the tree as a new graph with all vertices of the original graph, carrying their original
+/// IDs and data. Use [`min_cut_value`] to look up the minimum cut between two vertices.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted undirected graph to compute the tree of.
+pub fn gomory_hu_tree<T, W, G>(graph: &G) -> UndirectedSparseGraph<T, W>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let n = index.len();
+
+	// Every edge can carry its weight in flow in either direction
+	let network = Network::from_weights(&index, &weights);
+
+	// Start with a star around the first vertex, and refine it one vertex at a time
+	let mut parent: Vec<usize> = vec![0; n];
+	let mut cut: Vec<W> = vec![W::zero(); n];
+
+	for s in 1..n {
+		let t = parent[s];
+
+		let mut flow = network.clone();
+		flow.dinic(s, t);
+		let value = flow.outflow(s);
+		let side = flow.reachable(s);
+
+		cut[s] = value;
+		for i in 0..n {
+			if i != s && side[i] && parent[i] == t {
+				parent[i] = s;
+			}
+		}
+		if side[parent[t]] {
+			parent[s] = parent[t];
+			parent[t] = s;
+			cut[s] = cut[t];
+			cut[t] = value;
+		}
+	}
+
+	let vertices = index.ids.iter()
+		.filter_map(|&id| Some((id, graph.get_vertex_data(id)?)))
+		.collect();
+	let edges = (1..n).map(|s| (index.ids[s], index.ids[parent[s]], cut[s])).collect();
+
+	UndirectedSparseGraph::from_parts(vertices, edges)
+}
+
+/// Looks up the weight of a minimum cut between two vertices in a Gomory–Hu tree.
+///
+/// Returns the weight of the lightest edge on the tree path between the two vertices, or `None` if
+/// either vertex does not exist, if they are the same vertex, or if there is no path between them.
+///
+/// # Arguments
+///
+/// - `tree`: A Gomory–Hu tree, as computed by [`gomory_hu_tree`].
+/// - `vertex_id_1`: The ID of the first vertex.
+/// - `vertex_id_2`: The ID of the second vertex.
+pub fn min_cut_value<T, W>(tree: &UndirectedSparseGraph<T, W>, vertex_id_1: usize, vertex_id_2: usize) -> Option<W>
+where
+	T: Clone,
+	W: Weight + Default,
+{
+	let index = Indexed::new(tree);
+	let weights = index.weights(tree);
+	let start = *index.index.get(&vertex_id_1)?;
+	let end = *index.index.get(&vertex_id_2)?;
+	if start == end {
+		return None;
+	}
+
+	// Search the tree, keeping track of the lightest edge on the path to every vertex
+	let mut lightest: Vec<Option<W>> = vec![None; index.len()];
+	let mut visited = vec![false; index.len()];
+	let mut queue = VecDeque::from([start]);
+	visited[start] = true;
+
+	while let Some(u) = queue.pop_front() {
+		for (&v, &w) in index.adj[u].iter().zip(&weights[u]) {
+			if !visited[v] {
+				visited[v] = true;
+				lightest[v] = Some(match lightest[u] {
+					Some(l) if l < w => l,
+					_ => w,
+				});
+				queue.push_back(v);
+			}
+		}
+	}
+
+	lightest[end]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::flow::dinic;
+	use crate::{Graph, UndirectedGraph};
+
+	/// Builds the example graph from the Stoer–Wagner paper, whose minimum cut separates
+	/// {0, 1, 4, 5} from {2, 3, 6, 7} with weight 4.
+	fn build_graph<G: WeightedGraph<(), u32>>(mut graph: G) -> (G, Vec<usize>) {
+		let v: Vec<usize> = (0..8).map(|_| graph.add_vertex(())).collect();
+
+		let edges = [
+			(0, 1, 2), (0, 4, 3), (1, 2, 3), (1, 4, 2), (1, 5, 2), (2, 3, 4),
+			(2, 6, 2), (3, 6, 2), (3, 7, 2), (4, 5, 3), (5, 6, 1), (6, 7, 3),
+		];
+		for (a, b, w) in edges {
+			graph.set_edge_weight(v[a], v[b], w);
+		}
+
+		(graph, v)
+	}
+
+	#[test]
+	fn test_stoer_wagner() {
+		let (graph, v) = build_graph(UndirectedGraph::new());
+		let mut cut = stoer_wagner(&graph).unwrap();
+
+		assert_eq!(cut.weight, 4);
+		if cut.side_1.contains(&v[0]) {
+			std::mem::swap(&mut cut.side_1, &mut cut.side_2);
+		}
+		assert_eq!(cut.side_1, vec![v[2], v[3], v[6], v[7]]);
+		assert_eq!(cut.side_2, vec![v[0], v[1], v[4], v[5]]);
+
+		let (graph, _) = build_graph(UndirectedSparseGraph::new());
+		assert_eq!(stoer_wagner(&graph).unwrap().weight, 4);
+	}
+
+	#[test]
+	fn test_stoer_wagner_disconnected() {
+		let mut graph: UndirectedSparseGraph<(), f64> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+		graph.set_edge_weight(v[0], v[1], 1.5);
+		graph.set_edge_weight(v[2], v[3], 2.5);
+
+		let cut = stoer_wagner(&graph).unwrap();
+		assert_eq!(cut.weight, 0.0);
+		assert_eq!(cut.side_1.len() + cut.side_2.len(), 4);
+
+		let mut graph: UndirectedSparseGraph<(), f64> = UndirectedSparseGraph::new();
+		graph.add_vertex(());
+		assert!(stoer_wagner(&graph).is_none());
+	}
+
+	#[test]
+	fn test_gomory_hu_tree() {
+		let (graph, v) = build_graph(UndirectedSparseGraph::new());
+		let tree = gomory_hu_tree(&graph);
+
+		assert_eq!(tree.get_vertices(), v);
+		assert_eq!(crate::connectivity::connected_components(&tree).len(), 1);
+		assert!(!crate::toposort::is_cyclic_undirected(&tree));
+
+		// The tree answers every pair's minimum cut
+		for &a in &v {
+			for &b in &v {
+				if a != b {
+					assert_eq!(min_cut_value(&tree, a, b), Some(dinic(&graph, a, b).unwrap().value));
+				}
+			}
+		}
+
+		assert_eq!(min_cut_value(&tree, v[0], v[0]), None);
+		assert_eq!(min_cut_value(&tree, v[0], 99), None);
+	}
+
+	#[test]
+	fn test_gomory_hu_tree_keeps_vertex_data() {
+		let mut graph: UndirectedGraph<&str, i32> = UndirectedGraph::new();
+		let a = graph.add_vertex("a");
+		let b = graph.add_vertex("b");
+		let c = graph.add_vertex("c");
+		graph.set_edge_weight(a, b, 3);
+		graph.set_edge_weight(b, c, 2);
+		let d = graph.add_vertex("d");
+		graph.remove_vertex(d);
+
+		let tree = gomory_hu_tree(&graph);
+
+		assert_eq!(tree.get_vertex_data(c), Some("c"));
+		assert_eq!(min_cut_value(&tree, a, b), Some(3));
+		assert_eq!(min_cut_value(&tree, a, c), Some(2));
+	}
+}
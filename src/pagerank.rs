@@ -0,0 +1,249 @@
+//! PageRank and personalized PageRank.
+//!
+//! The functions in this module treat [`Graph::get_neighbors`] as returning the successors of a
+//! vertex, like the functions in [`strongly_connected`](crate::strongly_connected), so a vertex
+//! passes its rank on along its outgoing edges. On the undirected graphs in this crate every edge
+//! passes rank both ways.
+
+use std::collections::HashMap;
+
+use super::util::Indexed;
+use super::Graph;
+
+/// The damping factor [`personalized_pagerank`] uses.
+const DEFAULT_DAMPING: f64 = 0.85;
+/// The tolerance [`personalized_pagerank`] uses.
+const DEFAULT_TOLERANCE: f64 = 1e-10;
+/// The most iterations [`personalized_pagerank`] runs.
+const DEFAULT_MAX_ITERATIONS: usize = 1000;
+
+/// Computes the ranks by power iteration, starting from the teleport distribution.
+///
+/// A random surfer follows an outgoing edge of its vertex with probability `damping`, and otherwise
+/// jumps to a vertex drawn from `teleport`. Surfers at dangling vertices, which have no outgoing
+/// edges, always jump.
+fn power_iteration(index: &Indexed, teleport: &[f64], damping: f64, tolerance: f64, max_iterations: usize) -> Vec<f64> {
+	let n = index.len();
+	let mut rank = teleport.to_vec();
+	let mut next = vec![0.0; n];
+
+	for _ in 0..max_iterations {
+		let mut dangling = 0.0;
+		next.iter_mut().for_each(|x| *x = 0.0);
+		for (u, successors) in index.adj.iter().enumerate() {
+			if successors.is_empty() {
+				dangling += rank[u];
+				continue;
+			}
+
+			let share = damping * rank[u] / successors.len() as f64;
+			for &v in successors {
+				next[v] += share;
+			}
+		}
+
+		let jump = 1.0 - damping + damping * dangling;
+		let mut change = 0.0;
+		for v in 0..n {
+			next[v] += jump * teleport[v];
+			change += (next[v] - rank[v]).abs();
+		}
+
+		std::mem::swap(&mut rank, &mut next);
+		if change < tolerance {
+			break;
+		}
+	}
+
+	rank
+}
+
+/// Collects the ranks by vertex ID.
+fn by_id(index: &Indexed, rank: Vec<f64>) -> HashMap<usize, f64> {
+	index.ids.iter().copied().zip(rank).collect()
+}
+
+/// Computes the PageRank of every vertex of a graph: the probability of finding a random surfer at
+/// the vertex, where the surfer follows a random outgoing edge with probability `damping` and
+/// otherwise jumps to a random vertex.
+///
+/// A surfer at a dangling vertex, which has no outgoing edges, always jumps to a random vertex, so
+/// the ranks always add up to one. The ranks are found by power iteration, which stops once the
+/// ranks change by less than `tolerance` in total, or after `max_iterations` iterations. Each
+/// iteration takes `O(V + E)` time, and the error shrinks by a factor of `damping` or better per
+/// iteration.
+///
+/// Returns a map from the IDs of the vertices to their ranks.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to rank the vertices of.
+/// - `damping`: The probability of following an edge rather than jumping, usually 0.85.
+/// - `tolerance`: The total change in the ranks below which the iteration stops.
+/// - `max_iterations`: The most iterations to run.
+///
+/// # Panics
+///
+/// Panics if `damping` is not between 0 and 1.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::pagerank::pagerank;
+///
+/// // The center of a star collects about half the rank
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let center = graph.add_vertex(());
+/// for _ in 0..4 {
+///     let leaf = graph.add_vertex(());
+///     graph.add_edge(center, leaf);
+/// }
+///
+/// let ranks = pagerank(&graph, 0.85, 1e-10, 100);
+///
+/// assert!((ranks[&center] - 0.4757).abs() < 1e-4);
+/// assert!((ranks.values().sum::<f64>() - 1.0).abs() < 1e-9);
+/// ```
+pub fn pagerank<T: Clone, G: Graph<T>>(graph: &G, damping: f64, tolerance: f64, max_iterations: usize) -> HashMap<usize, f64> {
+	assert!((0.0..=1.0).contains(&damping), "the damping factor must be between 0 and 1");
+
+	let index = Indexed::new(graph);
+	let n = index.len();
+	let teleport = vec![1.0 / n as f64; n];
+	let rank = power_iteration(&index, &teleport, damping, tolerance, max_iterations);
+	by_id(&index, rank)
+}
+
+/// Computes the personalized PageRank of every vertex of a graph: the PageRank where the random
+/// surfer always jumps back to one of the seed vertices instead of to any vertex.
+///
+/// The ranks measure how close the vertices are to the seeds, and are zero for vertices that can't
+/// be reached from them. Surfers at dangling vertices, which have no outgoing edges, also jump back
+/// to the seeds. Uses a damping factor of 0.85, and iterates until the ranks change by less than
+/// `1e-10` in total.
+///
+/// Returns a map from the IDs of the vertices to their ranks. IDs in `seeds` that aren't in the
+/// graph are ignored, and if none of them are, every vertex is a seed as in [`pagerank`].
+///
+/// # Arguments
+///
+/// - `graph`: The graph to rank the vertices of.
+/// - `seeds`: The IDs of the vertices the surfer jumps to.
+pub fn personalized_pagerank<T: Clone, G: Graph<T>>(graph: &G, seeds: &[usize]) -> HashMap<usize, f64> {
+	let index = Indexed::new(graph);
+	let mut teleport = vec![0.0; index.len()];
+	let mut count = 0;
+	for u in seeds.iter().filter_map(|id| index.index.get(id).copied()) {
+		if teleport[u] == 0.0 {
+			teleport[u] = 1.0;
+			count += 1;
+		}
+	}
+
+	if count == 0 {
+		return pagerank(graph, DEFAULT_DAMPING, DEFAULT_TOLERANCE, DEFAULT_MAX_ITERATIONS);
+	}
+
+	teleport.iter_mut().for_each(|x| *x /= count as f64);
+	let rank = power_iteration(&index, &teleport, DEFAULT_DAMPING, DEFAULT_TOLERANCE, DEFAULT_MAX_ITERATIONS);
+	by_id(&index, rank)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::{Rng, TestDigraph};
+	use crate::UndirectedSparseGraph;
+
+	fn assert_close(a: f64, b: f64) {
+		assert!((a - b).abs() < 1e-8, "{a} is not close to {b}");
+	}
+
+	#[test]
+	fn test_pagerank_dangling() {
+		// The rank of the dangling vertex 1 is spread over both vertices: x0 = 1 / (2 + d)
+		let graph = TestDigraph::with_edges(2, &[(0, 1)]);
+		let ranks = pagerank(&graph, 0.85, 1e-12, 1000);
+		assert_close(ranks[&0], 1.0 / 2.85);
+		assert_close(ranks[&1], 1.0 - 1.0 / 2.85);
+
+		// Without damping, the surfer only jumps from the dangling vertex
+		let graph = TestDigraph::with_edges(3, &[(0, 1), (1, 2)]);
+		let ranks = pagerank(&graph, 1.0, 1e-12, 1000);
+		assert_close(ranks[&0], 1.0 / 6.0);
+		assert_close(ranks[&1], 2.0 / 6.0);
+		assert_close(ranks[&2], 3.0 / 6.0);
+	}
+
+	#[test]
+	fn test_pagerank_fixed_point() {
+		// Check the ranks against the definition on a random graph with dangling vertices
+		let n = 40;
+		let mut rng = Rng::new(1);
+		let mut edges = Vec::new();
+		for u in 0..n {
+			for v in 0..n {
+				if rng.below(10) == 0 && u % 7 != 3 {
+					edges.push((u, v));
+				}
+			}
+		}
+		let graph = TestDigraph::with_edges(n, &edges);
+		let ranks = pagerank(&graph, 0.85, 1e-13, 1000);
+
+		let out_degree = |u: usize| edges.iter().filter(|e| e.0 == u).count();
+		let dangling: f64 = (0..n).filter(|&u| out_degree(u) == 0).map(|u| ranks[&u]).sum();
+		for v in 0..n {
+			let incoming: f64 = edges.iter().filter(|e| e.1 == v).map(|&(u, _)| ranks[&u] / out_degree(u) as f64).sum();
+			assert_close(ranks[&v], 0.15 / n as f64 + 0.85 * (incoming + dangling / n as f64));
+		}
+		assert_close(ranks.values().sum(), 1.0);
+	}
+
+	#[test]
+	fn test_pagerank_undirected() {
+		// Without damping, the ranks on a connected non-bipartite graph are proportional to the degrees
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+		for (a, b) in [(0, 1), (1, 2), (2, 0), (2, 3)] {
+			graph.add_edge(v[a], v[b]);
+		}
+		let ranks = pagerank(&graph, 1.0, 1e-13, 1000);
+		for (i, degree) in [2.0, 2.0, 3.0, 1.0].into_iter().enumerate() {
+			assert_close(ranks[&v[i]], degree / 8.0);
+		}
+
+		// A cycle ranks every vertex the same
+		let graph = TestDigraph::with_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+		let ranks = pagerank(&graph, 0.85, 1e-12, 100);
+		(0..5).for_each(|u| assert_close(ranks[&u], 0.2));
+
+		assert!(pagerank(&UndirectedSparseGraph::<()>::new(), 0.85, 1e-6, 100).is_empty());
+	}
+
+	#[test]
+	fn test_personalized_pagerank() {
+		// Two paths, with the seed at the end of the first, which outranks the other end
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..6).map(|_| graph.add_vertex(())).collect();
+		for (a, b) in [(0, 1), (1, 2), (3, 4), (4, 5)] {
+			graph.add_edge(v[a], v[b]);
+		}
+
+		let ranks = personalized_pagerank(&graph, &[v[0]]);
+		assert!(ranks[&v[0]] > ranks[&v[2]]);
+		assert!(ranks[&v[1]] > ranks[&v[2]]);
+		(3..6).for_each(|i| assert_eq!(ranks[&v[i]], 0.0));
+		assert_close(ranks.values().sum(), 1.0);
+
+		// Surfers at the dangling vertex jump back to the seed, not to the unreachable vertex 2
+		let graph = TestDigraph::with_edges(3, &[(0, 1), (2, 0)]);
+		let ranks = personalized_pagerank(&graph, &[0, 0, 7]);
+		assert_close(ranks[&0], 1.0 / 1.85);
+		assert_eq!(ranks[&2], 0.0);
+
+		// Without any seeds in the graph, every vertex is a seed
+		assert_eq!(personalized_pagerank(&graph, &[7]), pagerank(&graph, 0.85, 1e-10, 1000));
+	}
+}
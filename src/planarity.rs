@@ -0,0 +1,860 @@
+//! Planarity testing with combinatorial embeddings and Kuratowski subgraphs.
+//!
+//! A graph is planar if it can be drawn in the plane without crossing edges. [`is_planar`] uses the
+//! left-right planarity test of de Fraysseix and Rosenstiehl, as described by Brandes: a depth-first
+//! search orients the graph, and every back edge is assigned to the left or right side of the tree
+//! path it returns to, subject to constraints that are only satisfiable if the graph is planar. The
+//! sides then give the cyclic order of the edges around every vertex. All searches are iterative, so
+//! they handle deep graphs without overflowing the stack.
+//!
+//! By Kuratowski's theorem, a graph is planar if and only if it contains no subdivision of `K5` or
+//! `K3,3`, so a non-planar graph comes with such a subgraph as a certificate. Self loops and
+//! parallel edges do not affect planarity and are ignored.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use super::util::Indexed;
+use super::Graph;
+
+/// A combinatorial embedding of a planar graph: the cyclic order of the edges around every vertex
+/// in a drawing without crossings.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlanarEmbedding {
+	/// A map from the ID of every vertex to the IDs of its neighbors in clockwise order. Tracing the
+	/// faces of the drawing, the edge after `(u, v)` is `(v, w)`, where `w` follows `u` around `v`.
+	pub rotation: HashMap<usize, Vec<usize>>,
+}
+
+/// The kind of Kuratowski subgraph found in a non-planar graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KuratowskiKind {
+	/// A subdivision of the complete graph on five vertices.
+	K5,
+	/// A subdivision of the complete bipartite graph on three and three vertices.
+	K33,
+}
+
+/// The error returned when a graph is not planar: a subgraph that is a subdivision of `K5` or
+/// `K3,3`, which can't be drawn without crossing edges.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KuratowskiSubgraph {
+	/// Whether the subgraph is a subdivision of `K5` or `K3,3`.
+	pub kind: KuratowskiKind,
+	/// The IDs of the vertices of degree three or four in the subgraph, which correspond to the
+	/// vertices of `K5` or `K3,3`.
+	pub branch_vertices: Vec<usize>,
+	/// The edges of the subgraph, as pairs of vertex IDs. The branch vertices are connected by paths
+	/// through vertices of degree two.
+	pub edges: Vec<(usize, usize)>,
+}
+
+impl fmt::Display for KuratowskiSubgraph {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let kind = match self.kind {
+			KuratowskiKind::K5 => "K5",
+			KuratowskiKind::K33 => "K3,3",
+		};
+		write!(f, "the graph is not planar: it contains a subdivision of {} on the vertices {:?}", kind, self.branch_vertices)
+	}
+}
+
+impl Error for KuratowskiSubgraph {}
+
+/// An interval of return edges on one side of a conflict pair, from its lowest to its highest.
+#[derive(Clone, Copy, Default)]
+struct Interval {
+	low: Option<usize>,
+	high: Option<usize>,
+}
+
+impl Interval {
+	fn is_empty(&self) -> bool {
+		self.low.is_none() && self.high.is_none()
+	}
+}
+
+/// Two intervals of return edges that must be on different sides.
+#[derive(Clone, Copy, Default)]
+struct ConflictPair {
+	left: Interval,
+	right: Interval,
+}
+
+impl ConflictPair {
+	fn swap(&mut self) {
+		std::mem::swap(&mut self.left, &mut self.right);
+	}
+}
+
+/// The cyclic orders of the neighbors around the vertices, as doubly linked lists.
+struct Rotation {
+	cw: Vec<HashMap<usize, usize>>,
+	ccw: Vec<HashMap<usize, usize>>,
+	first: Vec<Option<usize>>,
+}
+
+impl Rotation {
+	fn new(n: usize) -> Self {
+		Rotation { cw: vec![HashMap::new(); n], ccw: vec![HashMap::new(); n], first: vec![None; n] }
+	}
+
+	/// Places `w` around `v` directly clockwise of `reference`, or as the only neighbor.
+	fn add_cw(&mut self, v: usize, w: usize, reference: Option<usize>) {
+		match reference {
+			None => {
+				self.cw[v].insert(w, w);
+				self.ccw[v].insert(w, w);
+				self.first[v] = Some(w);
+			}
+			Some(r) => {
+				let next = self.cw[v][&r];
+				self.cw[v].insert(r, w);
+				self.cw[v].insert(w, next);
+				self.ccw[v].insert(next, w);
+				self.ccw[v].insert(w, r);
+			}
+		}
+	}
+
+	/// Places `w` around `v` directly counterclockwise of `reference`, or as the only neighbor.
+	fn add_ccw(&mut self, v: usize, w: usize, reference: Option<usize>) {
+		match reference {
+			None => self.add_cw(v, w, None),
+			Some(r) => {
+				self.add_cw(v, w, Some(self.ccw[v][&r]));
+				if self.first[v] == Some(r) {
+					self.first[v] = Some(w);
+				}
+			}
+		}
+	}
+
+	/// Places `w` around `v` directly counterclockwise of the first neighbor, making it the first.
+	fn add_first(&mut self, v: usize, w: usize) {
+		self.add_ccw(v, w, self.first[v]);
+	}
+
+	/// Lists the neighbors of `v` clockwise from the first.
+	fn neighbors(&self, v: usize) -> Vec<usize> {
+		let mut neighbors = Vec::new();
+		if let Some(first) = self.first[v] {
+			let mut w = first;
+			loop {
+				neighbors.push(w);
+				w = self.cw[v][&w];
+				if w == first {
+					break;
+				}
+			}
+		}
+		neighbors
+	}
+}
+
+/// The state of the left-right planarity test. Every edge is oriented away from the root of the
+/// depth-first search, and identified by its index in `source` and `target`.
+struct LeftRight<'a> {
+	adj: &'a [Vec<(usize, usize)>],
+	height: Vec<Option<usize>>,
+	parent_edge: Vec<Option<usize>>,
+	roots: Vec<usize>,
+	oriented: Vec<bool>,
+	source: Vec<usize>,
+	target: Vec<usize>,
+	out: Vec<Vec<usize>>,
+	lowpt: Vec<usize>,
+	lowpt2: Vec<usize>,
+	nesting_depth: Vec<i64>,
+	reference: Vec<Option<usize>>,
+	side: Vec<i64>,
+	lowpt_edge: Vec<usize>,
+	stack_bottom: Vec<usize>,
+	stack: Vec<ConflictPair>,
+}
+
+impl<'a> LeftRight<'a> {
+	fn new(adj: &'a [Vec<(usize, usize)>], edges: usize) -> Self {
+		let n = adj.len();
+		LeftRight {
+			adj,
+			height: vec![None; n],
+			parent_edge: vec![None; n],
+			roots: Vec::new(),
+			oriented: vec![false; edges],
+			source: vec![0; edges],
+			target: vec![0; edges],
+			out: vec![Vec::new(); n],
+			lowpt: vec![0; edges],
+			lowpt2: vec![0; edges],
+			nesting_depth: vec![0; edges],
+			reference: vec![None; edges],
+			side: vec![1; edges],
+			lowpt_edge: vec![0; edges],
+			stack_bottom: vec![0; edges],
+			stack: Vec::new(),
+		}
+	}
+
+	/// Runs the test, returning the embedding if the graph is planar.
+	fn run(mut self) -> Option<Rotation> {
+		let n = self.adj.len();
+		let mut next = vec![0; n];
+		for root in 0..n {
+			if self.height[root].is_none() {
+				self.height[root] = Some(0);
+				self.roots.push(root);
+				self.orient(root, &mut next);
+			}
+		}
+
+		let mut ordered = self.out.clone();
+		for edges in &mut ordered {
+			edges.sort_by_key(|&e| self.nesting_depth[e]);
+		}
+		next.fill(0);
+		let mut descended = vec![false; self.source.len()];
+		for i in 0..self.roots.len() {
+			if !self.test(self.roots[i], &ordered, &mut next, &mut descended) {
+				return None;
+			}
+		}
+
+		Some(self.embed())
+	}
+
+	/// Orients the edges reachable from `root` with a depth-first search, computing the lowpoints
+	/// and nesting depths of the edges.
+	fn orient(&mut self, root: usize, next: &mut [usize]) {
+		let mut stack = vec![root];
+		while let Some(v) = stack.pop() {
+			let hv = self.height[v].unwrap();
+			let parent = self.parent_edge[v];
+			while next[v] < self.adj[v].len() {
+				let (w, e) = self.adj[v][next[v]];
+				if !self.oriented[e] {
+					self.oriented[e] = true;
+					self.source[e] = v;
+					self.target[e] = w;
+					self.out[v].push(e);
+					self.lowpt[e] = hv;
+					self.lowpt2[e] = hv;
+					match self.height[w] {
+						None => {
+							// A tree edge: come back to it once `w` is finished
+							self.parent_edge[w] = Some(e);
+							self.height[w] = Some(hv + 1);
+							stack.push(v);
+							stack.push(w);
+							break;
+						}
+						Some(hw) => self.lowpt[e] = hw,
+					}
+				} else if self.source[e] != v {
+					// Already oriented from the other end
+					next[v] += 1;
+					continue;
+				}
+
+				self.nesting_depth[e] = 2 * self.lowpt[e] as i64 + i64::from(self.lowpt2[e] < hv);
+				if let Some(p) = parent {
+					if self.lowpt[e] < self.lowpt[p] {
+						self.lowpt2[p] = self.lowpt[p].min(self.lowpt2[e]);
+						self.lowpt[p] = self.lowpt[e];
+					} else if self.lowpt[e] > self.lowpt[p] {
+						self.lowpt2[p] = self.lowpt2[p].min(self.lowpt[e]);
+					} else {
+						self.lowpt2[p] = self.lowpt2[p].min(self.lowpt2[e]);
+					}
+				}
+				next[v] += 1;
+			}
+		}
+	}
+
+	/// Whether the interval contains a return edge higher than the lowpoint of `e`.
+	fn conflicting(&self, interval: &Interval, e: usize) -> bool {
+		interval.high.is_some_and(|h| self.lowpt[h] > self.lowpt[e])
+	}
+
+	/// The lowest lowpoint of the return edges in a conflict pair.
+	fn lowest(&self, pair: &ConflictPair) -> usize {
+		match (pair.left.low, pair.right.low) {
+			(None, Some(r)) => self.lowpt[r],
+			(Some(l), None) => self.lowpt[l],
+			(Some(l), Some(r)) => self.lowpt[l].min(self.lowpt[r]),
+			(None, None) => usize::MAX,
+		}
+	}
+
+	/// Tests the edges reachable from `root` for conflicts, assigning every return edge a side
+	/// relative to another. Returns whether they can be satisfied.
+	fn test(&mut self, root: usize, ordered: &[Vec<usize>], next: &mut [usize], descended: &mut [bool]) -> bool {
+		let mut stack = vec![root];
+		while let Some(v) = stack.pop() {
+			let hv = self.height[v].unwrap();
+			let parent = self.parent_edge[v];
+			let mut finished = true;
+			while next[v] < ordered[v].len() {
+				let e = ordered[v][next[v]];
+				let w = self.target[e];
+				if !descended[e] {
+					self.stack_bottom[e] = self.stack.len();
+					if self.parent_edge[w] == Some(e) {
+						descended[e] = true;
+						stack.push(v);
+						stack.push(w);
+						finished = false;
+						break;
+					}
+					self.lowpt_edge[e] = e;
+					self.stack.push(ConflictPair { left: Interval::default(), right: Interval { low: Some(e), high: Some(e) } });
+				}
+
+				// Integrate the return edges of `e`
+				if self.lowpt[e] < hv {
+					let p = parent.expect("edges returning below the root");
+					if next[v] == 0 {
+						self.lowpt_edge[p] = self.lowpt_edge[e];
+					} else if !self.add_constraints(e, p) {
+						return false;
+					}
+				}
+				next[v] += 1;
+			}
+
+			if finished {
+				if let Some(p) = parent {
+					self.remove_back_edges(p);
+				}
+			}
+		}
+		true
+	}
+
+	/// Merges the return edges of `e`, a later outgoing edge of the target of `parent`, with the
+	/// constraints of the earlier ones. Returns whether they can be satisfied.
+	fn add_constraints(&mut self, e: usize, parent: usize) -> bool {
+		let mut pair = ConflictPair::default();
+
+		// Merge the return edges of `e` into the right side
+		loop {
+			let mut q = self.stack.pop().expect("the return edges of an edge are on the stack");
+			if !q.left.is_empty() {
+				q.swap();
+			}
+			if !q.left.is_empty() {
+				return false;
+			}
+			let low = q.right.low.unwrap();
+			if self.lowpt[low] > self.lowpt[parent] {
+				if pair.right.is_empty() {
+					pair.right = q.right;
+				} else {
+					self.reference[pair.right.low.unwrap()] = q.right.high;
+				}
+				pair.right.low = q.right.low;
+			} else {
+				self.reference[low] = Some(self.lowpt_edge[parent]);
+			}
+			if self.stack.len() == self.stack_bottom[e] {
+				break;
+			}
+		}
+
+		// Merge the conflicting return edges of the earlier edges into the left side
+		while let Some(&top) = self.stack.last() {
+			if !self.conflicting(&top.left, e) && !self.conflicting(&top.right, e) {
+				break;
+			}
+			let mut q = self.stack.pop().unwrap();
+			if self.conflicting(&q.right, e) {
+				q.swap();
+			}
+			if self.conflicting(&q.right, e) {
+				return false;
+			}
+			if let Some(low) = pair.right.low {
+				self.reference[low] = q.right.high;
+			}
+			if q.right.low.is_some() {
+				pair.right.low = q.right.low;
+			}
+			if pair.left.is_empty() {
+				pair.left = q.left;
+			} else {
+				self.reference[pair.left.low.unwrap()] = q.left.high;
+			}
+			pair.left.low = q.left.low;
+		}
+
+		if !pair.left.is_empty() || !pair.right.is_empty() {
+			self.stack.push(pair);
+		}
+		true
+	}
+
+	/// Removes the return edges ending at the source of the tree edge `e` once its subtree is done,
+	/// and gives `e` the side of its highest remaining return edge.
+	fn remove_back_edges(&mut self, e: usize) {
+		let u = self.source[e];
+		let hu = self.height[u].unwrap();
+
+		while self.stack.last().is_some_and(|top| self.lowest(top) == hu) {
+			let pair = self.stack.pop().unwrap();
+			if let Some(low) = pair.left.low {
+				self.side[low] = -1;
+			}
+		}
+
+		if let Some(mut pair) = self.stack.pop() {
+			// Trim the intervals of return edges ending at `u`
+			while let Some(high) = pair.left.high.filter(|&h| self.target[h] == u) {
+				pair.left.high = self.reference[high];
+			}
+			if let (None, Some(low)) = (pair.left.high, pair.left.low) {
+				self.reference[low] = pair.right.low;
+				self.side[low] = -1;
+				pair.left.low = None;
+			}
+			while let Some(high) = pair.right.high.filter(|&h| self.target[h] == u) {
+				pair.right.high = self.reference[high];
+			}
+			if let (None, Some(low)) = (pair.right.high, pair.right.low) {
+				self.reference[low] = pair.left.low;
+				self.side[low] = -1;
+				pair.right.low = None;
+			}
+			self.stack.push(pair);
+		}
+
+		if self.lowpt[e] < hu {
+			if let Some(top) = self.stack.last() {
+				let (hl, hr) = (top.left.high, top.right.high);
+				self.reference[e] = match (hl, hr) {
+					(Some(l), Some(r)) if self.lowpt[l] > self.lowpt[r] => hl,
+					(Some(_), None) => hl,
+					_ => hr,
+				};
+			}
+		}
+	}
+
+	/// Resolves the side of an edge relative to the edges it refers to, following the chain of
+	/// references.
+	fn sign(&mut self, e: usize) -> i64 {
+		let mut chain = Vec::new();
+		let mut f = e;
+		while let Some(r) = self.reference[f] {
+			chain.push(f);
+			f = r;
+		}
+		let mut sign = self.side[f];
+		for &g in chain.iter().rev() {
+			self.side[g] *= sign;
+			self.reference[g] = None;
+			sign = self.side[g];
+		}
+		self.side[e]
+	}
+
+	/// Builds the embedding from the sides of the edges.
+	fn embed(mut self) -> Rotation {
+		let n = self.adj.len();
+		for e in 0..self.source.len() {
+			self.nesting_depth[e] *= self.sign(e);
+		}
+
+		let mut rotation = Rotation::new(n);
+		let mut ordered = std::mem::take(&mut self.out);
+		for (v, edges) in ordered.iter_mut().enumerate() {
+			edges.sort_by_key(|&e| self.nesting_depth[e]);
+			let mut previous = None;
+			for &e in edges.iter() {
+				rotation.add_cw(v, self.target[e], previous);
+				previous = Some(self.target[e]);
+			}
+		}
+
+		let mut left_ref = vec![0; n];
+		let mut right_ref = vec![0; n];
+		let mut next = vec![0; n];
+		for &root in &self.roots {
+			let mut stack = vec![root];
+			while let Some(v) = stack.pop() {
+				while next[v] < ordered[v].len() {
+					let e = ordered[v][next[v]];
+					let w = self.target[e];
+					next[v] += 1;
+					if self.parent_edge[w] == Some(e) {
+						rotation.add_first(w, v);
+						left_ref[v] = w;
+						right_ref[v] = w;
+						stack.push(v);
+						stack.push(w);
+						break;
+					} else if self.side[e] == 1 {
+						rotation.add_cw(w, v, Some(right_ref[w]));
+					} else {
+						rotation.add_ccw(w, v, Some(left_ref[w]));
+						left_ref[w] = v;
+					}
+				}
+			}
+		}
+
+		rotation
+	}
+}
+
+/// Tests whether the graph on `n` vertices with the given simple edges is planar, returning the
+/// embedding if it is.
+fn planar_rotation(n: usize, edges: &[(usize, usize)]) -> Option<Rotation> {
+	// A simple planar graph with at least three vertices has at most `3V - 6` edges
+	if n >= 3 && edges.len() > 3 * n - 6 {
+		return None;
+	}
+
+	let mut adj = vec![Vec::new(); n];
+	for (e, &(u, v)) in edges.iter().enumerate() {
+		adj[u].push((v, e));
+		adj[v].push((u, e));
+	}
+	LeftRight::new(&adj, edges.len()).run()
+}
+
+/// Shrinks a non-planar set of edges to a minimal one, which is a Kuratowski subgraph.
+///
+/// Removes chunks of edges as long as the rest stays non-planar, halving the chunks down to single
+/// edges. An edge that can't be removed at some point can't be removed later either, since removing
+/// edges keeps a planar graph planar.
+fn minimal_non_planar(n: usize, mut edges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+	let mut chunk = edges.len().div_ceil(2).max(1);
+	loop {
+		let mut i = 0;
+		while i < edges.len() {
+			let end = (i + chunk).min(edges.len());
+			let rest: Vec<(usize, usize)> = edges[..i].iter().chain(&edges[end..]).copied().collect();
+			if planar_rotation(n, &rest).is_none() {
+				edges = rest;
+			} else {
+				i = end;
+			}
+		}
+		if chunk == 1 {
+			return edges;
+		}
+		chunk = chunk.div_ceil(2);
+	}
+}
+
+/// Tests whether a graph is planar, with the left-right planarity test.
+///
+/// Takes `O(V + E)` time for a planar graph. A non-planar graph is shrunk to a Kuratowski subgraph
+/// by removing edges as long as the rest stays non-planar, which takes up to `O(E)` more tests but
+/// usually far fewer.
+///
+/// Returns a [`PlanarEmbedding`] if the graph is planar, with the neighbors of every vertex in the
+/// clockwise order of a drawing without crossings. Otherwise returns a [`KuratowskiSubgraph`]: a
+/// subdivision of `K5` or `K3,3` in the graph.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to test.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::planarity::{is_planar, KuratowskiKind};
+///
+/// // A complete graph on four vertices is planar
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+/// for a in 0..4 {
+///     for b in a + 1..4 {
+///         graph.add_edge(v[a], v[b]);
+///     }
+/// }
+/// let embedding = is_planar(&graph).unwrap();
+/// assert_eq!(embedding.rotation[&v[0]].len(), 3);
+///
+/// // Connecting the fifth vertex to all the others makes it a complete graph on five vertices
+/// for a in 0..4 {
+///     graph.add_edge(v[a], v[4]);
+/// }
+/// let kuratowski = is_planar(&graph).unwrap_err();
+/// assert_eq!(kuratowski.kind, KuratowskiKind::K5);
+/// assert_eq!(kuratowski.edges.len(), 10);
+/// ```
+pub fn is_planar<T: Clone, G: Graph<T>>(graph: &G) -> Result<PlanarEmbedding, KuratowskiSubgraph> {
+	let index = Indexed::new(graph);
+	let n = index.len();
+
+	let mut edges: Vec<(usize, usize)> = index.adj.iter().enumerate()
+		.flat_map(|(u, neighbors)| neighbors.iter().filter(move |&&v| u < v).map(move |&v| (u, v)))
+		.collect();
+	edges.sort_unstable();
+	edges.dedup();
+
+	if let Some(rotation) = planar_rotation(n, &edges) {
+		let rotation = (0..n).map(|u| (index.ids[u], rotation.neighbors(u).into_iter().map(|v| index.ids[v]).collect())).collect();
+		return Ok(PlanarEmbedding { rotation });
+	}
+
+	let edges = minimal_non_planar(n, edges);
+	let mut degree = vec![0; n];
+	for &(u, v) in &edges {
+		degree[u] += 1;
+		degree[v] += 1;
+	}
+	let branch_vertices: Vec<usize> = (0..n).filter(|&u| degree[u] > 2).collect();
+	let kind = if branch_vertices.len() == 5 { KuratowskiKind::K5 } else { KuratowskiKind::K33 };
+
+	Err(KuratowskiSubgraph {
+		kind,
+		branch_vertices: branch_vertices.into_iter().map(|u| index.ids[u]).collect(),
+		edges: edges.into_iter().map(|(u, v)| (index.ids[u], index.ids[v])).collect(),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::Rng;
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+	use std::collections::HashSet;
+
+	fn graph_from_edges(n: usize, edges: &[(usize, usize)]) -> (UndirectedSparseGraph<()>, Vec<usize>) {
+		let mut graph = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..n).map(|_| graph.add_vertex(())).collect();
+		for &(a, b) in edges {
+			graph.add_edge(v[a], v[b]);
+		}
+		(graph, v)
+	}
+
+	/// Checks that an embedding lists the neighbors of every vertex and satisfies Euler's formula:
+	/// a connected plane graph with at least one edge has `E - V + 2` faces.
+	fn check_embedding<G: Graph<()>>(graph: &G, embedding: &PlanarEmbedding) {
+		let vertices = graph.get_vertices();
+		assert_eq!(embedding.rotation.len(), vertices.len());
+		let mut edges = HashSet::new();
+		for &u in &vertices {
+			let mut expected: Vec<usize> = graph.get_neighbors(u).into_iter().filter(|&v| v != u).collect();
+			expected.sort_unstable();
+			expected.dedup();
+			let mut actual = embedding.rotation[&u].clone();
+			actual.sort_unstable();
+			assert_eq!(actual, expected);
+			edges.extend(expected.iter().map(|&v| (u, v)));
+		}
+
+		// Trace the faces through the directed edges
+		let mut visited = HashSet::new();
+		let mut faces = 0;
+		for &start in &edges {
+			if !visited.insert(start) {
+				continue;
+			}
+			faces += 1;
+			let (mut u, mut v) = start;
+			loop {
+				let around = &embedding.rotation[&v];
+				let i = around.iter().position(|&w| w == u).unwrap();
+				(u, v) = (v, around[(i + 1) % around.len()]);
+				if !visited.insert((u, v)) {
+					break;
+				}
+			}
+		}
+
+		// Count the components with at least one edge
+		let mut seen = HashSet::new();
+		let mut components = 0;
+		for &u in vertices.iter().filter(|&&u| !embedding.rotation[&u].is_empty()) {
+			if seen.insert(u) {
+				components += 1;
+				let mut stack = vec![u];
+				while let Some(x) = stack.pop() {
+					stack.extend(embedding.rotation[&x].iter().copied().filter(|&y| seen.insert(y)));
+				}
+			}
+		}
+		let (v, e) = (seen.len() as i64, edges.len() as i64 / 2);
+		assert_eq!(faces, e - v + 2 * components);
+	}
+
+	/// Checks that a Kuratowski subgraph is in the graph and is a subdivision of its kind.
+	fn check_kuratowski<G: Graph<()>>(graph: &G, kuratowski: &KuratowskiSubgraph) {
+		let mut adj: HashMap<usize, Vec<usize>> = HashMap::new();
+		for &(u, v) in &kuratowski.edges {
+			assert!(u != v && graph.is_adjacent(u, v));
+			adj.entry(u).or_default().push(v);
+			adj.entry(v).or_default().push(u);
+		}
+		assert!(adj.values().all(|neighbors| neighbors.len() >= 2));
+
+		// Follow the paths between the branch vertices
+		let branch: HashSet<usize> = kuratowski.branch_vertices.iter().copied().collect();
+		let mut paths = HashSet::new();
+		let mut inner = HashSet::new();
+		for &b in &kuratowski.branch_vertices {
+			for &first in &adj[&b] {
+				let (mut previous, mut current) = (b, first);
+				while !branch.contains(&current) {
+					assert_eq!(adj[&current].len(), 2);
+					assert!(inner.insert((b, current)));
+					let next = adj[&current].iter().copied().find(|&x| x != previous).unwrap();
+					(previous, current) = (current, next);
+				}
+				assert_ne!(current, b);
+				assert!(paths.insert((b, current)));
+			}
+		}
+		assert_eq!(inner.len() / 2 + branch.len(), adj.len());
+
+		match kuratowski.kind {
+			KuratowskiKind::K5 => {
+				assert_eq!(branch.len(), 5);
+				assert_eq!(paths.len(), 20);
+			}
+			KuratowskiKind::K33 => {
+				assert_eq!(branch.len(), 6);
+				assert_eq!(paths.len(), 18);
+				let first = kuratowski.branch_vertices[0];
+				let side: HashSet<usize> = branch.iter().copied().filter(|&b| b == first || !paths.contains(&(first, b))).collect();
+				assert_eq!(side.len(), 3);
+				assert!(paths.iter().all(|(a, b)| side.contains(a) != side.contains(b)));
+			}
+		}
+	}
+
+	#[test]
+	fn test_small_graphs() {
+		let complete = |n: usize| -> Vec<(usize, usize)> { (0..n).flat_map(|a| (a + 1..n).map(move |b| (a, b))).collect() };
+
+		let (graph, _) = graph_from_edges(5, &complete(5));
+		let kuratowski = is_planar(&graph).unwrap_err();
+		assert_eq!(kuratowski.kind, KuratowskiKind::K5);
+		check_kuratowski(&graph, &kuratowski);
+
+		let k33: Vec<(usize, usize)> = (0..3).flat_map(|a| (3..6).map(move |b| (a, b))).collect();
+		let (graph, _) = graph_from_edges(6, &k33);
+		let kuratowski = is_planar(&graph).unwrap_err();
+		assert_eq!(kuratowski.kind, KuratowskiKind::K33);
+		check_kuratowski(&graph, &kuratowski);
+
+		// The Petersen graph contains a subdivision of K3,3
+		let petersen = [(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (0, 5), (1, 6), (2, 7), (3, 8), (4, 9), (5, 7), (7, 9), (9, 6), (6, 8), (8, 5)];
+		let (graph, _) = graph_from_edges(10, &petersen);
+		let kuratowski = is_planar(&graph).unwrap_err();
+		assert_eq!(kuratowski.kind, KuratowskiKind::K33);
+		check_kuratowski(&graph, &kuratowski);
+
+		let (graph, _) = graph_from_edges(6, &complete(6));
+		check_kuratowski(&graph, &is_planar(&graph).unwrap_err());
+
+		for n in 0..5 {
+			let (graph, _) = graph_from_edges(n, &complete(n));
+			check_embedding(&graph, &is_planar(&graph).unwrap());
+		}
+
+		// K3,3 without an edge, the cube, and a wheel, with a self loop and a parallel edge
+		let (graph, _) = graph_from_edges(6, &k33[1..]);
+		check_embedding(&graph, &is_planar(&graph).unwrap());
+		let cube = [(0, 1), (1, 2), (2, 3), (3, 0), (4, 5), (5, 6), (6, 7), (7, 4), (0, 4), (1, 5), (2, 6), (3, 7)];
+		let (graph, _) = graph_from_edges(8, &cube);
+		check_embedding(&graph, &is_planar(&graph).unwrap());
+		let mut wheel: Vec<(usize, usize)> = (1..8).flat_map(|i| [(0, i), (i, i % 7 + 1)]).collect();
+		wheel.extend([(3, 3), (1, 2)]);
+		let (graph, _) = graph_from_edges(8, &wheel);
+		check_embedding(&graph, &is_planar(&graph).unwrap());
+	}
+
+	#[test]
+	fn test_disconnected() {
+		// A K5 next to a planar component and an isolated vertex
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..10).map(|_| graph.add_vertex(())).collect();
+		for (a, b) in [(5, 6), (6, 7), (7, 5), (7, 8)] {
+			graph.add_edge(v[a], v[b]);
+		}
+		check_embedding(&graph, &is_planar(&graph).unwrap());
+
+		for a in 0..5 {
+			for b in a + 1..5 {
+				graph.add_edge(v[a], v[b]);
+			}
+		}
+		let kuratowski = is_planar(&graph).unwrap_err();
+		check_kuratowski(&graph, &kuratowski);
+		assert_eq!(kuratowski.branch_vertices, v[..5].to_vec());
+		assert!(kuratowski.to_string().contains("K5"));
+	}
+
+	#[test]
+	fn test_grid() {
+		// A long grid, whose depth-first search goes deep
+		let (w, h) = (4, 500);
+		let mut edges = Vec::new();
+		for y in 0..h {
+			for x in 0..w {
+				if x + 1 < w {
+					edges.push((y * w + x, y * w + x + 1));
+				}
+				if y + 1 < h {
+					edges.push((y * w + x, (y + 1) * w + x));
+				}
+			}
+		}
+		let (graph, _) = graph_from_edges(w * h, &edges);
+		check_embedding(&graph, &is_planar(&graph).unwrap());
+	}
+
+	#[test]
+	fn test_triangulations() {
+		// Random stacked triangulations are maximal planar, so any further edge makes them non-planar
+		let mut rng = Rng::new(7);
+		for n in [5, 12, 40, 100] {
+			let mut edges = vec![(0, 1), (1, 2), (2, 0)];
+			let mut triangles = vec![(0, 1, 2)];
+			for v in 3..n {
+				let (a, b, c) = triangles.swap_remove(rng.below(triangles.len()));
+				edges.extend([(a, v), (b, v), (c, v)]);
+				triangles.extend([(a, b, v), (b, c, v), (a, c, v)]);
+			}
+			assert_eq!(edges.len(), 3 * n - 6);
+			let (mut graph, v) = graph_from_edges(n, &edges);
+			check_embedding(&graph, &is_planar(&graph).unwrap());
+
+			let (a, b) = (0..n).flat_map(|a| (a + 1..n).map(move |b| (a, b))).find(|&(a, b)| !graph.is_adjacent(v[a], v[b])).unwrap();
+			graph.add_edge(v[a], v[b]);
+			check_kuratowski(&graph, &is_planar(&graph).unwrap_err());
+		}
+	}
+
+	#[test]
+	fn test_random() {
+		let mut rng = Rng::new(3);
+		let (mut planar, mut non_planar) = (0, 0);
+		for (n, m) in [(8, 12), (10, 16), (15, 22), (20, 28), (30, 40), (60, 75)] {
+			for _ in 0..20 {
+				let edges: Vec<(usize, usize)> = (0..m).map(|_| (rng.below(n), rng.below(n))).collect();
+				let (graph, _) = graph_from_edges(n, &edges);
+				match is_planar(&graph) {
+					Ok(embedding) => {
+						check_embedding(&graph, &embedding);
+						planar += 1;
+					}
+					Err(kuratowski) => {
+						check_kuratowski(&graph, &kuratowski);
+						non_planar += 1;
+					}
+				}
+			}
+		}
+		assert!(planar > 20 && non_planar > 20, "{} planar, {} non-planar", planar, non_planar);
+	}
+}
@@ -0,0 +1,387 @@
+//! Shortest path algorithms.
+
+use std::collections::{BinaryHeap, HashMap};
+use std::error::Error;
+use std::fmt;
+
+use super::util::{Indexed, MinScored};
+use super::{Weight, WeightedGraph};
+
+/// The error returned when a graph contains a cycle of negative total weight, in which case
+/// shortest paths are not well defined.
+///
+/// Note that in an undirected graph, every edge with a negative weight forms a negative cycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NegativeCycle;
+
+impl fmt::Display for NegativeCycle {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "the graph contains a negative cycle")
+	}
+}
+
+impl Error for NegativeCycle {}
+
+/// The shortest paths between all pairs of vertices in a graph.
+///
+/// The rows and columns of the distance and next-hop matrices follow the order of
+/// [`vertices`](Self::vertices). All vertices in the results are identified by their IDs in the
+/// original graph.
+#[derive(Clone, Debug)]
+pub struct AllPairsShortestPaths<W> {
+	vertices: Vec<usize>, // The vertex ID of each row and column
+	index: HashMap<usize, usize>, // The row and column of each vertex ID
+	distances: Vec<Vec<Option<W>>>, // The length of the shortest path between each pair of vertices
+	next_hops: Vec<Vec<Option<usize>>>, // The vertex following the source on each shortest path
+}
+
+impl<W: Weight> AllPairsShortestPaths<W> {
+	/// Builds the result from matrices indexed by the positions of the vertices in `index`.
+	fn from_indexed(index: Indexed, distances: Vec<Vec<Option<W>>>, next_hops: Vec<Vec<Option<usize>>>) -> Self {
+		let next_hops = next_hops.into_iter()
+			.map(|row| row.into_iter().map(|hop| hop.map(|i| index.ids[i])).collect())
+			.collect();
+
+		Self {
+			vertices: index.ids,
+			index: index.index,
+			distances,
+			next_hops,
+		}
+	}
+
+	/// Gets the IDs of the vertices corresponding to the rows and columns of the matrices.
+	pub fn vertices(&self) -> &[usize] {
+		&self.vertices
+	}
+
+	/// Gets the distance matrix.
+	///
+	/// The entry at `[i][j]` is the length of the shortest path from the `i`th to the `j`th vertex,
+	/// or `None` if there is no path between them.
+	pub fn distances(&self) -> &[Vec<Option<W>>] {
+		&self.distances
+	}
+
+	/// Gets the next-hop matrix.
+	///
+	/// The entry at `[i][j]` is the ID of the vertex following the `i`th vertex on the shortest path
+	/// to the `j`th vertex, or `None` if there is no such path or if `i == j`.
+	pub fn next_hops(&self) -> &[Vec<Option<usize>>] {
+		&self.next_hops
+	}
+
+	/// Gets the length of the shortest path between two vertices.
+	/// Returns `None` if there is no path or if either vertex does not exist.
+	///
+	/// # Arguments
+	///
+	/// - `from`: The ID of the vertex the path starts at.
+	/// - `to`: The ID of the vertex the path ends at.
+	pub fn distance(&self, from: usize, to: usize) -> Option<W> {
+		self.distances[*self.index.get(&from)?][*self.index.get(&to)?]
+	}
+
+	/// Gets the vertex following `from` on the shortest path to `to`.
+	/// Returns `None` if there is no path, if either vertex does not exist, or if `from == to`.
+	///
+	/// # Arguments
+	///
+	/// - `from`: The ID of the vertex the path starts at.
+	/// - `to`: The ID of the vertex the path ends at.
+	pub fn next_hop(&self, from: usize, to: usize) -> Option<usize> {
+		self.next_hops[*self.index.get(&from)?][*self.index.get(&to)?]
+	}
+
+	/// Reconstructs the shortest path between two vertices.
+	///
+	/// Returns the IDs of the vertices on the path, including both endpoints, or `None` if there is
+	/// no path or if either vertex does not exist.
+	///
+	/// # Arguments
+	///
+	/// - `from`: The ID of the vertex the path starts at.
+	/// - `to`: The ID of the vertex the path ends at.
+	pub fn path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+		self.distance(from, to)?;
+
+		let mut path = vec![from];
+		let mut current = from;
+		while current != to {
+			current = self.next_hop(current, to)?;
+			path.push(current);
+		}
+
+		Some(path)
+	}
+}
+
+/// Computes the shortest paths between all pairs of vertices using the Floyd–Warshall algorithm.
+///
+/// Runs in `O(V^3)` time, which makes it a good fit for dense graphs such as
+/// [`UndirectedGraph`](crate::UndirectedGraph).
+///
+/// Returns [`NegativeCycle`] if the graph contains a cycle of negative total weight.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to compute the shortest paths of.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph, WeightedGraph};
+/// use istos::shortest_paths::floyd_warshall;
+///
+/// let mut graph: UndirectedGraph<(), u32> = UndirectedGraph::new();
+/// let v1 = graph.add_vertex(());
+/// let v2 = graph.add_vertex(());
+/// let v3 = graph.add_vertex(());
+///
+/// graph.set_edge_weight(v1, v2, 1);
+/// graph.set_edge_weight(v2, v3, 2);
+/// graph.set_edge_weight(v1, v3, 5);
+///
+/// let paths = floyd_warshall(&graph).unwrap();
+///
+/// assert_eq!(paths.distance(v1, v3), Some(3));
+/// assert_eq!(paths.path(v1, v3), Some(vec![v1, v2, v3]));
+/// ```
+pub fn floyd_warshall<T, W, G>(graph: &G) -> Result<AllPairsShortestPaths<W>, NegativeCycle>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let n = index.len();
+
+	let mut distances: Vec<Vec<Option<W>>> = vec![vec![None; n]; n];
+	let mut next_hops: Vec<Vec<Option<usize>>> = vec![vec![None; n]; n];
+
+	// Every vertex is at distance zero from itself
+	for (i, row) in distances.iter_mut().enumerate() {
+		row[i] = Some(W::zero());
+	}
+
+	// Start with the direct edges
+	for u in 0..n {
+		for (&v, &w) in index.adj[u].iter().zip(&weights[u]) {
+			if distances[u][v].is_none_or(|d| w < d) {
+				distances[u][v] = Some(w);
+				next_hops[u][v] = if u == v { None } else { Some(v) };
+			}
+		}
+	}
+
+	// Allow paths through each vertex in turn
+	for k in 0..n {
+		for i in 0..n {
+			let Some(d_ik) = distances[i][k] else { continue; };
+			for j in 0..n {
+				let Some(d_kj) = distances[k][j] else { continue; };
+				let candidate = d_ik + d_kj;
+				if distances[i][j].is_none_or(|d| candidate < d) {
+					distances[i][j] = Some(candidate);
+					next_hops[i][j] = next_hops[i][k];
+				}
+			}
+		}
+	}
+
+	// A vertex with a negative distance to itself lies on a negative cycle
+	if (0..n).any(|i| distances[i][i].is_some_and(|d| d < W::zero())) {
+		return Err(NegativeCycle);
+	}
+
+	Ok(AllPairsShortestPaths::from_indexed(index, distances, next_hops))
+}
+
+/// Computes the shortest paths between all pairs of vertices using Johnson's algorithm.
+///
+/// The edges are first reweighted using potentials found by the Bellman–Ford algorithm, which makes
+/// all weights non-negative, after which Dijkstra's algorithm is run from every vertex. This takes
+/// `O(VE log V)` time, which is faster than [`floyd_warshall`] on sparse graphs such as
+/// [`UndirectedSparseGraph`](crate::UndirectedSparseGraph).
+///
+/// Returns [`NegativeCycle`] if the graph contains a cycle of negative total weight.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to compute the shortest paths of.
+pub fn johnson<T, W, G>(graph: &G) -> Result<AllPairsShortestPaths<W>, NegativeCycle>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let n = index.len();
+
+	// Find the potentials with Bellman-Ford from a virtual source connected to every vertex with a
+	// zero weight edge. A relaxation in the last round means there is a negative cycle.
+	let mut potentials = vec![W::zero(); n];
+	for round in 0..=n {
+		let mut changed = false;
+		for u in 0..n {
+			for (&v, &w) in index.adj[u].iter().zip(&weights[u]) {
+				if potentials[u] + w < potentials[v] {
+					potentials[v] = potentials[u] + w;
+					changed = true;
+				}
+			}
+		}
+
+		if !changed {
+			break;
+		}
+		if round == n {
+			return Err(NegativeCycle);
+		}
+	}
+
+	// The reweighted edges are all non-negative
+	let reweighted: Vec<Vec<W>> = (0..n)
+		.map(|u| index.adj[u].iter().zip(&weights[u]).map(|(&v, &w)| w + potentials[u] - potentials[v]).collect())
+		.collect();
+
+	let mut distances: Vec<Vec<Option<W>>> = Vec::with_capacity(n);
+	let mut next_hops: Vec<Vec<Option<usize>>> = Vec::with_capacity(n);
+
+	for source in 0..n {
+		// Run Dijkstra's algorithm on the reweighted edges
+		let mut dist: Vec<Option<W>> = vec![None; n];
+		let mut parent: Vec<Option<usize>> = vec![None; n];
+		let mut settled = vec![false; n];
+		let mut order: Vec<usize> = Vec::with_capacity(n);
+		let mut heap = BinaryHeap::new();
+
+		dist[source] = Some(W::zero());
+		heap.push(MinScored(W::zero(), source));
+
+		while let Some(MinScored(d, u)) = heap.pop() {
+			if settled[u] {
+				continue;
+			}
+			settled[u] = true;
+			order.push(u);
+
+			for (&v, &w) in index.adj[u].iter().zip(&reweighted[u]) {
+				let candidate = d + w;
+				if !settled[v] && dist[v].is_none_or(|dv| candidate < dv) {
+					dist[v] = Some(candidate);
+					parent[v] = Some(u);
+					heap.push(MinScored(candidate, v));
+				}
+			}
+		}
+
+		// The first hop towards a vertex is the first hop towards its parent, unless the parent is
+		// the source itself. Parents are settled first, so the settlement order works.
+		let mut hops: Vec<Option<usize>> = vec![None; n];
+		for &v in order.iter().skip(1) {
+			let p = parent[v].expect("settled vertices other than the source have a parent");
+			hops[v] = if p == source { Some(v) } else { hops[p] };
+		}
+
+		// Undo the reweighting
+		distances.push(dist.iter().enumerate().map(|(v, d)| d.map(|d| d + potentials[v] - potentials[source])).collect());
+		next_hops.push(hops);
+	}
+
+	Ok(AllPairsShortestPaths::from_indexed(index, distances, next_hops))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Graph, UndirectedGraph, UndirectedSparseGraph};
+
+	/// Builds a small weighted graph where the direct edge is longer than the detour.
+	fn build_graph<G: WeightedGraph<(), i32>>(mut graph: G) -> (G, Vec<usize>) {
+		let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+
+		graph.set_edge_weight(v[0], v[1], 4);
+		graph.set_edge_weight(v[0], v[2], 1);
+		graph.set_edge_weight(v[2], v[1], 2);
+		graph.set_edge_weight(v[1], v[3], 5);
+		graph.set_edge_weight(v[2], v[3], 8);
+		// v[4] is isolated
+
+		(graph, v)
+	}
+
+	fn check_paths(paths: &AllPairsShortestPaths<i32>, v: &[usize]) {
+		assert_eq!(paths.vertices(), v);
+		assert_eq!(paths.distance(v[0], v[0]), Some(0));
+		assert_eq!(paths.distance(v[0], v[1]), Some(3));
+		assert_eq!(paths.distance(v[1], v[0]), Some(3));
+		assert_eq!(paths.distance(v[0], v[3]), Some(8));
+		assert_eq!(paths.distance(v[2], v[3]), Some(7));
+		assert_eq!(paths.distance(v[0], v[4]), None);
+		assert_eq!(paths.distance(v[0], 999), None);
+
+		assert_eq!(paths.next_hop(v[0], v[1]), Some(v[2]));
+		assert_eq!(paths.next_hop(v[0], v[0]), None);
+		assert_eq!(paths.path(v[0], v[3]), Some(vec![v[0], v[2], v[1], v[3]]));
+		assert_eq!(paths.path(v[3], v[0]), Some(vec![v[3], v[1], v[2], v[0]]));
+		assert_eq!(paths.path(v[1], v[1]), Some(vec![v[1]]));
+		assert_eq!(paths.path(v[0], v[4]), None);
+	}
+
+	#[test]
+	fn test_floyd_warshall() {
+		let (graph, v) = build_graph(UndirectedGraph::new());
+		check_paths(&floyd_warshall(&graph).unwrap(), &v);
+
+		let (graph, v) = build_graph(UndirectedSparseGraph::new());
+		check_paths(&floyd_warshall(&graph).unwrap(), &v);
+	}
+
+	#[test]
+	fn test_johnson() {
+		let (graph, v) = build_graph(UndirectedGraph::new());
+		check_paths(&johnson(&graph).unwrap(), &v);
+
+		let (graph, v) = build_graph(UndirectedSparseGraph::new());
+		check_paths(&johnson(&graph).unwrap(), &v);
+	}
+
+	#[test]
+	fn test_matrices_use_vertex_ids() {
+		let (mut graph, v) = build_graph(UndirectedSparseGraph::new());
+		graph.remove_vertex(v[2]);
+
+		let paths = johnson(&graph).unwrap();
+
+		assert_eq!(paths.vertices(), &[v[0], v[1], v[3], v[4]]);
+		assert_eq!(paths.distances()[0][2], Some(9));
+		assert_eq!(paths.next_hops()[0][2], Some(v[1]));
+		assert_eq!(paths.path(v[0], v[3]), Some(vec![v[0], v[1], v[3]]));
+	}
+
+	#[test]
+	fn test_negative_cycle() {
+		let (mut graph, v) = build_graph(UndirectedSparseGraph::new());
+		graph.set_edge_weight(v[3], v[4], -1);
+
+		assert_eq!(floyd_warshall(&graph).unwrap_err(), NegativeCycle);
+		assert_eq!(johnson(&graph).unwrap_err(), NegativeCycle);
+	}
+
+	#[test]
+	fn test_float_weights() {
+		let mut graph: UndirectedSparseGraph<(), f64> = UndirectedSparseGraph::new();
+		let v1 = graph.add_vertex(());
+		let v2 = graph.add_vertex(());
+		let v3 = graph.add_vertex(());
+
+		graph.set_edge_weight(v1, v2, 0.5);
+		graph.set_edge_weight(v2, v3, 0.25);
+
+		assert_eq!(floyd_warshall(&graph).unwrap().distance(v1, v3), Some(0.75));
+		assert_eq!(johnson(&graph).unwrap().distance(v3, v1), Some(0.75));
+	}
+}
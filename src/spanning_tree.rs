@@ -0,0 +1,328 @@
+//! Minimum spanning trees.
+//!
+//! All algorithms in this module find a minimum spanning forest: on a connected graph this is a
+//! minimum spanning tree, and on a disconnected graph it consists of a minimum spanning tree for
+//! every connected component.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use super::util::{Indexed, MinScored};
+use super::{Graph, UndirectedSparseGraph, UnionFind, Weight, WeightedGraph};
+
+/// A minimum spanning forest of a graph.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpanningForest<W> {
+	/// The edges of the forest as pairs of vertex IDs with the weight of the edge.
+	pub edges: Vec<(usize, usize, W)>,
+	/// The sum of the weights of the edges.
+	pub total_weight: W,
+}
+
+impl<W: Weight> SpanningForest<W> {
+	/// Builds the forest from edges between vertex indices.
+	fn from_indexed(index: &Indexed, edges: Vec<(usize, usize, W)>) -> Self {
+		let total_weight = edges.iter().fold(W::zero(), |total, e| total + e.2);
+		Self {
+			edges: edges.into_iter().map(|(u, v, w)| (index.ids[u], index.ids[v], w)).collect(),
+			total_weight,
+		}
+	}
+
+	/// Materializes the forest as a graph.
+	///
+	/// Returns a new graph with all vertices of the original graph, carrying their original IDs and
+	/// data, connected by the weighted edges of the forest.
+	///
+	/// # Arguments
+	///
+	/// - `graph`: The graph the forest was computed for.
+	pub fn to_graph<T: Clone, G: Graph<T>>(&self, graph: &G) -> UndirectedSparseGraph<T, W> {
+		let vertices = graph.get_vertices().into_iter()
+			.filter_map(|id| Some((id, graph.get_vertex_data(id)?)))
+			.collect();
+
+		UndirectedSparseGraph::from_parts(vertices, self.edges.clone())
+	}
+}
+
+/// Collects every edge of an indexed graph once, as a pair of indices with the weight.
+fn indexed_edges<W: Weight>(index: &Indexed, weights: &[Vec<W>]) -> Vec<(usize, usize, W)> {
+	let mut edges = Vec::new();
+	for (u, neighbors) in index.adj.iter().enumerate() {
+		for (&v, &w) in neighbors.iter().zip(&weights[u]) {
+			if u < v {
+				edges.push((u, v, w));
+			}
+		}
+	}
+	edges
+}
+
+/// Compares two weights, treating incomparable weights as equal.
+#[inline]
+fn compare<W: Weight>(a: W, b: W) -> Ordering {
+	a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+/// Finds a minimum spanning forest using Kruskal's algorithm.
+///
+/// The edges are considered from lightest to heaviest, and every edge that connects two different
+/// trees is added. Runs in `O(E log E)` time. The edges of the result are in order of increasing
+/// weight.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find a minimum spanning forest of.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedSparseGraph, WeightedGraph};
+/// use istos::spanning_tree::kruskal;
+///
+/// let mut graph: UndirectedSparseGraph<&str, u32> = UndirectedSparseGraph::new();
+/// let a = graph.add_vertex("a");
+/// let b = graph.add_vertex("b");
+/// let c = graph.add_vertex("c");
+///
+/// graph.set_edge_weight(a, b, 1);
+/// graph.set_edge_weight(b, c, 2);
+/// graph.set_edge_weight(a, c, 3);
+///
+/// let forest = kruskal(&graph);
+/// assert_eq!(forest.edges, vec![(a, b, 1), (b, c, 2)]);
+/// assert_eq!(forest.total_weight, 3);
+///
+/// let tree = forest.to_graph(&graph);
+/// assert_eq!(tree.get_vertex_data(c), Some("c"));
+/// assert!(!tree.is_adjacent(a, c));
+/// ```
+pub fn kruskal<T, W, G>(graph: &G) -> SpanningForest<W>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+
+	// The sort is stable, so equal weights keep the order of the vertices
+	let mut edges = indexed_edges(&index, &weights);
+	edges.sort_by(|a, b| compare(a.2, b.2));
+
+	let mut sets = UnionFind::new(index.len());
+	let forest = edges.into_iter().filter(|&(u, v, _)| sets.union(u, v)).collect();
+
+	SpanningForest::from_indexed(&index, forest)
+}
+
+/// Finds a minimum spanning forest using Prim's algorithm.
+///
+/// Each tree is grown from a single vertex by repeatedly adding the lightest edge leaving it. Runs
+/// in `O(E log V)` time. The edges of the result are in the order they were added, and each edge
+/// goes from the vertex already in the tree to the new vertex.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find a minimum spanning forest of.
+pub fn prim<T, W, G>(graph: &G) -> SpanningForest<W>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let n = index.len();
+
+	let mut in_tree = vec![false; n];
+	let mut forest = Vec::new();
+
+	for root in 0..n {
+		if in_tree[root] {
+			continue;
+		}
+
+		// The heap holds candidate edges as (weight, (to, from))
+		let mut heap: BinaryHeap<MinScored<W, (usize, usize)>> = BinaryHeap::new();
+		in_tree[root] = true;
+		for (&v, &w) in index.adj[root].iter().zip(&weights[root]) {
+			heap.push(MinScored(w, (v, root)));
+		}
+
+		while let Some(MinScored(w, (v, u))) = heap.pop() {
+			if in_tree[v] {
+				continue;
+			}
+
+			in_tree[v] = true;
+			forest.push((u, v, w));
+			for (&x, &wx) in index.adj[v].iter().zip(&weights[v]) {
+				if !in_tree[x] {
+					heap.push(MinScored(wx, (x, v)));
+				}
+			}
+		}
+	}
+
+	SpanningForest::from_indexed(&index, forest)
+}
+
+/// Finds a minimum spanning forest using Borůvka's algorithm.
+///
+/// In every round, each tree picks the lightest edge leaving it, and all picked edges are added at
+/// once. The number of trees at least halves every round, so this takes `O(E log V)` time. The
+/// edges of the result are in the order they were added.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted graph to find a minimum spanning forest of.
+pub fn boruvka<T, W, G>(graph: &G) -> SpanningForest<W>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let n = index.len();
+	let edges = indexed_edges(&index, &weights);
+
+	let mut sets = UnionFind::new(n);
+	let mut forest = Vec::new();
+
+	loop {
+		// Find the lightest edge leaving every tree. Ties are broken by the position of the edge,
+		// which keeps the picked edges from forming a cycle.
+		let mut cheapest: Vec<Option<usize>> = vec![None; n];
+		for (i, &(u, v, w)) in edges.iter().enumerate() {
+			let (root_u, root_v) = (sets.find(u), sets.find(v));
+			if root_u == root_v {
+				continue;
+			}
+
+			for root in [root_u, root_v] {
+				let better = cheapest[root].is_none_or(|j| compare(w, edges[j].2) == Ordering::Less);
+				if better {
+					cheapest[root] = Some(i);
+				}
+			}
+		}
+
+		let mut added = false;
+		for i in cheapest.into_iter().flatten() {
+			let (u, v, w) = edges[i];
+			if sets.union(u, v) {
+				forest.push((u, v, w));
+				added = true;
+			}
+		}
+
+		if !added {
+			break;
+		}
+	}
+
+	SpanningForest::from_indexed(&index, forest)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::connectivity::connected_components;
+	use crate::UndirectedGraph;
+
+	/// Builds the graph from the Wikipedia article on Kruskal's algorithm, plus an extra component.
+	fn build_graph<G: WeightedGraph<char, u32>>(mut graph: G) -> (G, Vec<usize>) {
+		let v: Vec<usize> = "ABCDEFGHI".chars().map(|c| graph.add_vertex(c)).collect();
+
+		let edges = [
+			(0, 1, 7), (0, 3, 5), (1, 2, 8), (1, 3, 9), (1, 4, 7), (2, 4, 5), (3, 4, 15),
+			(3, 5, 6), (4, 5, 8), (4, 6, 9), (5, 6, 11), (7, 8, 4),
+		];
+		for (a, b, w) in edges {
+			graph.set_edge_weight(v[a], v[b], w);
+		}
+
+		(graph, v)
+	}
+
+	/// Checks that a forest spans every component of a graph with the expected weight.
+	fn check_forest<G: WeightedGraph<char, u32>>(graph: &G, forest: &SpanningForest<u32>) {
+		assert_eq!(forest.total_weight, 43);
+		assert_eq!(forest.edges.len(), 7);
+		assert_eq!(forest.edges.iter().map(|e| e.2).sum::<u32>(), 43);
+
+		for &(u, v, w) in &forest.edges {
+			assert_eq!(graph.get_edge_weight(u, v), Some(w));
+		}
+
+		let tree = forest.to_graph(graph);
+		assert_eq!(connected_components(&tree), connected_components(graph));
+	}
+
+	#[test]
+	fn test_kruskal() {
+		let (graph, _) = build_graph(UndirectedSparseGraph::new());
+		check_forest(&graph, &kruskal(&graph));
+
+		let (graph, _) = build_graph(UndirectedGraph::new());
+		check_forest(&graph, &kruskal(&graph));
+	}
+
+	#[test]
+	fn test_prim() {
+		let (graph, _) = build_graph(UndirectedSparseGraph::new());
+		check_forest(&graph, &prim(&graph));
+
+		let (graph, _) = build_graph(UndirectedGraph::new());
+		check_forest(&graph, &prim(&graph));
+	}
+
+	#[test]
+	fn test_boruvka() {
+		let (graph, _) = build_graph(UndirectedSparseGraph::new());
+		check_forest(&graph, &boruvka(&graph));
+
+		let (graph, _) = build_graph(UndirectedGraph::new());
+		check_forest(&graph, &boruvka(&graph));
+	}
+
+	#[test]
+	fn test_equal_weights() {
+		// Every spanning tree of a complete graph with equal weights is minimal
+		let mut graph: UndirectedGraph<(), i32> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..6).map(|_| graph.add_vertex(())).collect();
+		for i in 0..6 {
+			for j in i + 1..6 {
+				graph.set_edge_weight(v[i], v[j], 1);
+			}
+		}
+
+		for forest in [kruskal(&graph), prim(&graph), boruvka(&graph)] {
+			assert_eq!(forest.edges.len(), 5);
+			assert_eq!(forest.total_weight, 5);
+			assert_eq!(connected_components(&forest.to_graph(&graph)).len(), 1);
+		}
+	}
+
+	#[test]
+	fn test_to_graph() {
+		let (mut graph, v) = build_graph(UndirectedSparseGraph::new());
+		graph.remove_vertex(v[8]);
+
+		let tree = kruskal(&graph).to_graph(&graph);
+
+		assert_eq!(tree.get_vertices(), graph.get_vertices());
+		assert_eq!(tree.get_vertex_data(v[2]), Some('C'));
+		assert_eq!(tree.get_vertex_data(v[8]), None);
+		assert_eq!(tree.get_edge_weight(v[0], v[3]), Some(5));
+		assert_eq!(tree.get_edge_weight(v[1], v[3]), None);
+
+		// New vertices get IDs after the highest one in the tree, which can be the ID of a vertex
+		// removed from the original graph
+		let mut tree = tree;
+		assert_eq!(tree.add_vertex('J'), v[8]);
+	}
+}
@@ -0,0 +1,300 @@
+//! Strongly connected components of directed graphs.
+//!
+//! The functions in this module treat [`Graph::get_neighbors`] as returning the successors of a
+//! vertex, so they work on any directed implementation of [`Graph`]. On the undirected graphs in
+//! this crate every edge can be followed both ways, so the strongly connected components are the
+//! same as the [connected components](crate::connectivity::connected_components).
+
+use std::collections::{HashMap, HashSet};
+
+use super::util::Indexed;
+use super::Graph;
+
+/// Finds the strongly connected components of a graph using Tarjan's algorithm.
+///
+/// Returns a vector with the IDs of the vertices in each component. The components are in reverse
+/// topological order: no edge leads from a component to one after it. The vertices within each
+/// component are in the order they were added to the graph.
+///
+/// The search is iterative, so it handles deep graphs without overflowing the stack.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the strongly connected components of.
+pub fn tarjan_scc<T: Clone, G: Graph<T>>(graph: &G) -> Vec<Vec<usize>> {
+	let index = Indexed::new(graph);
+	tarjan_indexed(&index).into_iter()
+		.map(|component| component.into_iter().map(|u| index.ids[u]).collect())
+		.collect()
+}
+
+/// Tarjan's algorithm on an indexed graph, returning the components as sorted vertex indices.
+fn tarjan_indexed(index: &Indexed) -> Vec<Vec<usize>> {
+	let n = index.len();
+
+	let mut preorder: Vec<Option<usize>> = vec![None; n]; // The DFS preorder number of each vertex
+	let mut low: Vec<usize> = vec![0; n]; // The lowest preorder number reachable from each vertex
+	let mut on_stack = vec![false; n];
+	let mut time = 0;
+
+	let mut components = Vec::new();
+	let mut stack: Vec<usize> = Vec::new(); // The vertices not yet assigned to a component
+
+	for root in 0..n {
+		if preorder[root].is_some() {
+			continue;
+		}
+
+		let mut calls: Vec<(usize, usize)> = Vec::new(); // The vertex and its next neighbor to visit
+		preorder[root] = Some(time);
+		low[root] = time;
+		time += 1;
+		stack.push(root);
+		on_stack[root] = true;
+		calls.push((root, 0));
+
+		while let Some(&(u, next)) = calls.last() {
+			if let Some(&v) = index.adj[u].get(next) {
+				calls.last_mut().unwrap().1 += 1;
+
+				match preorder[v] {
+					None => {
+						preorder[v] = Some(time);
+						low[v] = time;
+						time += 1;
+						stack.push(v);
+						on_stack[v] = true;
+						calls.push((v, 0));
+					}
+					Some(p) if on_stack[v] => low[u] = low[u].min(p),
+					Some(_) => {}
+				}
+			} else {
+				calls.pop();
+
+				// u is the root of a component, which consists of everything above it on the stack
+				if Some(low[u]) == preorder[u] {
+					let start = stack.iter().rposition(|&x| x == u).unwrap();
+					let mut component = stack.split_off(start);
+					for &v in &component {
+						on_stack[v] = false;
+					}
+					component.sort_unstable();
+					components.push(component);
+				}
+
+				if let Some(&(parent, _)) = calls.last() {
+					low[parent] = low[parent].min(low[u]);
+				}
+			}
+		}
+	}
+
+	components
+}
+
+/// Finds the strongly connected components of a graph using Kosaraju's algorithm.
+///
+/// Returns a vector with the IDs of the vertices in each component. The components are in
+/// topological order: no edge leads from a component to one before it. The vertices within each
+/// component are in the order they were added to the graph.
+///
+/// The searches are iterative, so they handle deep graphs without overflowing the stack.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to find the strongly connected components of.
+pub fn kosaraju_scc<T: Clone, G: Graph<T>>(graph: &G) -> Vec<Vec<usize>> {
+	let index = Indexed::new(graph);
+	let n = index.len();
+
+	// Find the order in which a depth-first search finishes the vertices
+	let mut visited = vec![false; n];
+	let mut finished: Vec<usize> = Vec::with_capacity(n);
+	for root in 0..n {
+		if visited[root] {
+			continue;
+		}
+
+		visited[root] = true;
+		let mut calls: Vec<(usize, usize)> = vec![(root, 0)];
+		while let Some(&(u, next)) = calls.last() {
+			if let Some(&v) = index.adj[u].get(next) {
+				calls.last_mut().unwrap().1 += 1;
+				if !visited[v] {
+					visited[v] = true;
+					calls.push((v, 0));
+				}
+			} else {
+				calls.pop();
+				finished.push(u);
+			}
+		}
+	}
+
+	// Reverse the edges
+	let mut reversed: Vec<Vec<usize>> = vec![Vec::new(); n];
+	for (u, neighbors) in index.adj.iter().enumerate() {
+		for &v in neighbors {
+			reversed[v].push(u);
+		}
+	}
+
+	// Everything reachable in the reversed graph from the last finished vertex that isn't already
+	// taken forms a component
+	let mut assigned = vec![false; n];
+	let mut components = Vec::new();
+	for &root in finished.iter().rev() {
+		if assigned[root] {
+			continue;
+		}
+
+		assigned[root] = true;
+		let mut component = Vec::new();
+		let mut stack = vec![root];
+		while let Some(u) = stack.pop() {
+			component.push(u);
+			for &v in &reversed[u] {
+				if !assigned[v] {
+					assigned[v] = true;
+					stack.push(v);
+				}
+			}
+		}
+
+		component.sort_unstable();
+		components.push(component.into_iter().map(|u| index.ids[u]).collect());
+	}
+
+	components
+}
+
+/// The condensation of a graph, in which every strongly connected component is contracted to a
+/// single vertex.
+///
+/// The condensation is always acyclic. Components are identified by their position in
+/// [`components`](Self::components), which is a topological order of the condensation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Condensation {
+	/// The IDs of the vertices in each component, in the order they were added to the graph.
+	pub components: Vec<Vec<usize>>,
+	/// The edges between the components, without duplicates. Every edge leads from a component to
+	/// a later one.
+	pub edges: Vec<(usize, usize)>,
+	/// The component each vertex ID belongs to.
+	pub component_of: HashMap<usize, usize>,
+}
+
+/// Computes the condensation of a graph.
+///
+/// Every strongly connected component becomes a single vertex of the condensation, with an edge
+/// between two components if there is an edge between any of their vertices. See [`Condensation`]
+/// for how the result is represented.
+///
+/// # Arguments
+///
+/// - `graph`: The graph to condense.
+pub fn condensation<T: Clone, G: Graph<T>>(graph: &G) -> Condensation {
+	let index = Indexed::new(graph);
+
+	// Tarjan's algorithm finds the components in reverse topological order
+	let mut components = tarjan_indexed(&index);
+	components.reverse();
+
+	let mut component_of: Vec<usize> = vec![0; index.len()];
+	for (c, component) in components.iter().enumerate() {
+		for &u in component {
+			component_of[u] = c;
+		}
+	}
+
+	// Collect the edges between different components
+	let mut edges = Vec::new();
+	let mut seen = HashSet::new();
+	for (u, neighbors) in index.adj.iter().enumerate() {
+		for &v in neighbors {
+			let edge = (component_of[u], component_of[v]);
+			if edge.0 != edge.1 && seen.insert(edge) {
+				edges.push(edge);
+			}
+		}
+	}
+	edges.sort_unstable();
+
+	Condensation {
+		components: components.into_iter()
+			.map(|component| component.into_iter().map(|u| index.ids[u]).collect())
+			.collect(),
+		edges,
+		component_of: component_of.into_iter().enumerate().map(|(u, c)| (index.ids[u], c)).collect(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::TestDigraph;
+	use crate::UndirectedSparseGraph;
+
+	/// Builds a graph with the components {0, 1, 2}, {3, 4} and {5}, where {0, 1, 2} leads to the
+	/// other two and {3, 4} leads to {5}.
+	fn build_graph() -> TestDigraph {
+		TestDigraph::with_edges(6, &[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (4, 5), (1, 5)])
+	}
+
+	#[test]
+	fn test_tarjan_scc() {
+		assert_eq!(tarjan_scc(&build_graph()), vec![vec![5], vec![3, 4], vec![0, 1, 2]]);
+	}
+
+	#[test]
+	fn test_kosaraju_scc() {
+		assert_eq!(kosaraju_scc(&build_graph()), vec![vec![0, 1, 2], vec![3, 4], vec![5]]);
+	}
+
+	#[test]
+	fn test_acyclic() {
+		let graph = TestDigraph::with_edges(3, &[(0, 1), (1, 2), (0, 2)]);
+
+		assert_eq!(tarjan_scc(&graph), vec![vec![2], vec![1], vec![0]]);
+		assert_eq!(kosaraju_scc(&graph), vec![vec![0], vec![1], vec![2]]);
+	}
+
+	#[test]
+	fn test_undirected() {
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+		graph.add_edge(v[0], v[2]);
+		graph.add_edge(v[3], v[1]);
+
+		let mut components = tarjan_scc(&graph);
+		components.sort();
+		assert_eq!(components, vec![vec![v[0], v[2]], vec![v[1], v[3]]]);
+
+		let mut components = kosaraju_scc(&graph);
+		components.sort();
+		assert_eq!(components, vec![vec![v[0], v[2]], vec![v[1], v[3]]]);
+	}
+
+	#[test]
+	fn test_condensation() {
+		let condensation = condensation(&build_graph());
+
+		assert_eq!(condensation.components, vec![vec![0, 1, 2], vec![3, 4], vec![5]]);
+		assert_eq!(condensation.edges, vec![(0, 1), (0, 2), (1, 2)]);
+		assert_eq!(condensation.component_of[&1], 0);
+		assert_eq!(condensation.component_of[&4], 1);
+		assert_eq!(condensation.component_of[&5], 2);
+	}
+
+	#[test]
+	fn test_deep_graph() {
+		// A long cycle would overflow the stack with a recursive search
+		let n = 5_000;
+		let edges: Vec<(usize, usize)> = (0..n).map(|i| (i, (i + 1) % n)).collect();
+		let graph = TestDigraph::with_edges(n, &edges);
+
+		assert_eq!(tarjan_scc(&graph).len(), 1);
+		assert_eq!(kosaraju_scc(&graph).len(), 1);
+	}
+}
@@ -0,0 +1,311 @@
+//! Topological sorting and cycle detection.
+//!
+//! [`toposort`] and [`kahn`] treat [`Graph::get_neighbors`] as returning the successors of a vertex,
+//! like the functions in [`strongly_connected`](crate::strongly_connected). Since every undirected
+//! edge can be followed both ways, they report a cycle for any undirected graph with an edge; use
+//! [`is_cyclic_undirected`] for undirected graphs instead.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::error::Error;
+use std::fmt;
+
+use super::util::Indexed;
+use super::{Graph, UnionFind};
+
+/// The error returned when a graph that should be acyclic contains a cycle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+	/// The IDs of the vertices on the cycle, in order. There is an edge from each vertex to the next,
+	/// and from the last vertex back to the first.
+	pub path: Vec<usize>,
+}
+
+impl fmt::Display for Cycle {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "the graph contains a cycle through the vertices {:?}", self.path)
+	}
+}
+
+impl Error for Cycle {}
+
+/// Sorts an indexed graph topologically with a depth-first search, or finds a cycle.
+fn dfs_toposort(index: &Indexed) -> Result<Vec<usize>, Vec<usize>> {
+	#[derive(Clone, Copy, PartialEq, Eq)]
+	enum State {
+		Unvisited,
+		Active,
+		Finished,
+	}
+
+	let n = index.len();
+	let mut state = vec![State::Unvisited; n];
+	let mut order: Vec<usize> = Vec::with_capacity(n);
+
+	for root in 0..n {
+		if state[root] != State::Unvisited {
+			continue;
+		}
+
+		state[root] = State::Active;
+		let mut calls: Vec<(usize, usize)> = vec![(root, 0)]; // The vertex and its next neighbor to visit
+		while let Some(&(u, next)) = calls.last() {
+			if let Some(&v) = index.adj[u].get(next) {
+				calls.last_mut().unwrap().1 += 1;
+				match state[v] {
+					State::Unvisited => {
+						state[v] = State::Active;
+						calls.push((v, 0));
+					}
+					State::Active => {
+						// The active vertices from v up to u form a cycle, closed by the edge back to v
+						let start = calls.iter().position(|&(x, _)| x == v).unwrap();
+						return Err(calls[start..].iter().map(|&(x, _)| x).collect());
+					}
+					State::Finished => {}
+				}
+			} else {
+				calls.pop();
+				state[u] = State::Finished;
+				order.push(u);
+			}
+		}
+	}
+
+	// Vertices finish after all their successors
+	order.reverse();
+	Ok(order)
+}
+
+/// Sorts the vertices of a graph topologically.
+///
+/// Returns the IDs of the vertices ordered so that every edge leads from a vertex to a later one. If
+/// the graph contains a cycle, there is no such order and a [`Cycle`] is returned instead.
+///
+/// The depth-first search is iterative, so it handles deep graphs without overflowing the stack.
+///
+/// # Arguments
+///
+/// - `graph`: The directed graph to sort.
+pub fn toposort<T: Clone, G: Graph<T>>(graph: &G) -> Result<Vec<usize>, Cycle> {
+	let index = Indexed::new(graph);
+	match dfs_toposort(&index) {
+		Ok(order) => Ok(order.into_iter().map(|u| index.ids[u]).collect()),
+		Err(cycle) => Err(Cycle { path: cycle.into_iter().map(|u| index.ids[u]).collect() }),
+	}
+}
+
+/// Sorts the vertices of a graph topologically using Kahn's algorithm.
+///
+/// Kahn's algorithm repeatedly places a vertex without incoming edges from unplaced vertices. When
+/// several vertices are ready, the one with the smallest key according to `tie_break` goes first
+/// (with ties broken by the order the vertices were added to the graph), so the order is fully
+/// determined by the graph and the keys.
+///
+/// Returns the IDs of the vertices ordered so that every edge leads from a vertex to a later one. If
+/// the graph contains a cycle, there is no such order and a [`Cycle`] is returned instead.
+///
+/// # Arguments
+///
+/// - `graph`: The directed graph to sort.
+/// - `tie_break`: A function from vertex ID to the key that decides which ready vertex goes first.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedSparseGraph};
+/// use istos::toposort::kahn;
+///
+/// // Without edges, every vertex is ready from the start
+/// let mut graph: UndirectedSparseGraph<&str> = UndirectedSparseGraph::new();
+/// let b = graph.add_vertex("b");
+/// let c = graph.add_vertex("c");
+/// let a = graph.add_vertex("a");
+///
+/// let order = kahn(&graph, |v| graph.get_vertex_data(v)).unwrap();
+///
+/// assert_eq!(order, vec![a, b, c]);
+/// ```
+pub fn kahn<T, G, K, F>(graph: &G, mut tie_break: F) -> Result<Vec<usize>, Cycle>
+where
+	T: Clone,
+	G: Graph<T>,
+	K: Ord,
+	F: FnMut(usize) -> K,
+{
+	let index = Indexed::new(graph);
+	let n = index.len();
+
+	// Count the incoming edges of every vertex
+	let mut in_degree = vec![0; n];
+	for neighbors in &index.adj {
+		for &v in neighbors {
+			in_degree[v] += 1;
+		}
+	}
+
+	let mut ready: BinaryHeap<Reverse<(K, usize)>> = (0..n)
+		.filter(|&u| in_degree[u] == 0)
+		.map(|u| Reverse((tie_break(index.ids[u]), u)))
+		.collect();
+
+	let mut order: Vec<usize> = Vec::with_capacity(n);
+	while let Some(Reverse((_, u))) = ready.pop() {
+		order.push(index.ids[u]);
+		for &v in &index.adj[u] {
+			in_degree[v] -= 1;
+			if in_degree[v] == 0 {
+				ready.push(Reverse((tie_break(index.ids[v]), v)));
+			}
+		}
+	}
+
+	// The vertices that never became ready lie on or behind a cycle
+	if order.len() < n {
+		let cycle = dfs_toposort(&index).expect_err("a graph Kahn's algorithm can't sort has a cycle");
+		return Err(Cycle { path: cycle.into_iter().map(|u| index.ids[u]).collect() });
+	}
+
+	Ok(order)
+}
+
+/// Determines whether an undirected graph contains a cycle.
+///
+/// Self loops count as cycles.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to test.
+pub fn is_cyclic_undirected<T: Clone, G: Graph<T>>(graph: &G) -> bool {
+	let index = Indexed::new(graph);
+
+	// An edge between two vertices that are already connected closes a cycle
+	let mut sets = UnionFind::new(index.len());
+	for (u, neighbors) in index.adj.iter().enumerate() {
+		for &v in neighbors.iter().filter(|&&v| v >= u) {
+			if !sets.union(u, v) {
+				return true;
+			}
+		}
+	}
+
+	false
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::util::TestDigraph;
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+
+	/// Checks that an order is a topological order of a graph.
+	fn check_order(graph: &TestDigraph, order: &[usize]) {
+		let mut position = vec![usize::MAX; order.len()];
+		for (i, &v) in order.iter().enumerate() {
+			position[v] = i;
+		}
+
+		assert!(position.iter().all(|&p| p != usize::MAX));
+		for u in graph.get_vertices() {
+			for v in graph.get_neighbors(u) {
+				assert!(position[u] < position[v]);
+			}
+		}
+	}
+
+	/// Checks that a path is a cycle of a graph.
+	fn check_cycle(graph: &TestDigraph, cycle: &Cycle) {
+		assert!(!cycle.path.is_empty());
+		for i in 0..cycle.path.len() {
+			assert!(graph.is_adjacent(cycle.path[i], cycle.path[(i + 1) % cycle.path.len()]));
+		}
+	}
+
+	#[test]
+	fn test_toposort() {
+		let graph = TestDigraph::with_edges(6, &[(5, 2), (5, 0), (4, 0), (4, 1), (2, 3), (3, 1)]);
+		check_order(&graph, &toposort(&graph).unwrap());
+	}
+
+	#[test]
+	fn test_toposort_cycle() {
+		let graph = TestDigraph::with_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)]);
+		let cycle = toposort(&graph).unwrap_err();
+
+		check_cycle(&graph, &cycle);
+		assert_eq!(cycle.path, vec![1, 2, 3]);
+
+		let graph = TestDigraph::with_edges(2, &[(0, 1), (1, 1)]);
+		assert_eq!(toposort(&graph).unwrap_err().path, vec![1]);
+	}
+
+	#[test]
+	fn test_kahn() {
+		let graph = TestDigraph::with_edges(6, &[(5, 2), (5, 0), (4, 0), (4, 1), (2, 3), (3, 1)]);
+
+		// Smallest ID first
+		let order = kahn(&graph, |v| v).unwrap();
+		check_order(&graph, &order);
+		assert_eq!(order, vec![4, 5, 0, 2, 3, 1]);
+
+		// Largest ID first
+		let order = kahn(&graph, Reverse).unwrap();
+		check_order(&graph, &order);
+		assert_eq!(order, vec![5, 4, 2, 3, 1, 0]);
+
+		// Equal keys fall back to the insertion order
+		assert_eq!(kahn(&graph, |_| ()).unwrap(), vec![4, 5, 0, 2, 3, 1]);
+	}
+
+	#[test]
+	fn test_kahn_cycle() {
+		let graph = TestDigraph::with_edges(4, &[(0, 1), (1, 2), (2, 0), (3, 0)]);
+		let cycle = kahn(&graph, |v| v).unwrap_err();
+
+		check_cycle(&graph, &cycle);
+		assert_eq!(cycle.path.len(), 3);
+	}
+
+	#[test]
+	fn test_undirected_edges_are_cycles() {
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v1 = graph.add_vertex(());
+		let v2 = graph.add_vertex(());
+
+		assert!(toposort(&graph).is_ok());
+
+		graph.add_edge(v1, v2);
+		assert_eq!(toposort(&graph).unwrap_err().path, vec![v1, v2]);
+	}
+
+	#[test]
+	fn test_is_cyclic_undirected() {
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+
+		graph.add_edge(v[0], v[1]);
+		graph.add_edge(v[1], v[2]);
+		graph.add_edge(v[1], v[3]);
+		assert!(!is_cyclic_undirected(&graph));
+
+		graph.add_edge(v[3], v[2]);
+		assert!(is_cyclic_undirected(&graph));
+
+		graph.remove_edge(v[3], v[2]);
+		graph.add_edge(v[3], v[3]);
+		assert!(is_cyclic_undirected(&graph));
+
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..3).map(|_| graph.add_vertex(())).collect();
+		graph.add_edge(v[0], v[1]);
+		graph.add_edge(v[2], v[1]);
+		assert!(!is_cyclic_undirected(&graph));
+
+		// A parallel edge is not a cycle
+		graph.add_edge(v[1], v[0]);
+		assert!(!is_cyclic_undirected(&graph));
+
+		graph.add_edge(v[0], v[2]);
+		assert!(is_cyclic_undirected(&graph));
+	}
+}
@@ -0,0 +1,768 @@
+//! Hamiltonian paths and cycles, and the traveling salesman problem.
+//!
+//! The exact searches use the dynamic programming of Bellman and of Held and Karp over all subsets
+//! of the vertices, so they take `O(2^V V^2)` time and `O(2^V V)` memory and are only suitable for
+//! graphs of up to around 20 vertices. The heuristics scale to large graphs, and work on the
+//! shortest path distances between the vertices, so that a tour may pass through vertices that
+//! aren't adjacent: each step of a tour goes along a shortest path to the next vertex.
+
+use super::euler::hierholzer;
+use super::matching::max_weight_mates;
+use super::util::{dijkstra, Indexed};
+use super::{Graph, Weight, WeightedGraph};
+
+/// The most vertices the exact searches accept. At this size, the cost and parent tables of
+/// [`held_karp`] have `23 * 2^23` entries each, about 1.7 GB in all with 64-bit weights, and the
+/// Hamiltonian searches use `2^24` words. Every further vertex more than doubles that.
+const MAX_EXACT_VERTICES: usize = 24;
+
+/// A round trip through all vertices of a graph, with its cost.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tour<W> {
+	/// The IDs of the vertices in the order they are visited. Every vertex appears once, and the
+	/// tour returns from the last vertex to the first.
+	pub vertices: Vec<usize>,
+	/// The total weight of the steps of the tour, including the step back to the first vertex.
+	pub cost: W,
+}
+
+/// Builds the adjacency of a graph as bit sets, without self loops.
+fn adjacency_bits(index: &Indexed) -> Vec<u64> {
+	index.adj.iter().enumerate()
+		.map(|(u, neighbors)| neighbors.iter().filter(|&&v| v != u).fold(0, |bits, &v| bits | 1 << v))
+		.collect()
+}
+
+/// Finds, for every subset of the vertices, the vertices at which a path through exactly that
+/// subset can end, when the paths start at `start` or, if it is `None`, anywhere.
+fn path_ends(adj: &[u64], start: Option<usize>) -> Vec<u64> {
+	let n = adj.len();
+	let mut ends = vec![0u64; 1 << n];
+	match start {
+		Some(s) => ends[1 << s] = 1 << s,
+		None => (0..n).for_each(|v| ends[1 << v] = 1 << v),
+	}
+
+	for mask in 1..ends.len() {
+		let mut last = ends[mask];
+		while last != 0 {
+			let j = last.trailing_zeros() as usize;
+			last &= last - 1;
+
+			let mut next = adj[j] & !(mask as u64);
+			while next != 0 {
+				let k = next.trailing_zeros() as usize;
+				next &= next - 1;
+				ends[mask | 1 << k] |= 1 << k;
+			}
+		}
+	}
+
+	ends
+}
+
+/// Walks back from the end of a path through all vertices to its start.
+fn trace_path(adj: &[u64], ends: &[u64], mut last: usize) -> Vec<usize> {
+	let mut mask = ends.len() - 1;
+	let mut path = vec![last];
+	while mask.count_ones() > 1 {
+		mask ^= 1 << last;
+		last = (ends[mask] & adj[last]).trailing_zeros() as usize;
+		path.push(last);
+	}
+	path.reverse();
+	path
+}
+
+/// Finds a Hamiltonian path of a graph: a path that visits every vertex exactly once.
+///
+/// Returns the IDs of the vertices in the order the path visits them, or `None` if there is no
+/// such path. The path of a graph without vertices is empty.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to find a path in.
+///
+/// # Panics
+///
+/// Panics if the graph has more than 24 vertices.
+pub fn hamiltonian_path<T: Clone, G: Graph<T>>(graph: &G) -> Option<Vec<usize>> {
+	let index = Indexed::new(graph);
+	let n = index.len();
+	assert!(n <= MAX_EXACT_VERTICES, "too many vertices for an exact search");
+	if n == 0 {
+		return Some(Vec::new());
+	}
+
+	let adj = adjacency_bits(&index);
+	let ends = path_ends(&adj, None);
+	let last = ends[(1 << n) - 1];
+	if last == 0 {
+		return None;
+	}
+
+	let path = trace_path(&adj, &ends, last.trailing_zeros() as usize);
+	Some(path.into_iter().map(|u| index.ids[u]).collect())
+}
+
+/// Finds a Hamiltonian cycle of a graph: a cycle that visits every vertex exactly once.
+///
+/// Returns the IDs of the vertices in the order the cycle visits them, starting from the earliest
+/// added vertex, or `None` if there is no such cycle. The cycle returns from the last vertex to the
+/// first. Graphs with fewer than three vertices have no cycles.
+///
+/// # Arguments
+///
+/// - `graph`: The undirected graph to find a cycle in.
+///
+/// # Panics
+///
+/// Panics if the graph has more than 24 vertices.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph};
+/// use istos::tsp::hamiltonian_cycle;
+///
+/// // A square with a pendant vertex has no Hamiltonian cycle until the pendant closes a triangle
+/// let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+/// for (a, b) in [(0, 1), (1, 2), (2, 3), (3, 0), (3, 4)] {
+///     graph.add_edge(v[a], v[b]);
+/// }
+/// assert_eq!(hamiltonian_cycle(&graph), None);
+///
+/// graph.add_edge(v[4], v[0]);
+/// assert_eq!(hamiltonian_cycle(&graph), Some(vec![v[0], v[1], v[2], v[3], v[4]]));
+/// ```
+pub fn hamiltonian_cycle<T: Clone, G: Graph<T>>(graph: &G) -> Option<Vec<usize>> {
+	let index = Indexed::new(graph);
+	let n = index.len();
+	assert!(n <= MAX_EXACT_VERTICES, "too many vertices for an exact search");
+	if n < 3 {
+		return None;
+	}
+
+	// A cycle can start anywhere, so start at the first vertex and end next to it
+	let adj = adjacency_bits(&index);
+	let ends = path_ends(&adj, Some(0));
+	let last = ends[(1 << n) - 1] & adj[0];
+	if last == 0 {
+		return None;
+	}
+
+	// End at the latest neighbor of the start, so that a cycle in the order of the vertices is found
+	let cycle = trace_path(&adj, &ends, 63 - last.leading_zeros() as usize);
+	Some(cycle.into_iter().map(|u| index.ids[u]).collect())
+}
+
+/// Finds a shortest Hamiltonian cycle of a weighted graph using the Held–Karp algorithm: an optimal
+/// tour that only uses the edges of the graph.
+///
+/// Returns `None` if the graph has no Hamiltonian cycle, which includes graphs with fewer than three
+/// vertices. The tour starts from the earliest added vertex.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted undirected graph to find a tour of.
+///
+/// # Panics
+///
+/// Panics if the graph has more than 24 vertices.
+pub fn held_karp<T, W, G>(graph: &G) -> Option<Tour<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let weights = index.weights(graph);
+	let n = index.len();
+	assert!(n <= MAX_EXACT_VERTICES, "too many vertices for an exact search");
+	if n < 3 {
+		return None;
+	}
+
+	let mut matrix: Vec<Vec<Option<W>>> = vec![vec![None; n]; n];
+	for u in 0..n {
+		for (&v, &w) in index.adj[u].iter().zip(&weights[u]) {
+			if u != v {
+				matrix[u][v] = Some(w);
+			}
+		}
+	}
+
+	// The paths start at vertex 0 and go through a subset of the other vertices, whose bit `k`
+	// stands for vertex `k + 1`. Entry `mask * m + k` is for the paths that end at vertex `k + 1`.
+	let m = n - 1;
+	let start = m as u8; // The parent of the vertices right after the start
+	let unreached = u8::MAX;
+	let mut cost: Vec<W> = vec![W::zero(); m << m];
+	let mut parent: Vec<u8> = vec![unreached; m << m];
+
+	for k in 0..m {
+		if let Some(w) = matrix[0][k + 1] {
+			cost[(1 << k) * m + k] = w;
+			parent[(1 << k) * m + k] = start;
+		}
+	}
+
+	for mask in 1..1usize << m {
+		for j in (0..m).filter(|&j| mask & 1 << j != 0) {
+			if parent[mask * m + j] == unreached {
+				continue;
+			}
+			let c = cost[mask * m + j];
+			for k in (0..m).filter(|&k| mask & 1 << k == 0) {
+				let Some(w) = matrix[j + 1][k + 1] else { continue };
+				let entry = (mask | 1 << k) * m + k;
+				if parent[entry] == unreached || c + w < cost[entry] {
+					cost[entry] = c + w;
+					parent[entry] = j as u8;
+				}
+			}
+		}
+	}
+
+	// Close the cheapest cycle
+	let full = (1 << m) - 1;
+	let mut best: Option<(W, usize)> = None;
+	for j in (0..m).filter(|&j| parent[full * m + j] != unreached) {
+		if let Some(w) = matrix[j + 1][0] {
+			let total = cost[full * m + j] + w;
+			if best.is_none_or(|(b, _)| total < b) {
+				best = Some((total, j));
+			}
+		}
+	}
+	let (total, mut j) = best?;
+
+	let mut vertices = Vec::with_capacity(n);
+	let mut mask = full;
+	while parent[mask * m + j] != start {
+		vertices.push(index.ids[j + 1]);
+		let previous = parent[mask * m + j] as usize;
+		mask ^= 1 << j;
+		j = previous;
+	}
+	vertices.push(index.ids[j + 1]);
+	vertices.push(index.ids[0]);
+	vertices.reverse();
+
+	Some(Tour { vertices, cost: total })
+}
+
+/// Finds the shortest path distance between every pair of vertices, or `None` if some pair is not
+/// connected.
+fn metric_closure<W: Weight>(index: &Indexed, weights: &[Vec<W>]) -> Option<Vec<Vec<W>>> {
+	(0..index.len())
+		.map(|source| dijkstra(&index.adj, weights, source).0.into_iter().collect())
+		.collect()
+}
+
+/// Computes the cost of visiting the vertices in order and returning to the first.
+fn tour_cost<W: Weight>(dist: &[Vec<W>], order: &[usize]) -> W {
+	(0..order.len()).fold(W::zero(), |cost, i| cost + dist[order[i]][order[(i + 1) % order.len()]])
+}
+
+/// Maps a tour of indices back to vertex IDs.
+fn to_tour<W: Weight>(index: &Indexed, dist: &[Vec<W>], order: Vec<usize>) -> Tour<W> {
+	Tour { cost: tour_cost(dist, &order), vertices: order.into_iter().map(|u| index.ids[u]).collect() }
+}
+
+/// Builds a tour with the nearest neighbor heuristic: starting from the earliest added vertex, the
+/// tour always goes on to the closest vertex it hasn't visited yet.
+///
+/// Runs in `O(V^2)` time after computing the distances between all vertices. The tour is usually
+/// around a quarter longer than an optimal one, but can be much worse; [`improve_tour`] makes it
+/// locally optimal. Edge weights must not be negative.
+///
+/// Returns `None` if the graph has no vertices or is not connected.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted undirected graph to find a tour of.
+pub fn nearest_neighbor_tour<T, W, G>(graph: &G) -> Option<Tour<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let dist = metric_closure(&index, &index.weights(graph))?;
+	let n = index.len();
+	if n == 0 {
+		return None;
+	}
+
+	let mut visited = vec![false; n];
+	let mut order = vec![0];
+	visited[0] = true;
+	for _ in 1..n {
+		let last = *order.last().unwrap();
+		let mut next: Option<usize> = None;
+		for v in (0..n).filter(|&v| !visited[v]) {
+			if next.is_none_or(|x| dist[last][v] < dist[last][x]) {
+				next = Some(v);
+			}
+		}
+		let next = next.unwrap();
+		visited[next] = true;
+		order.push(next);
+	}
+
+	Some(to_tour(&index, &dist, order))
+}
+
+/// Builds a tour with the Christofides algorithm.
+///
+/// The tour follows a minimum spanning tree, doubled up along a minimum weight perfect matching
+/// between the vertices of odd degree in the tree so that it has an Eulerian circuit, and skips the
+/// vertices the circuit visits again. Since the distances along shortest paths satisfy the
+/// triangle inequality, the tour is at most one and a half times as long as an optimal one. Runs in
+/// `O(V^3)` time. Edge weights must not be negative.
+///
+/// Returns `None` if the graph has no vertices or is not connected. The tour starts from the
+/// earliest added vertex.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted undirected graph to find a tour of.
+///
+/// # Panics
+///
+/// Panics if four times the largest distance between two vertices overflows the weight type, which
+/// the matching needs room for.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph, WeightedGraph};
+/// use istos::tsp::christofides;
+///
+/// // The corners of a 3 by 4 rectangle, with the diagonals 5 long
+/// let mut graph: UndirectedGraph<(), u32> = UndirectedGraph::new();
+/// let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+/// for (a, b, w) in [(0, 1, 3), (1, 2, 4), (2, 3, 3), (3, 0, 4), (0, 2, 5), (1, 3, 5)] {
+///     graph.set_edge_weight(v[a], v[b], w);
+/// }
+///
+/// let tour = christofides(&graph).unwrap();
+///
+/// assert_eq!(tour.cost, 14);
+/// assert_eq!(tour.vertices.len(), 4);
+/// ```
+pub fn christofides<T, W, G>(graph: &G) -> Option<Tour<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let dist = metric_closure(&index, &index.weights(graph))?;
+	let n = index.len();
+	if n == 0 {
+		return None;
+	}
+
+	// Find a minimum spanning tree of the distances with Prim's algorithm
+	let mut edges: Vec<(usize, usize)> = Vec::with_capacity(n);
+	let mut in_tree = vec![false; n];
+	let mut closest: Vec<(W, usize)> = (0..n).map(|v| (dist[0][v], 0)).collect();
+	in_tree[0] = true;
+	for _ in 1..n {
+		let v = (0..n).filter(|&v| !in_tree[v])
+			.reduce(|a, b| if closest[b].0 < closest[a].0 { b } else { a })
+			.unwrap();
+		in_tree[v] = true;
+		edges.push((closest[v].1, v));
+		for x in 0..n {
+			if !in_tree[x] && dist[v][x] < closest[x].0 {
+				closest[x] = (dist[v][x], v);
+			}
+		}
+	}
+
+	// Pair up the vertices of odd degree in the tree with a maximum weight matching on the distances
+	// subtracted from a weight larger than any of them. Every pair gets a positive weight, so two
+	// unmatched vertices could always be matched to each other, which makes the matching perfect
+	let mut odd_degree = vec![false; n];
+	for &(u, v) in &edges {
+		odd_degree[u] = !odd_degree[u];
+		odd_degree[v] = !odd_degree[v];
+	}
+	let odd: Vec<usize> = (0..n).filter(|&u| odd_degree[u]).collect();
+	let farthest = odd.iter()
+		.flat_map(|&u| odd.iter().map(move |&v| (u, v)))
+		.map(|(u, v)| dist[u][v])
+		.fold(W::zero(), |max, d| if d > max { d } else { max });
+	let bound = farthest + W::one();
+	let mut pairs = Vec::new();
+	for (i, &u) in odd.iter().enumerate() {
+		for (j, &v) in odd.iter().enumerate().skip(i + 1) {
+			pairs.push((i, j, bound - dist[u][v]));
+		}
+	}
+	for (i, mate) in max_weight_mates(odd.len(), pairs).into_iter().enumerate() {
+		let j = mate.expect("the matching is perfect");
+		if i < j {
+			edges.push((odd[i], odd[j]));
+		}
+	}
+
+	// Walk the Eulerian circuit, skipping the vertices visited already
+	let mut order = vec![0];
+	if !edges.is_empty() {
+		let circuit = hierholzer(n, &edges, 0).expect("the tree connects all vertices");
+		let mut visited = vec![false; n];
+		order = circuit.into_iter().filter(|&u| !std::mem::replace(&mut visited[u], true)).collect();
+	}
+
+	Some(to_tour(&index, &dist, order))
+}
+
+/// Improves a tour with 2-opt moves: reverses a stretch of the tour whenever that makes it shorter.
+/// Returns whether the tour changed.
+fn two_opt<W: Weight>(dist: &[Vec<W>], order: &mut [usize]) -> bool {
+	let n = order.len();
+	let mut improved = false;
+	loop {
+		let mut changed = false;
+		for i in 0..n.saturating_sub(2) {
+			for j in i + 2..n {
+				if i == 0 && j == n - 1 {
+					continue;
+				}
+
+				// Replace the steps a–b and c–d with a–c and b–d
+				let (a, b, c, d) = (order[i], order[i + 1], order[j], order[(j + 1) % n]);
+				if dist[a][c] + dist[b][d] < dist[a][b] + dist[c][d] {
+					order[i + 1..=j].reverse();
+					changed = true;
+				}
+			}
+		}
+
+		if !changed {
+			return improved;
+		}
+		improved = true;
+	}
+}
+
+/// Improves a tour with Or-opt moves: moves a stretch of up to three vertices, possibly reversed,
+/// to another place in the tour whenever that makes it shorter. Returns whether the tour changed.
+fn or_opt<W: Weight>(dist: &[Vec<W>], order: &mut Vec<usize>) -> bool {
+	let n = order.len();
+	let mut improved = false;
+
+	'search: loop {
+		for length in 1..=3.min(n.saturating_sub(2)) {
+			for i in 0..=n - length {
+				let (first, last) = (order[i], order[i + length - 1]);
+				let (p, q) = (order[(i + n - 1) % n], order[(i + length) % n]);
+
+				// The rest of the tour, from the vertex after the stretch around to the one before
+				let rest: Vec<usize> = order[i + length..].iter().chain(&order[..i]).copied().collect();
+				for k in 0..rest.len() - 1 {
+					let (a, b) = (rest[k], rest[k + 1]);
+					let removed = dist[p][first] + dist[last][q] + dist[a][b];
+					let forward = dist[a][first] + dist[last][b] + dist[p][q];
+					let backward = dist[a][last] + dist[first][b] + dist[p][q];
+
+					let reverse = if forward < removed {
+						false
+					} else if backward < removed {
+						true
+					} else {
+						continue;
+					};
+
+					let mut stretch = order[i..i + length].to_vec();
+					if reverse {
+						stretch.reverse();
+					}
+					*order = rest[..=k].iter().chain(&stretch).chain(&rest[k + 1..]).copied().collect();
+					improved = true;
+					continue 'search;
+				}
+			}
+		}
+
+		return improved;
+	}
+}
+
+/// Improves a tour by local search until it is locally optimal, alternating 2-opt moves, which
+/// reverse a stretch of the tour, and Or-opt moves, which move a stretch of up to three vertices to
+/// another place in the tour.
+///
+/// This is typically used on a tour from [`nearest_neighbor_tour`] or [`christofides`], and usually
+/// brings it within a few percent of an optimal tour. Each round of moves takes `O(V^2)` time. Edge
+/// weights must not be negative.
+///
+/// Returns `None` if the tour doesn't visit every vertex of the graph exactly once, or if the graph
+/// is not connected. The improved tour starts from the same vertex.
+///
+/// # Arguments
+///
+/// - `graph`: The weighted undirected graph the tour is in.
+/// - `tour`: The IDs of the vertices in the order the tour visits them.
+pub fn improve_tour<T, W, G>(graph: &G, tour: &[usize]) -> Option<Tour<W>>
+where
+	T: Clone,
+	W: Weight,
+	G: WeightedGraph<T, W>,
+{
+	let index = Indexed::new(graph);
+	let n = index.len();
+	let mut order: Vec<usize> = tour.iter().map(|id| index.index.get(id).copied()).collect::<Option<_>>()?;
+	let mut seen = vec![false; n];
+	if order.len() != n || order.iter().any(|&u| std::mem::replace(&mut seen[u], true)) {
+		return None;
+	}
+	let dist = metric_closure(&index, &index.weights(graph))?;
+
+	two_opt(&dist, &mut order);
+	while or_opt(&dist, &mut order) && two_opt(&dist, &mut order) {}
+
+	// Start from the same vertex again
+	if let Some(start) = order.iter().position(|&u| Some(&index.ids[u]) == tour.first()) {
+		order.rotate_left(start);
+	}
+
+	Some(to_tour(&index, &dist, order))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{UndirectedGraph, UndirectedSparseGraph};
+	use crate::util::{random_graph, Rng};
+
+	/// Generates random points in a square, and a complete graph with the distances between them
+	/// rounded to integers.
+	fn random_points(n: usize, seed: u64) -> UndirectedSparseGraph<(), u64> {
+		let mut rng = Rng::new(seed);
+		let points: Vec<(f64, f64)> = (0..n).map(|_| (rng.below(1000) as f64, rng.below(1000) as f64)).collect();
+
+		let mut graph = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..n).map(|_| graph.add_vertex(())).collect();
+		for a in 0..n {
+			for b in a + 1..n {
+				let (dx, dy) = (points[a].0 - points[b].0, points[a].1 - points[b].1);
+				graph.set_edge_weight(v[a], v[b], (dx * dx + dy * dy).sqrt().round() as u64);
+			}
+		}
+		graph
+	}
+
+	/// Finds the cheapest Hamiltonian cycle by trying every order of the vertices after the first.
+	fn brute_force(graph: &UndirectedSparseGraph<(), u64>) -> Option<u64> {
+		fn extend(graph: &UndirectedSparseGraph<(), u64>, path: &mut Vec<usize>, rest: &mut Vec<usize>, cost: u64, best: &mut Option<u64>) {
+			let last = *path.last().unwrap();
+			if rest.is_empty() {
+				if let Some(w) = graph.get_edge_weight(last, path[0]) {
+					if best.is_none_or(|b| cost + w < b) {
+						*best = Some(cost + w);
+					}
+				}
+				return;
+			}
+			for i in 0..rest.len() {
+				let v = rest.swap_remove(i);
+				if let Some(w) = graph.get_edge_weight(last, v) {
+					path.push(v);
+					extend(graph, path, rest, cost + w, best);
+					path.pop();
+				}
+				rest.push(v);
+				let end = rest.len() - 1;
+				rest.swap(i, end);
+			}
+		}
+
+		let v = graph.get_vertices();
+		let mut best = None;
+		extend(graph, &mut vec![v[0]], &mut v[1..].to_vec(), 0, &mut best);
+		best
+	}
+
+	/// Checks that a tour visits every vertex once and that its cost is right.
+	fn check_tour(graph: &UndirectedSparseGraph<(), u64>, tour: &Tour<u64>) {
+		let mut vertices = tour.vertices.clone();
+		vertices.sort_unstable();
+		assert_eq!(vertices, graph.get_vertices());
+
+		let n = tour.vertices.len();
+		let cost: u64 = (0..n).map(|i| graph.get_edge_weight(tour.vertices[i], tour.vertices[(i + 1) % n]).unwrap()).sum();
+		assert_eq!(cost, tour.cost);
+	}
+
+	#[test]
+	fn test_hamiltonian_path_and_cycle() {
+		for seed in 1..40 {
+			let graph = random_graph(8, 30, seed);
+			let v = graph.get_vertices();
+
+			// Compare with the cheapest tour when every edge costs one
+			let mut weighted: UndirectedSparseGraph<(), u64> = UndirectedSparseGraph::new();
+			for _ in 0..8 {
+				weighted.add_vertex(());
+			}
+			for &a in &v {
+				for b in graph.get_neighbors(a) {
+					weighted.set_edge_weight(a, b, 1);
+				}
+			}
+			let has_cycle = brute_force(&weighted).is_some();
+
+			match hamiltonian_cycle(&graph) {
+				Some(cycle) => {
+					assert!(has_cycle);
+					assert_eq!(cycle[0], v[0]);
+					assert!((0..8).all(|i| graph.is_adjacent(cycle[i], cycle[(i + 1) % 8])));
+				}
+				None => assert!(!has_cycle),
+			}
+
+			if let Some(path) = hamiltonian_path(&graph) {
+				let mut sorted = path.clone();
+				sorted.sort_unstable();
+				assert_eq!(sorted, v);
+				assert!(path.windows(2).all(|step| graph.is_adjacent(step[0], step[1])));
+			}
+			if has_cycle {
+				assert!(hamiltonian_path(&graph).is_some());
+			}
+		}
+	}
+
+	#[test]
+	fn test_hamiltonian_special_cases() {
+		// The Petersen graph has a Hamiltonian path but no Hamiltonian cycle
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let v: Vec<usize> = (0..10).map(|_| graph.add_vertex(())).collect();
+		for i in 0..5 {
+			graph.add_edge(v[i], v[(i + 1) % 5]);
+			graph.add_edge(v[i], v[i + 5]);
+			graph.add_edge(v[i + 5], v[(i + 2) % 5 + 5]);
+		}
+		assert!(hamiltonian_path(&graph).is_some());
+		assert_eq!(hamiltonian_cycle(&graph), None);
+
+		// A star has neither
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..4).map(|_| graph.add_vertex(())).collect();
+		for i in 1..4 {
+			graph.add_edge(v[0], v[i]);
+		}
+		assert_eq!(hamiltonian_path(&graph), None);
+		assert_eq!(hamiltonian_cycle(&graph), None);
+
+		// An edge is a path, but not a cycle
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		assert_eq!(hamiltonian_path(&graph), Some(Vec::new()));
+		let a = graph.add_vertex(());
+		let b = graph.add_vertex(());
+		graph.add_edge(a, b);
+		graph.add_edge(a, a);
+		assert_eq!(hamiltonian_path(&graph).map(|p| p.len()), Some(2));
+		assert_eq!(hamiltonian_cycle(&graph), None);
+	}
+
+	#[test]
+	fn test_held_karp() {
+		for seed in 1..20 {
+			let mut graph = random_points(8, seed);
+
+			// Remove some edges, so that not every order is a tour
+			let v = graph.get_vertices();
+			for i in 0..8usize {
+				if seed % 3 == i as u64 % 3 {
+					graph.remove_edge(v[i], v[(i + 3) % 8]);
+				}
+			}
+
+			let tour = held_karp(&graph);
+			assert_eq!(tour.as_ref().map(|t| t.cost), brute_force(&graph));
+			if let Some(tour) = tour {
+				assert_eq!(tour.vertices[0], v[0]);
+				check_tour(&graph, &tour);
+			}
+		}
+
+		let mut graph: UndirectedSparseGraph<(), u64> = UndirectedSparseGraph::new();
+		let a = graph.add_vertex(());
+		let b = graph.add_vertex(());
+		graph.set_edge_weight(a, b, 1);
+		assert_eq!(held_karp(&graph), None);
+	}
+
+	#[test]
+	fn test_heuristics() {
+		for seed in 1..10 {
+			let graph = random_points(9, seed);
+			let optimal = held_karp(&graph).unwrap().cost;
+
+			let nearest = nearest_neighbor_tour(&graph).unwrap();
+			check_tour(&graph, &nearest);
+			assert!(nearest.cost >= optimal);
+
+			let christofides = christofides(&graph).unwrap();
+			check_tour(&graph, &christofides);
+			assert!(christofides.cost >= optimal);
+			assert!(2 * christofides.cost <= 3 * optimal + 9);
+
+			let improved = improve_tour(&graph, &nearest.vertices).unwrap();
+			check_tour(&graph, &improved);
+			assert!(improved.cost <= nearest.cost);
+			assert!(improved.cost >= optimal);
+			assert_eq!(improved.vertices[0], nearest.vertices[0]);
+		}
+	}
+
+	#[test]
+	fn test_heuristics_large() {
+		// The local search comes within a few percent of the Christofides bound on many points
+		let graph = random_points(60, 3);
+		let christofides = christofides(&graph).unwrap();
+		let improved = improve_tour(&graph, &nearest_neighbor_tour(&graph).unwrap().vertices).unwrap();
+		check_tour(&graph, &christofides);
+		check_tour(&graph, &improved);
+		assert!(improved.cost * 10 <= christofides.cost * 11);
+	}
+
+	#[test]
+	fn test_heuristics_sparse() {
+		// On a path, the tour goes back along the path, through the vertices it visited already
+		let mut graph: UndirectedSparseGraph<(), f64> = UndirectedSparseGraph::new();
+		let v: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+		for i in 0..4 {
+			graph.set_edge_weight(v[i], v[i + 1], 1.5);
+		}
+
+		for tour in [nearest_neighbor_tour(&graph), christofides(&graph)] {
+			let tour = tour.unwrap();
+			assert_eq!(tour.cost, 12.0);
+			assert_eq!(tour.vertices, v);
+		}
+		assert_eq!(improve_tour(&graph, &[v[0], v[2], v[4], v[1], v[3]]).unwrap().cost, 12.0);
+
+		// The matching fits in a small weight type along with the tour
+		let mut small: UndirectedSparseGraph<(), u8> = UndirectedSparseGraph::new();
+		let u: Vec<usize> = (0..5).map(|_| small.add_vertex(())).collect();
+		for i in 0..4 {
+			small.set_edge_weight(u[i], u[i + 1], 10);
+		}
+		assert_eq!(christofides(&small).unwrap().cost, 80);
+
+		// Invalid tours and graphs without tours
+		assert_eq!(improve_tour(&graph, &[v[0], v[1]]), None);
+		assert_eq!(improve_tour(&graph, &[v[0], v[0], v[1], v[2], v[3]]), None);
+		graph.add_vertex(());
+		assert_eq!(christofides(&graph), None);
+		assert_eq!(nearest_neighbor_tour(&UndirectedSparseGraph::<(), f64>::new()), None);
+	}
+}
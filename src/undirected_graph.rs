@@ -1,11 +1,13 @@
-use super::Graph;
+use super::{Graph, WeightedGraph};
 
 /// A graph data structure with undirected edges, implemented using an adjacency matrix.
 ///
 /// A `UndirectedGraph` consists of a set of vertices and a set of edges connecting those vertices. Vertices are
 /// identified by a unique `usize` identifier, and have some associated data of type `T`. Edges are
-/// represented using an adjacency matrix, which stores a boolean value indicating whether there is an edge between
-/// each pair of vertices.
+/// represented using an adjacency matrix, which stores for each pair of vertices the weight of the edge between
+/// them, if there is one. Weights are of type `W`, which defaults to `()` for unweighted graphs. Edges added
+/// through [`Graph::add_edge`] get the weight `W::default()`; use [`WeightedGraph::set_edge_weight`] to add an
+/// edge with a specific weight.
 ///
 /// # Example
 ///
@@ -29,14 +31,35 @@ use super::Graph;
 /// // Print out the graph
 /// println!("{:?}", graph);
 /// ```
+///
+/// A weighted graph:
+///
+/// ```
+/// use istos::{Graph, UndirectedGraph, WeightedGraph};
+///
+/// let mut graph: UndirectedGraph<(), u32> = UndirectedGraph::new();
+///
+/// let v1 = graph.add_vertex(());
+/// let v2 = graph.add_vertex(());
+///
+/// graph.set_edge_weight(v1, v2, 5);
+///
+/// assert_eq!(graph.get_edge_weight(v2, v1), Some(5));
+/// ```
 #[derive(Clone, Debug)]
-pub struct UndirectedGraph<T: Clone> {
+pub struct UndirectedGraph<T: Clone, W: Clone = ()> {
 	vertices: Vec<(usize, T)>, // A vector of vertex IDs and associated data
-	edges: Vec<bool>, // An adjacency matrix representing the edges between vertices
+	edges: Vec<Option<W>>, // An adjacency matrix holding the weights of the edges between vertices
 	next_id: usize, // The ID to assign to the next added vertex
 }
 
-impl<T: Clone> UndirectedGraph<T> {
+impl<T: Clone, W: Clone> Default for UndirectedGraph<T, W> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Clone, W: Clone> UndirectedGraph<T, W> {
 	/// Create a blank UndirectedGraph.
 	pub fn new() -> Self {
 		Self {
@@ -65,15 +88,17 @@ impl<T: Clone> UndirectedGraph<T> {
 	}
 }
 
-impl<T: Clone> Graph<T> for UndirectedGraph<T> {
+impl<T: Clone, W: Clone + Default> Graph<T> for UndirectedGraph<T, W> {
 	fn add_vertex(&mut self, data: T) -> usize {
 		// Get the next available vertex ID
 		let id: usize = self.next_id;
 		
-		// Insert a new row and column in the adjacency matrix for the new vertex
+		// Insert a new row and column in the adjacency matrix for the new vertex. The new entry of
+		// each row goes at the end of that row; inserting in ascending order puts each entry at its
+		// final position
 		let size: usize = self.vertices.len();
 		for i in 0..=size {
-			self.edges.insert(size * i, false);
+			self.edges.insert((size * 2 - i + 1) * i / 2 + size, None);
 		}
 		
 		// Add the new vertex to the vertices vector with its associated data
@@ -108,8 +133,10 @@ impl<T: Clone> Graph<T> for UndirectedGraph<T> {
 		// Calculate the index of the corresponding edge in the `edges` vector
 		let index: usize = self.index_vector_with_coords(pos_1, pos_2);
 
-		// Set the value to true to indicate the presence of an edge
-		self.edges[index] = true;
+		// Store a default weight to indicate the presence of an edge, keeping the weight of an existing edge
+		if self.edges[index].is_none() {
+			self.edges[index] = Some(W::default());
+		}
 	}
 
 	fn remove_edge(&mut self, vertex_id_1: usize, vertex_id_2: usize) {
@@ -120,8 +147,8 @@ impl<T: Clone> Graph<T> for UndirectedGraph<T> {
 		// Calculate the index of the corresponding edge in the `edges` vector
 		let index: usize = self.index_vector_with_coords(pos_1, pos_2);
 
-		// Set the value to false to indicate the absence of an edge
-		self.edges[index] = false;
+		// Clear the value to indicate the absence of an edge
+		self.edges[index] = None;
 	}
 
 	fn get_vertex_data(&self, vertex_id: usize) -> Option<T> {
@@ -142,25 +169,55 @@ impl<T: Clone> Graph<T> for UndirectedGraph<T> {
 		let index: usize = self.index_vector_with_coords(pos_1, pos_2);
 
 		// Find the value of the edge at the index
-		self.edges[index]
+		self.edges[index].is_some()
 	}
 
 	fn get_neighbors(&self, vertex_id: usize) -> Vec<usize> {
 		let mut res = Vec::new();
 
-		// Iterate through all vertices to find neighbors of the given vertex
+		// Get the position of the vertex in the vertex list
+		let Some(pos) = self.get_index_from_id(vertex_id) else { return res; };
+
+		// Read the row of the vertex in the adjacency matrix, looking the other vertices up by
+		// position rather than by ID
 		for i in 0..self.vertices.len() {
-			let other_id = self.vertices[i].0;
-			if self.is_adjacent(vertex_id, other_id) {
-				res.push(other_id);
+			if self.edges[self.index_vector_with_coords(pos, i)].is_some() {
+				res.push(self.vertices[i].0);
 			}
 		}
 		
 		res
 	}
+
+	fn get_vertices(&self) -> Vec<usize> {
+		self.vertices.iter().map(|x| x.0).collect()
+	}
+}
+
+impl<T: Clone, W: Clone + Default> WeightedGraph<T, W> for UndirectedGraph<T, W> {
+	fn get_edge_weight(&self, vertex_id_1: usize, vertex_id_2: usize) -> Option<W> {
+		// Get the positions of the vertices in the vertex list
+		let pos_1 = self.get_index_from_id(vertex_id_1)?;
+		let pos_2 = self.get_index_from_id(vertex_id_2)?;
+
+		self.edges[self.index_vector_with_coords(pos_1, pos_2)].clone()
+	}
+
+	fn set_edge_weight(&mut self, vertex_id_1: usize, vertex_id_2: usize, weight: W) {
+		// Get the positions of the vertices in the vertex list
+		let Some(pos_1) = self.get_index_from_id(vertex_id_1) else { return; };
+		let Some(pos_2) = self.get_index_from_id(vertex_id_2) else { return; };
+
+		// Calculate the index of the corresponding edge in the `edges` vector
+		let index: usize = self.index_vector_with_coords(pos_1, pos_2);
+
+		// Store the weight, creating the edge if necessary
+		self.edges[index] = Some(weight);
+	}
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
 	use super::*;
 
@@ -303,4 +360,82 @@ mod tests {
 		assert_eq!(graph.get_neighbors(v2), vec![v1, v3]);
 		assert_eq!(graph.get_neighbors(v3), vec![v2]);
 	}
+
+	#[test]
+	fn test_get_neighbors_after_removal() {
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let v1 = graph.add_vertex(());
+		let v2 = graph.add_vertex(());
+		let v3 = graph.add_vertex(());
+		let v4 = graph.add_vertex(());
+
+		graph.add_edge(v1, v3);
+		graph.add_edge(v2, v3);
+		graph.add_edge(v3, v4);
+		graph.add_edge(v4, v4);
+		graph.remove_vertex(v2);
+
+		assert_eq!(graph.get_neighbors(v1), vec![v3]);
+		assert_eq!(graph.get_neighbors(v3), vec![v1, v4]);
+		assert_eq!(graph.get_neighbors(v4), vec![v3, v4]);
+		assert_eq!(graph.get_neighbors(v2), vec![]);
+		assert_eq!(graph.get_neighbors(100), vec![]);
+	}
+
+	#[test]
+	fn test_add_vertex_keeps_edges() {
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let v1 = graph.add_vertex(());
+		let v2 = graph.add_vertex(());
+		let v3 = graph.add_vertex(());
+
+		graph.add_edge(v1, v3);
+		graph.add_edge(v2, v2);
+
+		let others: Vec<usize> = (0..5).map(|_| graph.add_vertex(())).collect();
+
+		assert_eq!(graph.get_neighbors(v1), vec![v3]);
+		assert_eq!(graph.get_neighbors(v2), vec![v2]);
+		assert_eq!(graph.get_neighbors(v3), vec![v1]);
+		for &v in &others {
+			assert_eq!(graph.get_neighbors(v), vec![]);
+		}
+	}
+
+	#[test]
+	fn test_get_vertices() {
+		let mut graph: UndirectedGraph<()> = UndirectedGraph::new();
+		let v1 = graph.add_vertex(());
+		let v2 = graph.add_vertex(());
+		let v3 = graph.add_vertex(());
+
+		assert_eq!(graph.get_vertices(), vec![v1, v2, v3]);
+
+		graph.remove_vertex(v2);
+
+		assert_eq!(graph.get_vertices(), vec![v1, v3]);
+	}
+
+	#[test]
+	fn test_edge_weight() {
+		let mut graph: UndirectedGraph<(), i32> = UndirectedGraph::new();
+		let v1 = graph.add_vertex(());
+		let v2 = graph.add_vertex(());
+		let v3 = graph.add_vertex(());
+
+		graph.add_edge(v1, v2);
+		graph.set_edge_weight(v2, v3, 7);
+
+		assert_eq!(graph.get_edge_weight(v1, v2), Some(0));
+		assert_eq!(graph.get_edge_weight(v3, v2), Some(7));
+		assert_eq!(graph.get_edge_weight(v1, v3), None);
+		assert!(graph.is_adjacent(v2, v3));
+
+		// Adding an existing edge keeps its weight
+		graph.add_edge(v2, v3);
+		assert_eq!(graph.get_edge_weight(v2, v3), Some(7));
+
+		graph.remove_edge(v2, v3);
+		assert_eq!(graph.get_edge_weight(v2, v3), None);
+	}
 }
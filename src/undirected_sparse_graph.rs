@@ -1,9 +1,16 @@
-use super::Graph;
+use super::{Graph, WeightedGraph};
 
 /// The UndirectedSparseGraph struct represents an undirected sparse graph implemented
 /// using a variant of an adjacency list. The graph consists of a set of vertices, each of which
 /// has a unique usize identifier and some associated data of type T. The edges of the
-/// graph are represented using a vector of pairs of vertex identifiers.
+/// graph are represented using a vector of pairs of vertex identifiers, each carrying a weight of
+/// type W. The weight type defaults to `()` for unweighted graphs. Edges added through
+/// [`Graph::add_edge`] get the weight `W::default()`; use [`WeightedGraph::set_edge_weight`] to
+/// add an edge with a specific weight.
+///
+/// Adding an edge between two vertices that are already adjacent adds another edge, but
+/// [`Graph::get_neighbors`] lists every neighbor once, so the algorithms of this crate see the
+/// graph without parallel edges.
 ///
 /// # Example
 ///
@@ -27,14 +34,35 @@ use super::Graph;
 /// // Print out the graph
 /// println!("{:?}", graph);
 /// ```
+///
+/// A weighted graph:
+///
+/// ```
+/// use istos::{Graph, UndirectedSparseGraph, WeightedGraph};
+///
+/// let mut graph: UndirectedSparseGraph<(), f64> = UndirectedSparseGraph::new();
+///
+/// let v1 = graph.add_vertex(());
+/// let v2 = graph.add_vertex(());
+///
+/// graph.set_edge_weight(v1, v2, 2.5);
+///
+/// assert_eq!(graph.get_edge_weight(v2, v1), Some(2.5));
+/// ```
 #[derive(Clone, Debug)]
-pub struct UndirectedSparseGraph<T: Clone> {
+pub struct UndirectedSparseGraph<T: Clone, W: Clone = ()> {
 	vertices: Vec<(usize, T)>, // A vector of vertex IDs and associated data
-	edges: Vec<(usize, usize)>, // An list of the edges between vertices
+	edges: Vec<(usize, usize, W)>, // An list of the edges between vertices and their weights
 	next_id: usize, // The ID to assign to the next added vertex
 }
 
-impl<T: Clone> UndirectedSparseGraph<T> {
+impl<T: Clone, W: Clone> Default for UndirectedSparseGraph<T, W> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<T: Clone, W: Clone> UndirectedSparseGraph<T, W> {
 	/// Create a blank UndirectedSparseGraph.
 	pub fn new() -> Self {
 		Self {
@@ -43,9 +71,29 @@ impl<T: Clone> UndirectedSparseGraph<T> {
 			next_id: 0,
 		}
 	}
+
+	/// Create an UndirectedSparseGraph from existing vertices and edges, keeping the vertex IDs.
+	/// Used by algorithms that build a new graph out of (parts of) another graph.
+	pub(crate) fn from_parts(mut vertices: Vec<(usize, T)>, edges: Vec<(usize, usize, W)>) -> Self {
+		// The vertices must stay sorted by ID, like they are when the IDs are handed out in order
+		vertices.sort_by_key(|x| x.0);
+		let next_id = vertices.last().map_or(0, |x| x.0 + 1);
+
+		Self {
+			vertices,
+			edges,
+			next_id,
+		}
+	}
+
+	/// Utility function to find the position of the edge between two vertices in the edge list.
+	#[inline]
+	fn get_edge_position(&self, vertex_id_1: usize, vertex_id_2: usize) -> Option<usize> {
+		self.edges.iter().position(|x| (x.0, x.1) == (vertex_id_1, vertex_id_2) || (x.0, x.1) == (vertex_id_2, vertex_id_1))
+	}
 }
 
-impl<T: Clone> Graph<T> for UndirectedSparseGraph<T> {
+impl<T: Clone, W: Clone + Default> Graph<T> for UndirectedSparseGraph<T, W> {
 	fn add_vertex(&mut self, data: T) -> usize {
 		// Get the next available vertex ID
 		let id: usize = self.next_id;
@@ -62,15 +110,15 @@ impl<T: Clone> Graph<T> for UndirectedSparseGraph<T> {
 
 	fn remove_vertex(&mut self, vertex_id: usize) {
 		self.vertices.retain(|x| x.0 != vertex_id);
-		self.edges.retain(|&x| x.0 != vertex_id && x.1 != vertex_id);
+		self.edges.retain(|x| x.0 != vertex_id && x.1 != vertex_id);
 	}
 
 	fn add_edge(&mut self, vertex_id_1: usize, vertex_id_2: usize) {
-		self.edges.push((vertex_id_1, vertex_id_2));
+		self.edges.push((vertex_id_1, vertex_id_2, W::default()));
 	}
 
 	fn remove_edge(&mut self, vertex_id_1: usize, vertex_id_2: usize) {
-		self.edges.retain(|&x| x != (vertex_id_1, vertex_id_2) && x != (vertex_id_2, vertex_id_1));
+		self.edges.retain(|x| (x.0, x.1) != (vertex_id_1, vertex_id_2) && (x.0, x.1) != (vertex_id_2, vertex_id_1));
 	}
 
 	fn get_vertex_data(&self, vertex_id: usize) -> Option<T> {
@@ -82,25 +130,54 @@ impl<T: Clone> Graph<T> for UndirectedSparseGraph<T> {
 	}
 
 	fn is_adjacent(&self, vertex_id_1: usize, vertex_id_2: usize) -> bool {
-		self.edges.contains(&(vertex_id_1, vertex_id_2)) || self.edges.contains(&(vertex_id_2, vertex_id_1))
+		self.get_edge_position(vertex_id_1, vertex_id_2).is_some()
 	}
 
 	fn get_neighbors(&self, vertex_id: usize) -> Vec<usize> {
-		let mut res = Vec::new();
-		
-		// Iterate through all vertices to find neighbors of the given vertex
-		for i in 0..self.vertices.len() {
-			let other_id = self.vertices[i].0;
-			if self.is_adjacent(vertex_id, other_id) {
-				res.push(other_id);
-			}
-		}
-		
+		// Collect the other endpoint of every edge touching the given vertex
+		let mut res: Vec<usize> = self.edges.iter()
+			.filter_map(|x| {
+				if x.0 == vertex_id {
+					Some(x.1)
+				} else if x.1 == vertex_id {
+					Some(x.0)
+				} else {
+					None
+				}
+			})
+			.collect();
+
+		// List every neighbor once, however many edges lead to it, and only if it is a vertex of the
+		// graph. IDs are handed out in increasing order, so sorting by ID puts the neighbors in the
+		// order of the vertices vector, which is also sorted by ID
+		res.sort_unstable();
+		res.dedup();
+		res.retain(|id| self.vertices.binary_search_by_key(id, |x| x.0).is_ok());
+
 		res
 	}
+
+	fn get_vertices(&self) -> Vec<usize> {
+		self.vertices.iter().map(|x| x.0).collect()
+	}
+}
+
+impl<T: Clone, W: Clone + Default> WeightedGraph<T, W> for UndirectedSparseGraph<T, W> {
+	fn get_edge_weight(&self, vertex_id_1: usize, vertex_id_2: usize) -> Option<W> {
+		Some(self.edges[self.get_edge_position(vertex_id_1, vertex_id_2)?].2.clone())
+	}
+
+	fn set_edge_weight(&mut self, vertex_id_1: usize, vertex_id_2: usize, weight: W) {
+		// Update the weight of an existing edge, or add a new edge with the weight
+		match self.get_edge_position(vertex_id_1, vertex_id_2) {
+			Some(pos) => self.edges[pos].2 = weight,
+			None => self.edges.push((vertex_id_1, vertex_id_2, weight)),
+		}
+	}
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
 	use super::*;
 
@@ -245,4 +322,67 @@ mod tests {
 		assert_eq!(graph.get_neighbors(v2), vec![v1, v3]);
 		assert_eq!(graph.get_neighbors(v3), vec![v2]);
 	}
+
+	#[test]
+	fn test_get_neighbors_multigraph() {
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v1 = graph.add_vertex(());
+		let v2 = graph.add_vertex(());
+		let v3 = graph.add_vertex(());
+
+		// Parallel edges and self loops list the neighbor once, in the order of the vertices
+		graph.add_edge(v3, v1);
+		graph.add_edge(v1, v3);
+		graph.add_edge(v1, v1);
+		graph.add_edge(v1, v1);
+		graph.add_edge(v2, v1);
+
+		assert_eq!(graph.edges.len(), 5);
+		assert_eq!(graph.get_neighbors(v1), vec![v1, v2, v3]);
+		assert_eq!(graph.get_neighbors(v3), vec![v1]);
+
+		// Edges to IDs that are not vertices are left out
+		graph.add_edge(v2, 100);
+
+		assert_eq!(graph.get_neighbors(v2), vec![v1]);
+	}
+
+	#[test]
+	fn test_get_vertices() {
+		let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+		let v1 = graph.add_vertex(());
+		let v2 = graph.add_vertex(());
+		let v3 = graph.add_vertex(());
+
+		assert_eq!(graph.get_vertices(), vec![v1, v2, v3]);
+
+		graph.remove_vertex(v2);
+
+		assert_eq!(graph.get_vertices(), vec![v1, v3]);
+	}
+
+	#[test]
+	fn test_edge_weight() {
+		let mut graph: UndirectedSparseGraph<(), i32> = UndirectedSparseGraph::new();
+		let v1 = graph.add_vertex(());
+		let v2 = graph.add_vertex(());
+		let v3 = graph.add_vertex(());
+
+		graph.add_edge(v1, v2);
+		graph.set_edge_weight(v2, v3, 7);
+
+		assert_eq!(graph.edges.len(), 2);
+		assert_eq!(graph.get_edge_weight(v1, v2), Some(0));
+		assert_eq!(graph.get_edge_weight(v3, v2), Some(7));
+		assert_eq!(graph.get_edge_weight(v1, v3), None);
+		assert!(graph.is_adjacent(v2, v3));
+
+		// Setting the weight of an existing edge does not add another edge
+		graph.set_edge_weight(v3, v2, 9);
+		assert_eq!(graph.edges.len(), 2);
+		assert_eq!(graph.get_edge_weight(v2, v3), Some(9));
+
+		graph.remove_edge(v2, v3);
+		assert_eq!(graph.get_edge_weight(v2, v3), None);
+	}
 }
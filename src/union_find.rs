@@ -0,0 +1,214 @@
+/// A disjoint-set data structure, also known as union-find.
+///
+/// A `UnionFind` keeps track of a partition of the elements `0..len()` into disjoint sets. Sets are
+/// merged with [`union`](Self::union), and [`find`](Self::find) returns a representative element
+/// of the set containing a given element. Both operations use path compression and union by rank,
+/// which makes them run in nearly constant amortized time.
+///
+/// Since vertex IDs are handed out sequentially, a `UnionFind` can track the connectivity of a
+/// graph incrementally as edges are added, by using the vertex IDs as elements.
+///
+/// # Example
+///
+/// ```
+/// use istos::{Graph, UndirectedSparseGraph, UnionFind};
+///
+/// let mut graph: UndirectedSparseGraph<()> = UndirectedSparseGraph::new();
+/// let mut sets = UnionFind::new(0);
+///
+/// let v1 = graph.add_vertex(());
+/// let v2 = graph.add_vertex(());
+/// let v3 = graph.add_vertex(());
+/// sets.extend(3);
+///
+/// graph.add_edge(v1, v2);
+/// sets.union(v1, v2);
+///
+/// assert!(sets.connected(v1, v2));
+/// assert!(!sets.connected(v1, v3));
+/// assert_eq!(sets.set_count(), 2);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct UnionFind {
+	parents: Vec<usize>, // The parent of each element, roots are their own parent
+	ranks: Vec<u8>, // An upper bound on the height of the tree below each root
+	set_count: usize, // The number of disjoint sets
+}
+
+impl UnionFind {
+	/// Create a UnionFind with `len` elements, each in its own set.
+	pub fn new(len: usize) -> Self {
+		Self {
+			parents: (0..len).collect(),
+			ranks: vec![0; len],
+			set_count: len,
+		}
+	}
+
+	/// Gets the number of elements.
+	pub fn len(&self) -> usize {
+		self.parents.len()
+	}
+
+	/// Returns `true` if there are no elements.
+	pub fn is_empty(&self) -> bool {
+		self.parents.is_empty()
+	}
+
+	/// Gets the number of disjoint sets.
+	pub fn set_count(&self) -> usize {
+		self.set_count
+	}
+
+	/// Adds a new element in its own set.
+	/// Returns the new element.
+	pub fn add(&mut self) -> usize {
+		let element = self.parents.len();
+		self.parents.push(element);
+		self.ranks.push(0);
+		self.set_count += 1;
+		element
+	}
+
+	/// Adds new elements, each in its own set, until there are at least `len` elements.
+	///
+	/// # Arguments
+	///
+	/// - `len`: The number of elements to grow to.
+	pub fn extend(&mut self, len: usize) {
+		while self.parents.len() < len {
+			self.add();
+		}
+	}
+
+	/// Finds the representative of the set containing an element.
+	///
+	/// # Arguments
+	///
+	/// - `element`: The element to find the set of.
+	///
+	/// # Panics
+	///
+	/// Panics if `element` is out of bounds.
+	pub fn find(&mut self, element: usize) -> usize {
+		// Find the root
+		let mut root = element;
+		while self.parents[root] != root {
+			root = self.parents[root];
+		}
+
+		// Point everything on the path directly at the root
+		let mut current = element;
+		while self.parents[current] != root {
+			let next = self.parents[current];
+			self.parents[current] = root;
+			current = next;
+		}
+
+		root
+	}
+
+	/// Merges the sets containing two elements.
+	/// Returns `true` if the elements were in different sets.
+	///
+	/// # Arguments
+	///
+	/// - `element_1`: An element of the first set.
+	/// - `element_2`: An element of the second set.
+	///
+	/// # Panics
+	///
+	/// Panics if either element is out of bounds.
+	pub fn union(&mut self, element_1: usize, element_2: usize) -> bool {
+		let root_1 = self.find(element_1);
+		let root_2 = self.find(element_2);
+		if root_1 == root_2 {
+			return false;
+		}
+
+		// Attach the shorter tree below the taller one
+		if self.ranks[root_1] < self.ranks[root_2] {
+			self.parents[root_1] = root_2;
+		} else if self.ranks[root_1] > self.ranks[root_2] {
+			self.parents[root_2] = root_1;
+		} else {
+			self.parents[root_2] = root_1;
+			self.ranks[root_1] += 1;
+		}
+
+		self.set_count -= 1;
+		true
+	}
+
+	/// Determines whether two elements are in the same set.
+	///
+	/// # Arguments
+	///
+	/// - `element_1`: The first element to test.
+	/// - `element_2`: The second element to test.
+	///
+	/// # Panics
+	///
+	/// Panics if either element is out of bounds.
+	pub fn connected(&mut self, element_1: usize, element_2: usize) -> bool {
+		self.find(element_1) == self.find(element_2)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_new() {
+		let mut sets = UnionFind::new(3);
+
+		assert_eq!(sets.len(), 3);
+		assert_eq!(sets.set_count(), 3);
+		assert_eq!(sets.find(0), 0);
+		assert_eq!(sets.find(2), 2);
+		assert!(UnionFind::new(0).is_empty());
+	}
+
+	#[test]
+	fn test_union() {
+		let mut sets = UnionFind::new(5);
+
+		assert!(sets.union(0, 1));
+		assert!(sets.union(3, 4));
+		assert!(sets.union(1, 4));
+		assert!(!sets.union(0, 3));
+
+		assert_eq!(sets.set_count(), 2);
+		assert!(sets.connected(0, 3));
+		assert!(sets.connected(4, 1));
+		assert!(!sets.connected(2, 0));
+		assert_eq!(sets.find(0), sets.find(4));
+	}
+
+	#[test]
+	fn test_add() {
+		let mut sets = UnionFind::new(1);
+
+		assert_eq!(sets.add(), 1);
+		sets.extend(4);
+
+		assert_eq!(sets.len(), 4);
+		assert_eq!(sets.set_count(), 4);
+
+		sets.union(0, 3);
+		assert!(sets.connected(3, 0));
+		assert_eq!(sets.set_count(), 3);
+	}
+
+	#[test]
+	fn test_long_chain() {
+		let mut sets = UnionFind::new(10_000);
+		for i in 1..10_000 {
+			sets.union(i - 1, i);
+		}
+
+		assert_eq!(sets.set_count(), 1);
+		assert!(sets.connected(0, 9_999));
+	}
+}
@@ -0,0 +1,392 @@
+//! Internal helpers shared by the graph algorithms.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use super::{Graph, Weight, WeightedGraph};
+
+/// A snapshot of a graph's adjacency, with the vertices mapped to contiguous indices.
+///
+/// The algorithms work on indices internally and map their results back to vertex IDs, so that
+/// they don't have to go through the (potentially slow) `Graph` methods in their inner loops.
+#[derive(Clone, Debug)]
+pub(crate) struct Indexed {
+	pub ids: Vec<usize>, // The vertex ID for each index
+	pub index: HashMap<usize, usize>, // The index for each vertex ID
+	pub adj: Vec<Vec<usize>>, // The indices of the neighbors of each vertex
+}
+
+impl Indexed {
+	/// Builds the index of a graph.
+	pub fn new<T: Clone, G: Graph<T>>(graph: &G) -> Self {
+		let ids = graph.get_vertices();
+		let index: HashMap<usize, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+		let adj = ids.iter()
+			.map(|&id| graph.get_neighbors(id).iter().filter_map(|n| index.get(n).copied()).collect())
+			.collect();
+
+		Self { ids, index, adj }
+	}
+
+	/// The number of vertices in the graph.
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.ids.len()
+	}
+
+	/// Looks up the weight of every edge in the index. The weight of the edge to `adj[u][k]` is
+	/// stored at `[u][k]`.
+	pub fn weights<T: Clone, W: Clone, G: WeightedGraph<T, W>>(&self, graph: &G) -> Vec<Vec<W>> {
+		self.adj.iter().enumerate()
+			.map(|(u, ns)| ns.iter()
+				.map(|&v| graph.get_edge_weight(self.ids[u], self.ids[v]).expect("neighbors are connected by an edge"))
+				.collect())
+			.collect()
+	}
+}
+
+/// A set of vertex indices stored as a bit vector, for fast intersections on dense graphs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct BitSet {
+	words: Vec<u64>,
+}
+
+impl BitSet {
+	/// Creates an empty set for the indices `0..n`.
+	pub fn new(n: usize) -> Self {
+		Self { words: vec![0; n.div_ceil(64)] }
+	}
+
+	/// Creates a set from the indices in an iterator.
+	pub fn from_indices(n: usize, indices: impl IntoIterator<Item = usize>) -> Self {
+		let mut set = Self::new(n);
+		for i in indices {
+			set.insert(i);
+		}
+		set
+	}
+
+	#[inline]
+	pub fn insert(&mut self, i: usize) {
+		self.words[i / 64] |= 1 << (i % 64);
+	}
+
+	#[inline]
+	pub fn remove(&mut self, i: usize) {
+		self.words[i / 64] &= !(1 << (i % 64));
+	}
+
+	#[inline]
+	pub fn contains(&self, i: usize) -> bool {
+		self.words[i / 64] & (1 << (i % 64)) != 0
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.words.iter().all(|&w| w == 0)
+	}
+
+	pub fn len(&self) -> usize {
+		self.words.iter().map(|w| w.count_ones() as usize).sum()
+	}
+
+	/// The elements of either set.
+	pub fn union(&self, other: &Self) -> Self {
+		Self { words: self.words.iter().zip(&other.words).map(|(a, b)| a | b).collect() }
+	}
+
+	/// The elements of both sets.
+	pub fn intersection(&self, other: &Self) -> Self {
+		Self { words: self.words.iter().zip(&other.words).map(|(a, b)| a & b).collect() }
+	}
+
+	/// The number of elements of both sets, without building the intersection.
+	pub fn intersection_len(&self, other: &Self) -> usize {
+		self.words.iter().zip(&other.words).map(|(a, b)| (a & b).count_ones() as usize).sum()
+	}
+
+	/// The elements of this set that are not in the other.
+	pub fn difference(&self, other: &Self) -> Self {
+		Self { words: self.words.iter().zip(&other.words).map(|(a, b)| a & !b).collect() }
+	}
+
+	/// The smallest element.
+	pub fn first(&self) -> Option<usize> {
+		let k = self.words.iter().position(|&w| w != 0)?;
+		Some(k * 64 + self.words[k].trailing_zeros() as usize)
+	}
+
+	/// The elements in increasing order.
+	pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+		self.words.iter().enumerate().flat_map(|(k, &word)| {
+			let mut word = word;
+			std::iter::from_fn(move || {
+				if word == 0 {
+					return None;
+				}
+				let bit = word.trailing_zeros() as usize;
+				word &= word - 1;
+				Some(k * 64 + bit)
+			})
+		})
+	}
+}
+
+/// Builds the adjacency matrix of an indexed graph as bit sets, without self loops.
+pub(crate) fn adjacency_matrix(index: &Indexed) -> Vec<BitSet> {
+	let n = index.len();
+	index.adj.iter().enumerate()
+		.map(|(u, neighbors)| BitSet::from_indices(n, neighbors.iter().copied().filter(|&v| v != u)))
+		.collect()
+}
+
+/// Computes the core number of every vertex of an undirected graph with the Batagelj–Zaversnik
+/// algorithm, in `O(V + E)` time: repeatedly removes a vertex of minimum degree among the remaining
+/// vertices, using buckets of vertices by degree. Self loops are ignored.
+///
+/// Returns the vertices in the order they were removed, and the core number of every vertex.
+pub(crate) fn core_decomposition(adj: &[Vec<usize>]) -> (Vec<usize>, Vec<usize>) {
+	let n = adj.len();
+	let mut degree: Vec<usize> = adj.iter().enumerate()
+		.map(|(u, neighbors)| neighbors.iter().filter(|&&v| v != u).count())
+		.collect();
+
+	// Sort the vertices by degree, where the bucket of degree `d` starts at `start[d]`
+	let max_degree = degree.iter().copied().max().unwrap_or(0);
+	let mut start = vec![0; max_degree + 2];
+	for &d in &degree {
+		start[d + 1] += 1;
+	}
+	for d in 1..start.len() {
+		start[d] += start[d - 1];
+	}
+	let mut order = vec![0; n];
+	let mut position = vec![0; n];
+	let mut next = start.clone();
+	for u in 0..n {
+		position[u] = next[degree[u]];
+		order[position[u]] = u;
+		next[degree[u]] += 1;
+	}
+
+	for i in 0..n {
+		let u = order[i];
+		for &v in &adj[u] {
+			if degree[v] <= degree[u] {
+				continue;
+			}
+
+			// Move the neighbor to the front of its bucket, and the bucket boundary past it
+			let d = degree[v];
+			let first = order[start[d]];
+			order.swap(position[v], start[d]);
+			position.swap(v, first);
+			start[d] += 1;
+			degree[v] -= 1;
+		}
+	}
+
+	(order, degree)
+}
+
+/// Orders the vertices of an undirected graph by repeatedly removing one of minimum degree among
+/// the remaining vertices. Every vertex has at most `d` neighbors later in the order, where `d` is
+/// the degeneracy of the graph. Self loops are ignored.
+pub(crate) fn degeneracy_order(adj: &[Vec<usize>]) -> Vec<usize> {
+	core_decomposition(adj).0
+}
+
+/// An entry of a min-heap ordered by a partially ordered score.
+///
+/// `BinaryHeap` is a max-heap, so the ordering is reversed. Incomparable scores are treated as equal.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct MinScored<K, V>(pub K, pub V);
+
+impl<K: PartialOrd, V> PartialEq for MinScored<K, V> {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other) == Ordering::Equal
+	}
+}
+
+impl<K: PartialOrd, V> Eq for MinScored<K, V> {}
+
+impl<K: PartialOrd, V> PartialOrd for MinScored<K, V> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<K: PartialOrd, V> Ord for MinScored<K, V> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		other.0.partial_cmp(&self.0).unwrap_or(Ordering::Equal)
+	}
+}
+
+/// A small pseudorandom number generator (splitmix64), so that the randomized algorithms are
+/// reproducible from a seed without depending on an external crate.
+#[derive(Clone, Debug)]
+pub(crate) struct Rng {
+	state: u64,
+}
+
+impl Rng {
+	/// Creates a generator from a seed. Every seed, including zero, gives a different sequence.
+	pub fn new(seed: u64) -> Self {
+		Self { state: seed }
+	}
+
+	/// Returns the next 64 random bits.
+	pub fn next_u64(&mut self) -> u64 {
+		self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		z ^ (z >> 31)
+	}
+
+	/// Returns a random number in `0..n`. `n` must not be zero.
+	pub fn below(&mut self, n: usize) -> usize {
+		((self.next_u64() as u128 * n as u128) >> 64) as usize
+	}
+
+	/// Returns a random number in `[0, 1)`.
+	pub fn next_f64(&mut self) -> f64 {
+		(self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+	}
+
+	/// Shuffles a slice with the Fisher–Yates shuffle.
+	pub fn shuffle<T>(&mut self, items: &mut [T]) {
+		for i in (1..items.len()).rev() {
+			items.swap(i, self.below(i + 1));
+		}
+	}
+}
+
+/// Finds the shortest paths from a vertex with Dijkstra's algorithm, as the distance and the parent
+/// of every vertex.
+pub(crate) fn dijkstra<W: Weight>(adj: &[Vec<usize>], weights: &[Vec<W>], source: usize) -> (Vec<Option<W>>, Vec<Option<usize>>) {
+	let n = adj.len();
+	let mut dist: Vec<Option<W>> = vec![None; n];
+	let mut parent: Vec<Option<usize>> = vec![None; n];
+	let mut settled = vec![false; n];
+	let mut heap = BinaryHeap::new();
+
+	dist[source] = Some(W::zero());
+	heap.push(MinScored(W::zero(), source));
+
+	while let Some(MinScored(d, u)) = heap.pop() {
+		if settled[u] {
+			continue;
+		}
+		settled[u] = true;
+
+		for (&v, &w) in adj[u].iter().zip(&weights[u]) {
+			let candidate = d + w;
+			if !settled[v] && dist[v].is_none_or(|dv| candidate < dv) {
+				dist[v] = Some(candidate);
+				parent[v] = Some(u);
+				heap.push(MinScored(candidate, v));
+			}
+		}
+	}
+
+	(dist, parent)
+}
+
+/// A minimal directed graph for testing the algorithms that interpret
+/// [`Graph::get_neighbors`] as the successors of a vertex.
+#[cfg(test)]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TestDigraph {
+	vertices: Vec<usize>,
+	edges: Vec<(usize, usize)>,
+	next_id: usize,
+}
+
+#[cfg(test)]
+impl TestDigraph {
+	/// Builds a graph with `n` vertices and the given directed edges.
+	pub fn with_edges(n: usize, edges: &[(usize, usize)]) -> Self {
+		let mut graph = Self::default();
+		for _ in 0..n {
+			graph.add_vertex(());
+		}
+		for &(from, to) in edges {
+			graph.add_edge(from, to);
+		}
+		graph
+	}
+}
+
+#[cfg(test)]
+impl Graph<()> for TestDigraph {
+	fn add_vertex(&mut self, _data: ()) -> usize {
+		self.vertices.push(self.next_id);
+		self.next_id += 1;
+		self.next_id - 1
+	}
+
+	fn remove_vertex(&mut self, vertex_id: usize) {
+		self.vertices.retain(|&x| x != vertex_id);
+		self.edges.retain(|&x| x.0 != vertex_id && x.1 != vertex_id);
+	}
+
+	fn add_edge(&mut self, vertex_id_1: usize, vertex_id_2: usize) {
+		self.edges.push((vertex_id_1, vertex_id_2));
+	}
+
+	fn remove_edge(&mut self, vertex_id_1: usize, vertex_id_2: usize) {
+		self.edges.retain(|&x| x != (vertex_id_1, vertex_id_2));
+	}
+
+	fn get_vertex_data(&self, vertex_id: usize) -> Option<()> {
+		self.vertices.contains(&vertex_id).then_some(())
+	}
+
+	fn set_vertex_data(&mut self, _vertex_id: usize, _data: ()) {}
+
+	fn is_adjacent(&self, vertex_id_1: usize, vertex_id_2: usize) -> bool {
+		self.edges.contains(&(vertex_id_1, vertex_id_2))
+	}
+
+	fn get_neighbors(&self, vertex_id: usize) -> Vec<usize> {
+		self.edges.iter().filter(|x| x.0 == vertex_id).map(|x| x.1).collect()
+	}
+
+	fn get_vertices(&self) -> Vec<usize> {
+		self.vertices.clone()
+	}
+}
+
+/// Generates a random graph on `n` vertices, holding the data `0..n`, where each pair of vertices
+/// is adjacent with a chance of `percent` in a hundred.
+#[cfg(test)]
+pub(crate) fn random_graph(n: usize, percent: u64, seed: u64) -> crate::UndirectedSparseGraph<usize> {
+	let mut rng = Rng::new(seed);
+	let mut graph = crate::UndirectedSparseGraph::new();
+	let v: Vec<usize> = (0..n).map(|i| graph.add_vertex(i)).collect();
+	for a in 0..n {
+		for b in a + 1..n {
+			if rng.next_u64() % 100 < percent {
+				graph.add_edge(v[a], v[b]);
+			}
+		}
+	}
+	graph
+}
+
+/// Generates a random graph like [`random_graph`], with edge weights drawn from
+/// `1..=max_weight`.
+#[cfg(test)]
+pub(crate) fn random_weighted_graph(n: usize, percent: u64, max_weight: u32, seed: u64) -> crate::UndirectedSparseGraph<usize, u32> {
+	let mut rng = Rng::new(seed);
+	let mut graph = crate::UndirectedSparseGraph::new();
+	let v: Vec<usize> = (0..n).map(|i| graph.add_vertex(i)).collect();
+	for a in 0..n {
+		for b in a + 1..n {
+			if rng.next_u64() % 100 < percent {
+				graph.set_edge_weight(v[a], v[b], rng.below(max_weight as usize) as u32 + 1);
+			}
+		}
+	}
+	graph
+}
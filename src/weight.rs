@@ -0,0 +1,65 @@
+//! Numeric edge weights.
+
+use std::fmt::Debug;
+use std::ops::{Add, Div, Mul, Sub};
+
+/// A trait for the numeric weights used by the algorithms in this crate.
+///
+/// This trait is implemented for all primitive integer and floating point types. Weights only need
+/// to be partially ordered, but algorithms assume that they never compare as unordered (so floating
+/// point weights must not be `NaN`).
+pub trait Weight: Copy + Debug + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> {
+	/// Returns the additive identity of the weight type.
+	fn zero() -> Self;
+
+	/// Returns the multiplicative identity of the weight type.
+	fn one() -> Self;
+
+	/// Converts the weight to a floating point number, rounding it if it can't be represented
+	/// exactly.
+	fn to_f64(self) -> f64;
+
+	/// Adds two weights, returning `None` if the sum overflows the weight type. Floating point sums
+	/// never overflow, since they become infinite instead.
+	fn checked_add(self, other: Self) -> Option<Self> {
+		Some(self + other)
+	}
+}
+
+macro_rules! impl_weight {
+	(@checked_add integer, $a:expr, $b:expr) => {
+		// Inherent methods take precedence over the trait method of the same name
+		<Self>::checked_add($a, $b)
+	};
+	(@checked_add float, $a:expr, $b:expr) => {
+		Some($a + $b)
+	};
+	($kind:ident, $zero:expr, $one:expr => $($t:ty),*) => {
+		$(
+			impl Weight for $t {
+				#[inline]
+				fn zero() -> Self {
+					$zero
+				}
+
+				#[inline]
+				fn one() -> Self {
+					$one
+				}
+
+				#[inline]
+				fn to_f64(self) -> f64 {
+					self as f64
+				}
+
+				#[inline]
+				fn checked_add(self, other: Self) -> Option<Self> {
+					impl_weight!(@checked_add $kind, self, other)
+				}
+			}
+		)*
+	};
+}
+
+impl_weight!(integer, 0, 1 => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_weight!(float, 0.0, 1.0 => f32, f64);